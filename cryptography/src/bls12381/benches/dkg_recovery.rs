@@ -1,4 +1,8 @@
-use commonware_cryptography::{bls12381::dkg, ed25519::insecure_signer, Scheme};
+use commonware_cryptography::{
+    bls12381::{dkg, primitives::group::MinPk},
+    ed25519::insecure_signer,
+    Scheme,
+};
 use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
 use std::collections::HashMap;
 use std::hint::black_box;
@@ -21,7 +25,7 @@ fn benchmark_dkg_recovery(c: &mut Criterion) {
                     let mut commitments = HashMap::new();
                     for i in 0..n {
                         let me = contributors[i as usize].clone();
-                        let p0 = dkg::contributor::P0::new(
+                        let p0 = dkg::contributor::P0::<MinPk>::new(
                             me,
                             t,
                             None,
@@ -55,7 +59,7 @@ fn benchmark_dkg_recovery(c: &mut Criterion) {
                     for i in 0..n {
                         // Get recipient share
                         let (_, shares) = commitments.get(&i).unwrap();
-                        let share = shares[0];
+                        let share = shares[0].clone();
 
                         // Send share to contributor
                         let dealer = contributors[i as usize].clone();
@@ -1,5 +1,8 @@
 use commonware_cryptography::{
-    bls12381::{dkg, primitives::poly},
+    bls12381::{
+        dkg,
+        primitives::{group::MinPk, poly},
+    },
     ed25519::insecure_signer,
     Scheme,
 };
@@ -26,7 +29,7 @@ fn benchmark_reshare_recovery(c: &mut Criterion) {
         let mut commitments = Vec::new();
         for i in 0..n {
             let me = contributors[i as usize].clone();
-            let p0 = dkg::contributor::P0::new(
+            let p0 = dkg::contributor::P0::<MinPk>::new(
                 me,
                 t,
                 None,
@@ -68,7 +71,7 @@ fn benchmark_reshare_recovery(c: &mut Criterion) {
             for j in 0..n {
                 // Get recipient share
                 let (shares, _) = contributor_shares.get(&i).unwrap();
-                let share = shares[j as usize];
+                let share = shares[j as usize].clone();
 
                 // Send share to recipient
                 let (_, recipient) = contributor_shares.get_mut(&j).unwrap();
@@ -108,8 +111,8 @@ fn benchmark_reshare_recovery(c: &mut Criterion) {
                         let mut commitments = HashMap::new();
                         for i in 0..n {
                             let me = contributors[i as usize].clone();
-                            let share = outputs[i as usize].share;
-                            let p0 = dkg::contributor::P0::new(
+                            let share = outputs[i as usize].share().unwrap().clone();
+                            let p0 = dkg::contributor::P0::<MinPk>::new(
                                 me,
                                 t,
                                 Some((group.clone(), share)),
@@ -143,7 +146,7 @@ fn benchmark_reshare_recovery(c: &mut Criterion) {
                         for i in 0..n {
                             // Get recipient share
                             let (_, shares) = commitments.get(&i).unwrap();
-                            let share = shares[0];
+                            let share = shares[0].clone();
 
                             // Send share to contributor
                             let dealer = contributors[i as usize].clone();
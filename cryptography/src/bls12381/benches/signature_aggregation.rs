@@ -1,4 +1,7 @@
-use commonware_cryptography::bls12381::{dkg, primitives};
+use commonware_cryptography::bls12381::{
+    dkg,
+    primitives::{self, group::MinPk, poly},
+};
 use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
 use std::hint::black_box;
 
@@ -9,14 +12,14 @@ fn benchmark_signature_aggregation(c: &mut Criterion) {
         c.bench_function(&format!("n={} t={}", n, t), |b| {
             b.iter_batched(
                 || {
-                    let (_, shares) = dkg::ops::generate_shares(None, n, t);
+                    let (_, shares) = dkg::ops::generate_shares::<MinPk>(None, n, t);
                     shares
                         .iter()
-                        .map(|s| primitives::ops::partial_sign(s, &msg[..]))
+                        .map(|s| primitives::ops::partial_sign::<MinPk>(s, &msg[..]))
                         .collect::<Vec<_>>()
                 },
                 |partials| {
-                    black_box(primitives::ops::aggregate(t, partials).unwrap());
+                    black_box(primitives::ops::aggregate::<MinPk>(t, partials).unwrap());
                 },
                 BatchSize::SmallInput,
             );
@@ -24,5 +27,35 @@ fn benchmark_signature_aggregation(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, benchmark_signature_aggregation);
+fn benchmark_signature_aggregation_precomputed(c: &mut Criterion) {
+    let msg = b"hello";
+    for &n in &[5, 10, 20, 50, 100, 250, 500] {
+        let t = dkg::utils::threshold(n).unwrap();
+        let weights = poly::Weights::new(&(0..t).collect::<Vec<_>>()).unwrap();
+        c.bench_function(&format!("precomputed n={} t={}", n, t), |b| {
+            b.iter_batched(
+                || {
+                    let (_, shares) = dkg::ops::generate_shares::<MinPk>(None, n, t);
+                    shares
+                        .iter()
+                        .map(|s| primitives::ops::partial_sign::<MinPk>(s, &msg[..]))
+                        .collect::<Vec<_>>()
+                },
+                |partials| {
+                    black_box(
+                        primitives::ops::aggregate_with_weights::<MinPk>(&weights, &partials)
+                            .unwrap(),
+                    );
+                },
+                BatchSize::SmallInput,
+            );
+        });
+    }
+}
+
+criterion_group!(
+    benches,
+    benchmark_signature_aggregation,
+    benchmark_signature_aggregation_precomputed
+);
 criterion_main!(benches);
@@ -32,24 +32,69 @@
 //! qualifies as an attributable fault that disqualifies a dealer/recipient from a round of DKG/Resharing. A developer
 //! can additionally handle such a fault as they see fit (may warrant additional punishment).
 //!
+//! # Proof of Possession
+//!
+//! If dealer identities are BLS12-381 public keys, the arbiter can be configured (via
+//! `P0::require_proof_of_possession`) to reject a dealer's commitment until it has presented a valid
+//! proof of possession for its identity key. Presenting an invalid proof is treated as an
+//! attributable fault.
+//!
+//! # Encrypted Shares
+//!
+//! The arbiter can be configured (via `P0::require_encrypted_shares`) to verify dealings of publicly
+//! verifiable encrypted shares (see [super::pvss]) instead of relying on acks, complaints, and reveals
+//! to determine whether a dealer distributed valid shares. Each recipient must register an encryption
+//! key in `P0` (or be disqualified) and a valid dealing posted in `P1` counts as an ack from each recipient.
+//! Posting an invalid dealing is treated as an attributable fault. Plaintext acks and complaints
+//! are rejected in this mode and any dealer that does not post a dealing in `P1` is disqualified.
+//!
+//! # Weighted Mode
+//!
+//! When constructed with `P0::new_weighted`, each recipient owns as many share indices as its
+//! weight (see [super::weighted]) and `threshold` is expressed in weight. A dealer must then be
+//! acked by recipients holding a combined weight of at least `threshold` (including its own weight,
+//! if it is also a recipient), requests for missing shares are made per share index, and a dealing
+//! of encrypted shares must contain one encrypted share per share index.
+//!
+//! # Refresh Mode
+//!
+//! When constructed with `P0::new_refresh`, the participants of a previous round re-randomize their
+//! shares without changing the group secret. Each dealer must commit to a polynomial with a zero constant
+//! term (posting any other commitment is treated as an attributable fault) and the recovered group
+//! polynomial is the sum of the previous group polynomial and all included commitments.
+//!
 //! # Warning
 //!
 //! It is up to the developer to authorize interaction with the arbiter. This is purposely
 //! not provided by the Arbiter because this authorization function is highly dependent on
 //! the context in which the contributor is being used.
 
-use super::utils;
+use super::{
+    codec::{self, Reader, Writer},
+    utils,
+};
 use crate::bls12381::{
-    dkg::{ops, Error},
-    primitives::{group::Share, poly},
+    dkg::{
+        ops,
+        pvss::{self, EncryptedShare, EncryptionKey},
+        weighted::{self, Allocation},
+        Error,
+    },
+    primitives::{
+        group::{Element, MinPk, Share, Variant},
+        ops::verify_proof_of_possession,
+        poly,
+    },
 };
 use crate::PublicKey;
+use rayon::{prelude::*, ThreadPoolBuilder};
 use std::collections::{HashMap, HashSet};
 
 /// Gather commitments from all contributors.
-pub struct P0 {
+pub struct P0<V: Variant = MinPk> {
     threshold: u32,
-    previous: Option<poly::Public>,
+    previous: Option<poly::Public<V>>,
+    refresh: Option<poly::Public<V>>,
     concurrency: usize,
 
     dealers: Vec<PublicKey>,
@@ -57,19 +102,90 @@ pub struct P0 {
 
     recipients: Vec<PublicKey>,
     recipients_ordered: HashMap<PublicKey, u32>,
+    allocation: Allocation,
+
+    require_proofs: bool,
+    proofs: HashSet<PublicKey>,
 
-    commitments: HashMap<PublicKey, poly::Public>,
+    require_encryption: bool,
+    keys: HashMap<u32, EncryptionKey>,
+
+    commitments: HashMap<PublicKey, poly::Public<V>>,
     disqualified: HashSet<PublicKey>,
 }
 
-impl P0 {
+impl<V: Variant> P0<V> {
     /// Create a new abiter for a DKG/Resharing procedure.
     pub fn new(
         threshold: u32,
-        previous: Option<poly::Public>,
-        mut dealers: Vec<PublicKey>,
+        previous: Option<poly::Public<V>>,
+        dealers: Vec<PublicKey>,
         mut recipients: Vec<PublicKey>,
         concurrency: usize,
+    ) -> Self {
+        recipients.sort();
+        let allocation = Allocation::uniform(recipients.len() as u32);
+        Self::init(
+            threshold,
+            previous,
+            dealers,
+            recipients,
+            allocation,
+            concurrency,
+        )
+    }
+
+    /// Create a new arbiter for a weighted DKG procedure, where each recipient owns
+    /// as many shares as its weight (and `threshold` is expressed in weight).
+    ///
+    /// If any weight is zero, this will panic.
+    pub fn new_weighted(
+        threshold: u32,
+        dealers: Vec<PublicKey>,
+        recipients: Vec<(PublicKey, u32)>,
+        concurrency: usize,
+    ) -> Self {
+        let (recipients, allocation) = weighted::sort(recipients);
+        Self::init(
+            threshold,
+            None,
+            dealers,
+            recipients,
+            allocation,
+            concurrency,
+        )
+    }
+
+    /// Create a new arbiter for a refresh procedure, where the participants of the round that
+    /// generated `previous` re-randomize their shares (without changing the group secret).
+    ///
+    /// `participants` must be the recipients of the round that generated `previous`.
+    pub fn new_refresh(
+        previous: poly::Public<V>,
+        mut participants: Vec<PublicKey>,
+        concurrency: usize,
+    ) -> Self {
+        participants.sort();
+        let allocation = Allocation::uniform(participants.len() as u32);
+        let mut arbiter = Self::init(
+            previous.required(),
+            None,
+            participants.clone(),
+            participants,
+            allocation,
+            concurrency,
+        );
+        arbiter.refresh = Some(previous);
+        arbiter
+    }
+
+    fn init(
+        threshold: u32,
+        previous: Option<poly::Public<V>>,
+        mut dealers: Vec<PublicKey>,
+        recipients: Vec<PublicKey>,
+        allocation: Allocation,
+        concurrency: usize,
     ) -> Self {
         dealers.sort();
         let dealers_ordered = dealers
@@ -77,26 +193,101 @@ impl P0 {
             .enumerate()
             .map(|(i, pk)| (pk.clone(), i as u32))
             .collect();
-        recipients.sort();
         let recipients_ordered = recipients
             .iter()
             .enumerate()
             .map(|(i, pk)| (pk.clone(), i as u32))
             .collect();
-        dealers.sort();
         Self {
             threshold,
             previous,
+            refresh: None,
             concurrency,
             dealers,
             dealers_ordered,
             recipients,
             recipients_ordered,
+            allocation,
+            require_proofs: false,
+            proofs: HashSet::new(),
+            require_encryption: false,
+            keys: HashMap::new(),
             commitments: HashMap::new(),
             disqualified: HashSet::new(),
         }
     }
 
+    /// Require every dealer to present a valid proof of possession for its identity key
+    /// (a serialized `V::Public`) before its commitment is accepted.
+    pub fn require_proof_of_possession(&mut self) {
+        self.require_proofs = true;
+    }
+
+    /// Verify and track a proof of possession for a dealer's identity key.
+    pub fn proof_of_possession(
+        &mut self,
+        dealer: PublicKey,
+        signature: V::Signature,
+    ) -> Result<(), Error> {
+        // Check if contributor is disqualified
+        if self.disqualified.contains(&dealer) {
+            return Err(Error::ContributorDisqualified);
+        }
+
+        // Ensure contributor is a dealer
+        if !self.dealers_ordered.contains_key(&dealer) {
+            return Err(Error::ContirbutorInvalid);
+        }
+
+        // Check if proof already exists
+        if self.proofs.contains(&dealer) {
+            return Err(Error::DuplicateProofOfPossession);
+        }
+
+        // Verify the proof is valid
+        let valid = match V::Public::deserialize(&dealer) {
+            Some(public) => verify_proof_of_possession::<V>(&public, &signature).is_ok(),
+            None => false,
+        };
+        if !valid {
+            self.disqualified.insert(dealer);
+            return Err(Error::InvalidProofOfPossession);
+        }
+        self.proofs.insert(dealer);
+        Ok(())
+    }
+
+    /// Require every dealer to post its shares encrypted to all recipients (instead of
+    /// distributing them privately) and every recipient to register an encryption key.
+    pub fn require_encrypted_shares(&mut self) {
+        self.require_encryption = true;
+    }
+
+    /// Track the key a recipient will receive encrypted shares under.
+    pub fn encryption_key(
+        &mut self,
+        recipient: PublicKey,
+        key: EncryptionKey,
+    ) -> Result<(), Error> {
+        // Check if contributor is disqualified
+        if self.disqualified.contains(&recipient) {
+            return Err(Error::ContributorDisqualified);
+        }
+
+        // Find the index of the recipient
+        let idx = match self.recipients_ordered.get(&recipient) {
+            Some(idx) => *idx,
+            None => return Err(Error::ContirbutorInvalid),
+        };
+
+        // Check if key already exists
+        if self.keys.contains_key(&idx) {
+            return Err(Error::DuplicateEncryptionKey);
+        }
+        self.keys.insert(idx, key);
+        Ok(())
+    }
+
     /// Required number of commitments to continue procedure.
     pub fn required(&self) -> u32 {
         match &self.previous {
@@ -111,7 +302,11 @@ impl P0 {
     }
 
     /// Verify and track a commitment from a dealer.
-    pub fn commitment(&mut self, dealer: PublicKey, commitment: poly::Public) -> Result<(), Error> {
+    pub fn commitment(
+        &mut self,
+        dealer: PublicKey,
+        commitment: poly::Public<V>,
+    ) -> Result<(), Error> {
         // Check if contributor is disqualified
         if self.disqualified.contains(&dealer) {
             return Err(Error::ContributorDisqualified);
@@ -128,8 +323,22 @@ impl P0 {
             return Err(Error::DuplicateCommitment);
         }
 
+        // Check if proof of possession is required
+        if self.require_proofs && !self.proofs.contains(&dealer) {
+            return Err(Error::MissingProofOfPossession);
+        }
+
         // Verify the commitment is valid
-        match ops::verify_commitment(self.previous.as_ref(), idx, &commitment, self.threshold) {
+        let result = match self.refresh {
+            Some(_) => ops::verify_refresh_commitment::<V>(&commitment, self.threshold),
+            None => ops::verify_commitment::<V>(
+                self.previous.as_ref(),
+                idx,
+                &commitment,
+                self.threshold,
+            ),
+        };
+        match result {
             Ok(()) => {
                 self.commitments.insert(dealer, commitment);
             }
@@ -141,8 +350,71 @@ impl P0 {
         Ok(())
     }
 
+    /// Serializes the state of the arbiter (to be resumed with `deserialize`).
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut writer = Writer::new(codec::ARBITER_P0);
+        writer.u32(self.threshold);
+        writer.option_poly(self.previous.as_ref());
+        writer.option_poly(self.refresh.as_ref());
+        writer.usize(self.concurrency);
+        writer.public_keys(&self.dealers);
+        writer.public_keys(&self.recipients);
+        writer.allocation(&self.allocation);
+        writer.bool(self.require_proofs);
+        writer.public_key_set(&self.proofs);
+        writer.bool(self.require_encryption);
+        writer.encryption_keys(&self.keys);
+        writer.commitments(&self.commitments);
+        writer.public_key_set(&self.disqualified);
+        writer.finish()
+    }
+
+    /// Deserializes the state of an arbiter.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(bytes, codec::ARBITER_P0)?;
+        let threshold = reader.u32()?;
+        let previous = reader.option_poly()?;
+        let refresh = reader.option_poly()?;
+        let concurrency = reader.usize()?;
+        let dealers = reader.public_keys()?;
+        let dealers_ordered = codec::ordered(&dealers);
+        let recipients = reader.public_keys()?;
+        let recipients_ordered = codec::ordered(&recipients);
+        let allocation = reader.allocation(recipients.len())?;
+        let require_proofs = reader.bool()?;
+        let proofs = reader.public_key_set()?;
+        if proofs
+            .iter()
+            .any(|dealer| !dealers_ordered.contains_key(dealer))
+        {
+            return None;
+        }
+        let require_encryption = reader.bool()?;
+        let keys = reader.encryption_keys(recipients.len())?;
+        let commitments = reader.commitments(&dealers_ordered)?;
+        let disqualified = reader.public_key_set()?;
+        reader.finish()?;
+        Some(Self {
+            threshold,
+            previous,
+            refresh,
+            concurrency,
+            dealers,
+            dealers_ordered,
+            recipients,
+            recipients_ordered,
+            allocation,
+            require_proofs,
+            proofs,
+            require_encryption,
+            keys,
+            commitments,
+            disqualified,
+        })
+    }
+
     /// If there exist at least `required()` commitments, proceed to `P1`.
-    pub fn finalize(mut self) -> (Option<P1>, HashSet<PublicKey>) {
+    pub fn finalize(mut self) -> (Option<P1<V>>, HashSet<PublicKey>) {
         // Disqualify any contributors who did not submit a commitment
         for contributor in self.dealers.iter() {
             if !self.commitments.contains_key(contributor) {
@@ -150,6 +422,15 @@ impl P0 {
             }
         }
 
+        // Disqualify any recipients who did not register an encryption key (if required)
+        if self.require_encryption {
+            for (idx, recipient) in self.recipients.iter().enumerate() {
+                if !self.keys.contains_key(&(idx as u32)) {
+                    self.disqualified.insert(recipient.clone());
+                }
+            }
+        }
+
         // Ensure we have enough commitments to proceed
         if self.commitments.len() < self.required() as usize {
             return (None, self.disqualified);
@@ -160,11 +441,16 @@ impl P0 {
             Some(P1 {
                 threshold: self.threshold,
                 previous: self.previous,
+                refresh: self.refresh,
                 concurrency: self.concurrency,
                 dealers: self.dealers,
                 dealers_ordered: self.dealers_ordered,
                 recipients: self.recipients,
                 recipients_ordered: self.recipients_ordered,
+                allocation: self.allocation,
+                require_encryption: self.require_encryption,
+                keys: self.keys,
+                dealings: HashSet::new(),
                 commitments: self.commitments,
                 disqualified: self.disqualified.clone(),
                 acks: HashMap::new(),
@@ -175,9 +461,10 @@ impl P0 {
 }
 
 /// Collect acknowledgements and complaints from all recipients.
-pub struct P1 {
+pub struct P1<V: Variant = MinPk> {
     threshold: u32,
-    previous: Option<poly::Public>,
+    previous: Option<poly::Public<V>>,
+    refresh: Option<poly::Public<V>>,
     concurrency: usize,
 
     dealers: Vec<PublicKey>,
@@ -185,21 +472,30 @@ pub struct P1 {
 
     recipients: Vec<PublicKey>,
     recipients_ordered: HashMap<PublicKey, u32>,
+    allocation: Allocation,
 
-    commitments: HashMap<PublicKey, poly::Public>,
+    require_encryption: bool,
+    keys: HashMap<u32, EncryptionKey>,
+    dealings: HashSet<u32>,
+
+    commitments: HashMap<PublicKey, poly::Public<V>>,
     disqualified: HashSet<PublicKey>,
 
     acks: HashMap<u32, HashSet<u32>>,
 }
 
 /// Alias for a commitment from a dealer.
-pub type Commitment = (u32, PublicKey, poly::Public);
+pub type Commitment<V = MinPk> = (u32, PublicKey, poly::Public<V>);
 
 /// Alias for a request for a missing share from a dealer
-/// for a recipient.
+/// (at the index of a share owned by a recipient).
 pub type Request = (u32, u32);
 
-impl P1 {
+/// Alias for `P2` and the requests for missing shares required
+/// to complete it.
+pub type Transition<V = MinPk> = (P2<V>, Vec<Request>);
+
+impl<V: Variant> P1<V> {
     /// Required number of commitments to continue procedure.
     pub fn required(&self) -> u32 {
         match &self.previous {
@@ -219,7 +515,7 @@ impl P1 {
     }
 
     /// Return all tracked commitments.
-    pub fn commitments(&self) -> Vec<Commitment> {
+    pub fn commitments(&self) -> Vec<Commitment<V>> {
         self.commitments
             .iter()
             .filter_map(|(contributor, commitment)| {
@@ -234,7 +530,15 @@ impl P1 {
     }
 
     /// Verify and track an acknowledgement from a recipient for a dealer.
+    ///
+    /// If encrypted shares are required, acks are rejected (a dealer is only acked by
+    /// posting a valid dealing).
     pub fn ack(&mut self, recipient: PublicKey, dealer: u32) -> Result<(), Error> {
+        // Ensure plaintext acks are expected
+        if self.require_encryption {
+            return Err(Error::UnexpectedAck);
+        }
+
         // Check if contributor is disqualified
         if self.disqualified.contains(&recipient) {
             return Err(Error::ContributorDisqualified);
@@ -277,16 +581,106 @@ impl P1 {
         }
     }
 
+    /// Verify and track a dealing of encrypted shares from a dealer.
+    ///
+    /// A dealing must contain exactly one encrypted share for each share index owned by a
+    /// recipient (other than the dealer) that registered an encryption key, ordered by share index. If the
+    /// dealing is valid, it is treated as an ack from each of these recipients. If it is
+    /// invalid, the dealer is disqualified.
+    pub fn dealing(&mut self, dealer: PublicKey, shares: &[EncryptedShare]) -> Result<(), Error> {
+        // Ensure encrypted shares are expected
+        if !self.require_encryption {
+            return Err(Error::UnexpectedDealing);
+        }
+
+        // Check if contributor is disqualified
+        if self.disqualified.contains(&dealer) {
+            return Err(Error::ContributorDisqualified);
+        }
+
+        // Find the index of the dealer
+        let idx = match self.dealers_ordered.get(&dealer) {
+            Some(idx) => *idx,
+            None => return Err(Error::ContirbutorInvalid),
+        };
+
+        // Check if dealing already exists
+        if self.dealings.contains(&idx) {
+            return Err(Error::DuplicateDealing);
+        }
+        let commitment = match self.commitments.get(&dealer) {
+            Some(commitment) => commitment,
+            None => return Err(Error::MissingCommitment),
+        };
+
+        // Ensure there is a share for each share index of each recipient
+        let recipients = self
+            .recipients
+            .iter()
+            .enumerate()
+            .filter_map(|(recipient, recipient_bytes)| {
+                let recipient = recipient as u32;
+                if *recipient_bytes == dealer || !self.keys.contains_key(&recipient) {
+                    return None;
+                }
+                Some(recipient)
+            })
+            .collect::<Vec<_>>();
+        let expected = recipients
+            .iter()
+            .flat_map(|recipient| self.allocation.indices(*recipient))
+            .collect::<Vec<_>>();
+        if shares.len() != expected.len()
+            || shares
+                .iter()
+                .zip(expected.iter())
+                .any(|(share, index)| share.index != *index)
+        {
+            self.disqualified.insert(dealer);
+            return Err(Error::InvalidDealing);
+        }
+
+        // Verify all encrypted shares
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(self.concurrency)
+            .build()
+            .expect("unable to build thread pool");
+        let result = pool.install(|| {
+            shares.par_iter().try_for_each(|share| {
+                let owner = self.allocation.owner(share.index).unwrap();
+                let key = self.keys.get(&owner).unwrap();
+                pvss::verify::<V>(idx, commitment, key, share)
+            })
+        });
+        if let Err(e) = result {
+            self.disqualified.insert(dealer);
+            return Err(e);
+        }
+
+        // Treat the dealing as an ack from all recipients
+        self.dealings.insert(idx);
+        self.acks.entry(idx).or_default().extend(recipients);
+        Ok(())
+    }
+
     /// Verify a complaint from a recipient for a dealer.
     ///
     /// If a complaint is valid, the dealer is disqualified. If a
     /// complaint is invalid, the recipient is disqualified.
+    ///
+    /// If encrypted shares are required, complaints are rejected (an invalid dealing
+    /// is detected when it is posted).
     pub fn complaint(
         &mut self,
         recipient: PublicKey,
         dealer: u32,
         share: &Share,
     ) -> Result<(), Error> {
+        // Ensure plaintext complaints are expected
+        if self.require_encryption {
+            return Err(Error::UnexpectedComplaint);
+        }
+
         // Check if contributor is disqualified
         if self.disqualified.contains(&recipient) {
             return Err(Error::ContributorDisqualified);
@@ -316,13 +710,22 @@ impl P1 {
         }
 
         // Verify complaint
+        //
+        // A share at an index not owned by the recipient is checked against its first index
+        // (and will fail verification).
+        let indices = self.allocation.indices(idx);
+        let index = if indices.contains(&share.index) {
+            share.index
+        } else {
+            indices.start
+        };
         let commitment = self.commitments.get(dealer_key).unwrap();
-        match ops::verify_share(
+        match ops::verify_share::<V>(
             self.previous.as_ref(),
             dealer,
             commitment,
             self.threshold,
-            idx,
+            index,
             share,
         ) {
             Ok(_) => {
@@ -342,7 +745,7 @@ impl P1 {
     fn requests(&self) -> (HashMap<u32, HashSet<u32>>, Vec<Request>) {
         // Compute missing shares
         let mut missing_dealings = HashMap::new(); // dealer -> {recipient}
-        let mut required_reveals = HashMap::new(); // recipient -> {dealer}
+        let mut required_reveals: HashMap<u32, HashSet<u32>> = HashMap::new(); // recipient -> {dealer}
         for (dealer, acks) in self.acks.iter() {
             for (recipient, recipient_bytes) in self.recipients.iter().enumerate() {
                 // Skip any recipients that are disqualified or have already acked
@@ -361,10 +764,10 @@ impl P1 {
                 // Add dealer -> recipient to tracker
                 let entry = missing_dealings.entry(*dealer).or_insert_with(HashSet::new);
                 entry.insert(recipient);
-                let entry = required_reveals
+                required_reveals
                     .entry(recipient)
-                    .or_insert_with(HashSet::new);
-                entry.insert(*dealer);
+                    .or_default()
+                    .insert(*dealer);
             }
         }
 
@@ -386,18 +789,81 @@ impl P1 {
             // shares because a particular recipient may just be refusing to participate.
         }
 
-        // Construct required reveals
+        // Construct required reveals (for each share index owned by a recipient)
         let mut reveals = Vec::new();
+        let mut missing_shares = HashMap::new(); // dealer -> {share index}
         for (dealer, recipients) in missing_dealings.iter() {
+            let entry: &mut HashSet<u32> = missing_shares.entry(*dealer).or_default();
             for recipient in recipients.iter() {
-                reveals.push((*dealer, *recipient));
+                for index in self.allocation.indices(*recipient) {
+                    entry.insert(index);
+                    reveals.push((*dealer, index));
+                }
             }
         }
-        (missing_dealings, reveals)
+        (missing_shares, reveals)
     }
 
-    /// If there exist at least `threshold - 1` acks each for `required()` dealers, proceed to `P2`.
-    pub fn finalize(mut self) -> (Option<(P2, Vec<Request>)>, HashSet<PublicKey>) {
+    /// Serializes the state of the arbiter (to be resumed with `deserialize`).
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut writer = Writer::new(codec::ARBITER_P1);
+        writer.u32(self.threshold);
+        writer.option_poly(self.previous.as_ref());
+        writer.option_poly(self.refresh.as_ref());
+        writer.usize(self.concurrency);
+        writer.public_keys(&self.dealers);
+        writer.public_keys(&self.recipients);
+        writer.allocation(&self.allocation);
+        writer.bool(self.require_encryption);
+        writer.encryption_keys(&self.keys);
+        writer.indices(&self.dealings);
+        writer.commitments(&self.commitments);
+        writer.public_key_set(&self.disqualified);
+        writer.index_sets(&self.acks);
+        writer.finish()
+    }
+
+    /// Deserializes the state of an arbiter.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(bytes, codec::ARBITER_P1)?;
+        let threshold = reader.u32()?;
+        let previous = reader.option_poly()?;
+        let refresh = reader.option_poly()?;
+        let concurrency = reader.usize()?;
+        let dealers = reader.public_keys()?;
+        let dealers_ordered = codec::ordered(&dealers);
+        let recipients = reader.public_keys()?;
+        let recipients_ordered = codec::ordered(&recipients);
+        let allocation = reader.allocation(recipients.len())?;
+        let require_encryption = reader.bool()?;
+        let keys = reader.encryption_keys(recipients.len())?;
+        let dealings = reader.indices(dealers.len())?;
+        let commitments = reader.commitments(&dealers_ordered)?;
+        let disqualified = reader.public_key_set()?;
+        let acks = reader.index_sets(dealers.len(), recipients.len())?;
+        reader.finish()?;
+        Some(Self {
+            threshold,
+            previous,
+            refresh,
+            concurrency,
+            dealers,
+            dealers_ordered,
+            recipients,
+            recipients_ordered,
+            allocation,
+            require_encryption,
+            keys,
+            dealings,
+            commitments,
+            disqualified,
+            acks,
+        })
+    }
+
+    /// If there exist at least `threshold - 1` acks (or, in weighted mode, acks from recipients holding
+    /// `threshold` weight including the dealer's own) each for `required()` dealers, proceed to `P2`.
+    pub fn finalize(mut self) -> (Option<Transition<V>>, HashSet<PublicKey>) {
         // Remove acks of disqualified recipients
         for acks in self.acks.values_mut() {
             for disqualified in self.disqualified.iter() {
@@ -409,17 +875,34 @@ impl P1 {
             }
         }
 
-        // Disqualify any commitments without at least `self.threshold` acks
+        // Disqualify any dealers that did not post a dealing (if required)
+        if self.require_encryption {
+            for dealer in self.commitments.keys() {
+                let idx = self.dealers_ordered.get(dealer).unwrap();
+                if !self.dealings.contains(idx) {
+                    self.disqualified.insert(dealer.clone());
+                }
+            }
+        }
+
+        // Disqualify any commitments without at least `self.threshold` acks (by weight)
         for dealer in self.commitments.keys() {
             let idx = self.dealers_ordered.get(dealer).unwrap();
             let acks = match self.acks.get(idx) {
-                Some(acks) => acks.len(),
+                Some(acks) => acks
+                    .iter()
+                    .map(|recipient| self.allocation.weight(*recipient) as u64)
+                    .sum(),
                 None => 0,
             };
 
-            // Check against `self.threshold - 1` because we don't send an
+            // Include the weight of the dealer because we don't send an
             // ack for ourselves.
-            if acks < (self.threshold - 1) as usize {
+            let own = match self.recipients_ordered.get(dealer) {
+                Some(recipient) => self.allocation.weight(*recipient) as u64,
+                None => 1,
+            };
+            if acks + own < self.threshold as u64 {
                 self.disqualified.insert(dealer.clone());
             }
         }
@@ -444,6 +927,7 @@ impl P1 {
                 P2 {
                     threshold: self.threshold,
                     previous: self.previous,
+                    refresh: self.refresh,
                     concurrency: self.concurrency,
                     dealers: self.dealers,
                     dealers_ordered: self.dealers_ordered,
@@ -462,22 +946,76 @@ impl P1 {
 
 /// Output of the DKG/Resharing procedure.
 #[derive(Clone)]
-pub struct Output {
-    pub public: poly::Public,
+pub struct Output<V: Variant = MinPk> {
+    pub public: poly::Public<V>,
     pub commitments: Vec<u32>,
     pub resolutions: HashMap<(u32, u32), Share>,
 }
 
+impl<V: Variant> Output<V> {
+    /// Serializes the output.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut writer = Writer::new(codec::ARBITER_OUTPUT);
+        writer.poly(&self.public);
+        writer.u32(self.commitments.len() as u32);
+        for commitment in &self.commitments {
+            writer.u32(*commitment);
+        }
+        write_resolutions(&mut writer, &self.resolutions);
+        writer.finish()
+    }
+
+    /// Deserializes the output.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(bytes, codec::ARBITER_OUTPUT)?;
+        let public = reader.poly()?;
+        let mut commitments = Vec::new();
+        for _ in 0..reader.u32()? {
+            commitments.push(reader.u32()?);
+        }
+        let resolutions = read_resolutions(&mut reader)?;
+        reader.finish()?;
+        Some(Self {
+            public,
+            commitments,
+            resolutions,
+        })
+    }
+}
+
+fn write_resolutions(writer: &mut Writer, resolutions: &HashMap<(u32, u32), Share>) {
+    let mut resolutions = resolutions.iter().collect::<Vec<_>>();
+    resolutions.sort_by_key(|(key, _)| **key);
+    writer.u32(resolutions.len() as u32);
+    for ((dealer, _), share) in resolutions {
+        writer.u32(*dealer);
+        writer.share(share);
+    }
+}
+
+fn read_resolutions(reader: &mut Reader) -> Option<HashMap<(u32, u32), Share>> {
+    let mut resolutions = HashMap::new();
+    for _ in 0..reader.u32()? {
+        let dealer = reader.u32()?;
+        let share = reader.share()?;
+        if resolutions.insert((dealer, share.index), share).is_some() {
+            return None;
+        }
+    }
+    Some(resolutions)
+}
+
 /// Collect missing shares (if any) and recover the public polynomial.
-pub struct P2 {
+pub struct P2<V: Variant = MinPk> {
     threshold: u32,
-    previous: Option<poly::Public>,
+    previous: Option<poly::Public<V>>,
+    refresh: Option<poly::Public<V>>,
     concurrency: usize,
 
     dealers: Vec<PublicKey>,
     dealers_ordered: HashMap<PublicKey, u32>,
 
-    commitments: HashMap<PublicKey, poly::Public>,
+    commitments: HashMap<PublicKey, poly::Public<V>>,
     disqualified: HashSet<PublicKey>,
 
     acks: HashMap<u32, HashSet<u32>>,
@@ -486,7 +1024,7 @@ pub struct P2 {
     resolutions: HashMap<(u32, u32), Share>,
 }
 
-impl P2 {
+impl<V: Variant> P2<V> {
     /// Required number of commitments to continue procedure.
     pub fn required(&self) -> u32 {
         match &self.previous {
@@ -506,7 +1044,7 @@ impl P2 {
     }
 
     /// Return all tracked commitments.
-    pub fn commitments(&self) -> Vec<Commitment> {
+    pub fn commitments(&self) -> Vec<Commitment<V>> {
         self.commitments
             .iter()
             .filter_map(|(contributor, commitment)| {
@@ -542,7 +1080,7 @@ impl P2 {
 
         // Verify share
         let commitment = self.commitments.get(&dealer).unwrap();
-        if let Err(e) = ops::verify_share(
+        if let Err(e) = ops::verify_share::<V>(
             self.previous.as_ref(),
             idx,
             commitment,
@@ -570,9 +1108,55 @@ impl P2 {
         }
     }
 
+    /// Serializes the state of the arbiter (to be resumed with `deserialize`).
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut writer = Writer::new(codec::ARBITER_P2);
+        writer.u32(self.threshold);
+        writer.option_poly(self.previous.as_ref());
+        writer.option_poly(self.refresh.as_ref());
+        writer.usize(self.concurrency);
+        writer.public_keys(&self.dealers);
+        writer.commitments(&self.commitments);
+        writer.public_key_set(&self.disqualified);
+        writer.index_sets(&self.acks);
+        writer.index_sets(&self.missing_dealings);
+        write_resolutions(&mut writer, &self.resolutions);
+        writer.finish()
+    }
+
+    /// Deserializes the state of an arbiter.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(bytes, codec::ARBITER_P2)?;
+        let threshold = reader.u32()?;
+        let previous = reader.option_poly()?;
+        let refresh = reader.option_poly()?;
+        let concurrency = reader.usize()?;
+        let dealers = reader.public_keys()?;
+        let dealers_ordered = codec::ordered(&dealers);
+        let commitments = reader.commitments(&dealers_ordered)?;
+        let disqualified = reader.public_key_set()?;
+        let acks = reader.index_sets(dealers.len(), u32::MAX as usize)?;
+        let missing_dealings = reader.index_sets(dealers.len(), u32::MAX as usize)?;
+        let resolutions = read_resolutions(&mut reader)?;
+        reader.finish()?;
+        Some(Self {
+            threshold,
+            previous,
+            refresh,
+            concurrency,
+            dealers,
+            dealers_ordered,
+            commitments,
+            disqualified,
+            acks,
+            missing_dealings,
+            resolutions,
+        })
+    }
+
     /// If there exist at least `threshold` resolutions for `required()` dealers, recover
     /// the group public polynomial.
-    pub fn finalize(mut self) -> (Result<Output, Error>, HashSet<PublicKey>) {
+    pub fn finalize(mut self) -> (Result<Output<V>, Error>, HashSet<PublicKey>) {
         // Remove any dealers that did not distribute all required shares (may not be `n`)
         for (dealer, recipients) in &self.missing_dealings {
             if recipients.is_empty() {
@@ -589,6 +1173,12 @@ impl P2 {
                 .remove(self.dealers_ordered.get(disqualified).unwrap());
         }
 
+        // Remove any resolutions from disqualified dealers
+        let dealers = &self.dealers;
+        let disqualified = &self.disqualified;
+        self.resolutions
+            .retain(|(dealer, _), _| !disqualified.contains(&dealers[*dealer as usize]));
+
         // Determine if we have enough resolutions
         let required = self.required();
         if self.acks.len() < required as usize {
@@ -606,21 +1196,34 @@ impl P2 {
                         (*idx, commitment.clone())
                     })
                     .collect();
-                match ops::recover_public(&previous, commitments, self.threshold, self.concurrency)
-                {
+                match ops::recover_public::<V>(
+                    &previous,
+                    commitments,
+                    self.threshold,
+                    self.concurrency,
+                ) {
                     Ok(public) => public,
                     Err(e) => return (Err(e), self.disqualified),
                 }
             }
             None => {
                 let commitments = self.commitments.values().cloned().collect();
-                match ops::construct_public(commitments, required) {
+                match ops::construct_public::<V>(commitments, required) {
                     Ok(public) => public,
                     Err(e) => return (Err(e), self.disqualified),
                 }
             }
         };
 
+        // If refreshing, offset the previous group polynomial
+        let public = match self.refresh {
+            Some(mut previous) => {
+                previous.add(&public);
+                previous
+            }
+            None => public,
+        };
+
         // Generate output
         let output = Output {
             public,
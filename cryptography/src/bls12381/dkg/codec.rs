@@ -0,0 +1,373 @@
+//! Versioned encoding of the state of a DKG/Resharing procedure.
+//!
+//! Every encoding starts with the [VERSION] of the format and a tag identifying the encoded
+//! type. Collections are encoded in sorted order, so the same state always has the same encoding.
+
+use super::weighted::Allocation;
+use crate::bls12381::primitives::{
+    group::{Element, Share, G1, PRIVATE_KEY_LENGTH},
+    poly::Poly,
+};
+use crate::PublicKey;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use zeroize::Zeroize;
+
+/// Version of the encoding.
+///
+/// This must be incremented whenever the encoding of any type changes.
+pub const VERSION: u8 = 2;
+
+pub const ARBITER_P0: u8 = 0;
+pub const ARBITER_P1: u8 = 1;
+pub const ARBITER_P2: u8 = 2;
+pub const ARBITER_OUTPUT: u8 = 3;
+pub const CONTRIBUTOR_P0: u8 = 4;
+pub const CONTRIBUTOR_P1: u8 = 5;
+pub const CONTRIBUTOR_P2: u8 = 6;
+pub const CONTRIBUTOR_OUTPUT: u8 = 7;
+pub const CONTRIBUTOR_DEALING: u8 = 8;
+
+/// Returns the index of each participant (in the provided order).
+pub fn ordered(participants: &[PublicKey]) -> HashMap<PublicKey, u32> {
+    participants
+        .iter()
+        .enumerate()
+        .map(|(i, pk)| (pk.clone(), i as u32))
+        .collect()
+}
+
+/// Returns participants sorted by their index.
+pub fn participants(ordered: &HashMap<PublicKey, u32>) -> Vec<PublicKey> {
+    let mut participants = ordered.iter().collect::<Vec<_>>();
+    participants.sort_by_key(|(_, idx)| **idx);
+    participants.into_iter().map(|(pk, _)| pk.clone()).collect()
+}
+
+/// Encodes state into a buffer.
+///
+/// Because encoded state may contain secrets, any buffer that is outgrown is
+/// zeroized (rather than left behind for the allocator).
+pub struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    pub fn new(tag: u8) -> Self {
+        let mut buf = Vec::with_capacity(1024);
+        buf.push(VERSION);
+        buf.push(tag);
+        Self { buf }
+    }
+
+    fn extend(&mut self, bytes: &[u8]) {
+        let required = self.buf.len() + bytes.len();
+        if required > self.buf.capacity() {
+            let mut grown = Vec::with_capacity(required.max(2 * self.buf.capacity()));
+            grown.extend_from_slice(&self.buf);
+            self.buf.zeroize();
+            self.buf = grown;
+        }
+        self.buf.extend_from_slice(bytes);
+    }
+
+    pub fn bool(&mut self, value: bool) {
+        self.extend(&[value as u8]);
+    }
+
+    pub fn u32(&mut self, value: u32) {
+        self.extend(&value.to_be_bytes());
+    }
+
+    pub fn usize(&mut self, value: usize) {
+        self.extend(&(value as u64).to_be_bytes());
+    }
+
+    pub fn public_key(&mut self, pk: &PublicKey) {
+        self.u32(pk.len() as u32);
+        self.extend(pk);
+    }
+
+    pub fn public_keys(&mut self, pks: &[PublicKey]) {
+        self.u32(pks.len() as u32);
+        for pk in pks {
+            self.public_key(pk);
+        }
+    }
+
+    pub fn public_key_set(&mut self, pks: &HashSet<PublicKey>) {
+        let mut pks = pks.iter().cloned().collect::<Vec<_>>();
+        pks.sort();
+        self.public_keys(&pks);
+    }
+
+    pub fn indices(&mut self, indices: &HashSet<u32>) {
+        let mut indices = indices.iter().copied().collect::<Vec<_>>();
+        indices.sort();
+        self.u32(indices.len() as u32);
+        for idx in indices {
+            self.u32(idx);
+        }
+    }
+
+    pub fn index_sets(&mut self, sets: &HashMap<u32, HashSet<u32>>) {
+        let sets = sets.iter().collect::<BTreeMap<_, _>>();
+        self.u32(sets.len() as u32);
+        for (idx, set) in sets {
+            self.u32(*idx);
+            self.indices(set);
+        }
+    }
+
+    pub fn element<E: Element>(&mut self, element: &E) {
+        self.extend(&element.serialize());
+    }
+
+    /// Writes a polynomial (omitting a zero constant term, like that of a refresh commitment,
+    /// which cannot be encoded as a point).
+    pub fn poly<C: Element>(&mut self, poly: &Poly<C>) {
+        let zero = *poly.constant() == C::zero();
+        self.u32(poly.required());
+        self.bool(zero);
+        for coeff in poly.coefficients().iter().skip(zero as usize) {
+            self.element(coeff);
+        }
+    }
+
+    pub fn option_poly<C: Element>(&mut self, poly: Option<&Poly<C>>) {
+        self.bool(poly.is_some());
+        if let Some(poly) = poly {
+            self.poly(poly);
+        }
+    }
+
+    pub fn share(&mut self, share: &Share) {
+        let mut bytes = share.serialize();
+        self.extend(&bytes);
+        bytes.zeroize();
+    }
+
+    pub fn commitments<C: Element>(&mut self, commitments: &HashMap<PublicKey, Poly<C>>) {
+        let commitments = commitments.iter().collect::<BTreeMap<_, _>>();
+        self.u32(commitments.len() as u32);
+        for (dealer, commitment) in commitments {
+            self.public_key(dealer);
+            self.poly(commitment);
+        }
+    }
+
+    pub fn allocation(&mut self, allocation: &Allocation) {
+        let weights = allocation.weights();
+        self.u32(weights.len() as u32);
+        for weight in weights {
+            self.u32(weight);
+        }
+    }
+
+    pub fn encryption_keys(&mut self, keys: &HashMap<u32, G1>) {
+        let keys = keys.iter().collect::<BTreeMap<_, _>>();
+        self.u32(keys.len() as u32);
+        for (idx, key) in keys {
+            self.u32(*idx);
+            self.element(key);
+        }
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+}
+
+/// Decodes state from a buffer.
+pub struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    /// Returns `None` if the buffer is not of the expected version and type.
+    pub fn new(buf: &'a [u8], tag: u8) -> Option<Self> {
+        if buf.len() < 2 || buf[0] != VERSION || buf[1] != tag {
+            return None;
+        }
+        Some(Self { buf: &buf[2..] })
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.buf.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Some(taken)
+    }
+
+    /// Reads a length (bounded by the remaining size of the buffer to avoid
+    /// allocating for a malicious length).
+    fn length(&mut self) -> Option<usize> {
+        let len = self.u32()? as usize;
+        if len > self.buf.len() {
+            return None;
+        }
+        Some(len)
+    }
+
+    pub fn bool(&mut self) -> Option<bool> {
+        match self.take(1)?[0] {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub fn usize(&mut self) -> Option<usize> {
+        usize::try_from(u64::from_be_bytes(self.take(8)?.try_into().unwrap())).ok()
+    }
+
+    pub fn public_key(&mut self) -> Option<PublicKey> {
+        let len = self.length()?;
+        Some(PublicKey::copy_from_slice(self.take(len)?))
+    }
+
+    /// Reads a list of participants (which must be sorted and unique).
+    pub fn public_keys(&mut self) -> Option<Vec<PublicKey>> {
+        let len = self.length()?;
+        let mut pks: Vec<PublicKey> = Vec::with_capacity(len);
+        for _ in 0..len {
+            let pk = self.public_key()?;
+            if let Some(last) = pks.last() {
+                if *last >= pk {
+                    return None;
+                }
+            }
+            pks.push(pk);
+        }
+        Some(pks)
+    }
+
+    pub fn public_key_set(&mut self) -> Option<HashSet<PublicKey>> {
+        Some(self.public_keys()?.into_iter().collect())
+    }
+
+    /// Reads a set of indices (each of which must be less than `bound`).
+    pub fn indices(&mut self, bound: usize) -> Option<HashSet<u32>> {
+        let len = self.length()?;
+        let mut indices = HashSet::with_capacity(len);
+        for _ in 0..len {
+            let idx = self.u32()?;
+            if idx as usize >= bound || !indices.insert(idx) {
+                return None;
+            }
+        }
+        Some(indices)
+    }
+
+    pub fn index_sets(
+        &mut self,
+        key_bound: usize,
+        value_bound: usize,
+    ) -> Option<HashMap<u32, HashSet<u32>>> {
+        let len = self.length()?;
+        let mut sets = HashMap::with_capacity(len);
+        for _ in 0..len {
+            let idx = self.u32()?;
+            if idx as usize >= key_bound {
+                return None;
+            }
+            let set = self.indices(value_bound)?;
+            if sets.insert(idx, set).is_some() {
+                return None;
+            }
+        }
+        Some(sets)
+    }
+
+    pub fn element<E: Element>(&mut self) -> Option<E> {
+        E::deserialize(self.take(E::size())?)
+    }
+
+    pub fn poly<C: Element>(&mut self) -> Option<Poly<C>> {
+        let required = self.length()?;
+        if required == 0 {
+            return None;
+        }
+        let mut coeffs = Vec::with_capacity(required);
+        if self.bool()? {
+            coeffs.push(C::zero());
+        }
+        while coeffs.len() < required {
+            coeffs.push(self.element()?);
+        }
+        Some(Poly::from(coeffs))
+    }
+
+    pub fn option_poly<C: Element>(&mut self) -> Option<Option<Poly<C>>> {
+        match self.bool()? {
+            true => Some(Some(self.poly()?)),
+            false => Some(None),
+        }
+    }
+
+    pub fn share(&mut self) -> Option<Share> {
+        Share::deserialize(self.take(4 + PRIVATE_KEY_LENGTH)?)
+    }
+
+    /// Reads commitments (from dealers in `dealers`).
+    pub fn commitments<C: Element>(
+        &mut self,
+        dealers: &HashMap<PublicKey, u32>,
+    ) -> Option<HashMap<PublicKey, Poly<C>>> {
+        let len = self.length()?;
+        let mut commitments = HashMap::with_capacity(len);
+        for _ in 0..len {
+            let dealer = self.public_key()?;
+            if !dealers.contains_key(&dealer) {
+                return None;
+            }
+            let commitment = self.poly()?;
+            if commitments.insert(dealer, commitment).is_some() {
+                return None;
+            }
+        }
+        Some(commitments)
+    }
+
+    /// Reads an allocation (which must assign shares to exactly `recipients` recipients).
+    pub fn allocation(&mut self, recipients: usize) -> Option<Allocation> {
+        let len = self.length()?;
+        if len != recipients {
+            return None;
+        }
+        let mut weights = Vec::with_capacity(len);
+        for _ in 0..len {
+            weights.push(self.u32()?);
+        }
+        Allocation::new(&weights)
+    }
+
+    pub fn encryption_keys(&mut self, bound: usize) -> Option<HashMap<u32, G1>> {
+        let len = self.length()?;
+        let mut keys = HashMap::with_capacity(len);
+        for _ in 0..len {
+            let idx = self.u32()?;
+            if idx as usize >= bound {
+                return None;
+            }
+            let key = self.element()?;
+            if keys.insert(idx, key).is_some() {
+                return None;
+            }
+        }
+        Some(keys)
+    }
+
+    /// Returns `None` if there are any unread bytes.
+    pub fn finish(self) -> Option<()> {
+        if !self.buf.is_empty() {
+            return None;
+        }
+        Some(())
+    }
+}
@@ -9,53 +9,257 @@
 //! an honest contributor from recognizing a commitment as valid (that all other
 //! contributors have agreed upon).
 //!
+//! # Weighted Mode
+//!
+//! When constructed with `new_weighted`, a recipient owns multiple share indices (see
+//! [super::weighted]) and the [Output] contains one share per index owned. Otherwise, the
+//! [Output] contains a single share (returned by [Output::share]).
+//!
+//! # Refresh Mode
+//!
+//! When constructed with `P0::new_refresh`, the contributor deals shares of zero (and only accepts
+//! commitments with a zero constant term). The [Output] then contains the previous group polynomial
+//! offset by all included commitments and the previous share offset by all shares received, which
+//! recovers the same group secret as before but cannot be combined with shares from before the refresh.
+//!
 //! # Warning
 //!
 //! It is up to the developer to authorize interaction with the contributor. This is purposely
 //! not provided by the contributor because this authorization function is highly dependent on
 //! the context in which the contributor is being used.
 
+use super::codec::{self, Reader, Writer};
 use crate::bls12381::{
-    dkg::{ops, Error},
+    dkg::{
+        ops,
+        weighted::{self, Allocation},
+        Error,
+    },
     primitives::{
-        group::{self, Element, Share},
+        group::{self, Element, MinPk, Share, Variant},
         poly::{self, Eval},
     },
 };
 use crate::PublicKey;
+use rand::{rngs::OsRng, CryptoRng, RngCore};
 use std::collections::{BTreeMap, HashMap, HashSet};
+use zeroize::{Zeroize, Zeroizing};
 
 /// Output of a DKG/Resharing procedure.
 #[derive(Clone)]
-pub struct Output {
-    pub public: poly::Public,
-    pub commitments: Vec<poly::Public>,
-    pub share: Share,
+pub struct Output<V: Variant = MinPk> {
+    pub public: poly::Public<V>,
+    pub commitments: Vec<poly::Public<V>>,
+    pub shares: Vec<Share>,
+}
+
+impl<V: Variant> Output<V> {
+    /// Returns the share of a contributor that owns a single share index (i.e. any
+    /// contributor not constructed with `new_weighted`).
+    ///
+    /// Returns `None` if the contributor owns more than one share index (use `shares`).
+    pub fn share(&self) -> Option<&Share> {
+        match self.shares.as_slice() {
+            [share] => Some(share),
+            _ => None,
+        }
+    }
+
+    /// Serializes the output.
+    pub fn serialize(&self) -> Zeroizing<Vec<u8>> {
+        let mut writer = Writer::new(codec::CONTRIBUTOR_OUTPUT);
+        writer.poly(&self.public);
+        writer.u32(self.commitments.len() as u32);
+        for commitment in &self.commitments {
+            writer.poly(commitment);
+        }
+        writer.u32(self.shares.len() as u32);
+        for share in &self.shares {
+            writer.share(share);
+        }
+        Zeroizing::new(writer.finish())
+    }
+
+    /// Deserializes the output.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(bytes, codec::CONTRIBUTOR_OUTPUT)?;
+        let public = reader.poly()?;
+        let mut commitments = Vec::new();
+        for _ in 0..reader.u32()? {
+            commitments.push(reader.poly()?);
+        }
+        let mut shares = Vec::new();
+        for _ in 0..reader.u32()? {
+            shares.push(reader.share()?);
+        }
+        reader.finish()?;
+        Some(Self {
+            public,
+            commitments,
+            shares,
+        })
+    }
+}
+
+impl<V: Variant> Zeroize for Output<V> {
+    fn zeroize(&mut self) {
+        self.shares.zeroize();
+    }
+}
+
+impl<V: Variant> Drop for Output<V> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+/// Commitment and shares generated by a dealer (returned by `P0::finalize`).
+///
+/// To redistribute the same shares after a restart (rather than dealing new ones
+/// that conflict with the commitment already sent to the arbiter), this should be
+/// persisted before the commitment is sent.
+#[derive(Clone)]
+pub struct Dealing<V: Variant = MinPk> {
+    pub commitment: poly::Public<V>,
+    pub shares: Vec<Share>,
+}
+
+impl<V: Variant> Dealing<V> {
+    /// Serializes the dealing.
+    pub fn serialize(&self) -> Zeroizing<Vec<u8>> {
+        let mut writer = Writer::new(codec::CONTRIBUTOR_DEALING);
+        writer.poly(&self.commitment);
+        writer.u32(self.shares.len() as u32);
+        for share in &self.shares {
+            writer.share(share);
+        }
+        Zeroizing::new(writer.finish())
+    }
+
+    /// Deserializes the dealing.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(bytes, codec::CONTRIBUTOR_DEALING)?;
+        let commitment = reader.poly()?;
+        let mut shares = Vec::new();
+        for _ in 0..reader.u32()? {
+            shares.push(reader.share()?);
+        }
+        reader.finish()?;
+        Some(Self { commitment, shares })
+    }
+}
+
+impl<V: Variant> Zeroize for Dealing<V> {
+    fn zeroize(&mut self) {
+        self.shares.zeroize();
+    }
+}
+
+impl<V: Variant> Drop for Dealing<V> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
 }
 
 /// Generate shares and a commitment (optional).
-pub struct P0 {
+pub struct P0<V: Variant = MinPk> {
     me: PublicKey,
     threshold: u32,
-    previous: Option<(poly::Public, Share)>,
+    previous: Option<(poly::Public<V>, Share)>,
+    refresh: Option<(poly::Public<V>, Share)>,
     concurrency: usize,
 
     dealers_ordered: HashMap<PublicKey, u32>,
     recipients: Vec<PublicKey>,
     recipients_ordered: HashMap<PublicKey, u32>,
+    allocation: Allocation,
 }
 
-impl P0 {
+impl<V: Variant> P0<V> {
     /// Create a new dealer for a DKG/Resharing procedure (optional).
     ///
     /// If `me` is not in `dealers`, this will panic.
     pub fn new(
         me: PublicKey,
         threshold: u32,
-        previous: Option<(poly::Public, Share)>,
-        mut dealers: Vec<PublicKey>,
+        previous: Option<(poly::Public<V>, Share)>,
+        dealers: Vec<PublicKey>,
         mut recipients: Vec<PublicKey>,
         concurrency: usize,
+    ) -> Self {
+        recipients.sort();
+        let allocation = Allocation::uniform(recipients.len() as u32);
+        Self::init(
+            me,
+            threshold,
+            previous,
+            dealers,
+            recipients,
+            allocation,
+            concurrency,
+        )
+    }
+
+    /// Create a new dealer for a weighted DKG procedure, where each recipient
+    /// owns as many shares as its weight (and `threshold` is expressed in weight).
+    ///
+    /// The `shares` returned by `finalize` are indexed by share index (recipient `i` should be
+    /// sent the shares at `Allocation::indices(i)`).
+    ///
+    /// If `me` is not in `dealers` or any weight is zero, this will panic.
+    pub fn new_weighted(
+        me: PublicKey,
+        threshold: u32,
+        dealers: Vec<PublicKey>,
+        recipients: Vec<(PublicKey, u32)>,
+        concurrency: usize,
+    ) -> Self {
+        let (recipients, allocation) = weighted::sort(recipients);
+        Self::init(
+            me,
+            threshold,
+            None,
+            dealers,
+            recipients,
+            allocation,
+            concurrency,
+        )
+    }
+
+    /// Create a new dealer for a refresh procedure, where the participants of the round that
+    /// generated `previous` (the group polynomial and our share of it) re-randomize their shares.
+    ///
+    /// `participants` must be the recipients of the round that generated `previous`. If `me` is
+    /// not in `participants`, this will panic.
+    pub fn new_refresh(
+        me: PublicKey,
+        previous: (poly::Public<V>, Share),
+        mut participants: Vec<PublicKey>,
+        concurrency: usize,
+    ) -> Self {
+        participants.sort();
+        let allocation = Allocation::uniform(participants.len() as u32);
+        let mut dealer = Self::init(
+            me,
+            previous.0.required(),
+            None,
+            participants.clone(),
+            participants,
+            allocation,
+            concurrency,
+        );
+        dealer.refresh = Some(previous);
+        dealer
+    }
+
+    fn init(
+        me: PublicKey,
+        threshold: u32,
+        previous: Option<(poly::Public<V>, Share)>,
+        mut dealers: Vec<PublicKey>,
+        recipients: Vec<PublicKey>,
+        allocation: Allocation,
+        concurrency: usize,
     ) -> Self {
         dealers.sort();
         let dealers_ordered = dealers
@@ -66,7 +270,6 @@ impl P0 {
         if !dealers_ordered.contains_key(&me) {
             panic!("me must be in dealers");
         }
-        recipients.sort();
         let recipients_ordered = recipients
             .iter()
             .enumerate()
@@ -76,34 +279,100 @@ impl P0 {
             me,
             threshold,
             previous,
+            refresh: None,
             concurrency,
             dealers_ordered,
             recipients,
             recipients_ordered,
+            allocation,
+        }
+    }
+
+    /// Serializes the state of the contributor (to be resumed with `deserialize`).
+    pub fn serialize(&self) -> Zeroizing<Vec<u8>> {
+        let mut writer = Writer::new(codec::CONTRIBUTOR_P0);
+        writer.public_key(&self.me);
+        writer.u32(self.threshold);
+        writer.bool(self.previous.is_some());
+        if let Some((public, share)) = &self.previous {
+            writer.poly(public);
+            writer.share(share);
         }
+        write_refresh::<V>(&mut writer, self.refresh.as_ref());
+        writer.usize(self.concurrency);
+        writer.public_keys(&codec::participants(&self.dealers_ordered));
+        writer.public_keys(&self.recipients);
+        writer.allocation(&self.allocation);
+        Zeroizing::new(writer.finish())
+    }
+
+    /// Deserializes the state of a contributor.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader::new(bytes, codec::CONTRIBUTOR_P0)?;
+        let me = reader.public_key()?;
+        let threshold = reader.u32()?;
+        let previous = match reader.bool()? {
+            true => Some((reader.poly()?, reader.share()?)),
+            false => None,
+        };
+        let refresh = read_refresh::<V>(&mut reader)?;
+        let concurrency = reader.usize()?;
+        let dealers_ordered = codec::ordered(&reader.public_keys()?);
+        if !dealers_ordered.contains_key(&me) {
+            return None;
+        }
+        let recipients = reader.public_keys()?;
+        let recipients_ordered = codec::ordered(&recipients);
+        let allocation = reader.allocation(recipients.len())?;
+        reader.finish()?;
+        Some(Self {
+            me,
+            threshold,
+            previous,
+            refresh,
+            concurrency,
+            dealers_ordered,
+            recipients,
+            recipients_ordered,
+            allocation,
+        })
     }
 
     /// Construct commitment, shares, and optionally `P1` (if the dealer
     /// is also a recipient).
-    pub fn finalize(self) -> (Option<P1>, poly::Public, Vec<Share>) {
+    pub fn finalize(self) -> (Option<P1<V>>, poly::Public<V>, Vec<Share>) {
+        self.finalize_from(&mut OsRng)
+    }
+
+    /// Construct commitment, shares (sampled from the provided RNG), and optionally
+    /// `P1` (if the dealer is also a recipient).
+    pub fn finalize_from<R: RngCore + CryptoRng>(
+        mut self,
+        rng: &mut R,
+    ) -> (Option<P1<V>>, poly::Public<V>, Vec<Share>) {
         // Generate shares and commitment
-        let (public, share) = match self.previous {
+        let (public, share) = match self.previous.take() {
             Some((public, share)) => (Some(public), Some(share)),
             None => (None, None),
         };
-        let (commitment, shares) =
-            ops::generate_shares(share, self.recipients.len() as u32, self.threshold);
+        let n = self.allocation.total();
+        let (commitment, shares) = match self.refresh {
+            Some(_) => ops::generate_refresh_shares_from::<V, _>(rng, n, self.threshold),
+            None => ops::generate_shares_from::<V, _>(rng, share, n, self.threshold),
+        };
 
         // Proceed to next phase
         let p1 = if self.recipients_ordered.contains_key(&self.me) {
             // We manually construct P1 to avoid resorting the dealers/recipients
             Some(P1 {
-                me: self.me,
+                me: self.me.clone(),
                 threshold: self.threshold,
                 previous: public,
+                refresh: self.refresh.take(),
                 concurrency: self.concurrency,
-                dealers_ordered: self.dealers_ordered,
-                recipients_ordered: self.recipients_ordered,
+                dealers_ordered: std::mem::take(&mut self.dealers_ordered),
+                recipients_ordered: std::mem::take(&mut self.recipients_ordered),
+                allocation: self.allocation.clone(),
                 commitments: HashMap::new(),
                 valid: BTreeMap::new(),
             })
@@ -114,30 +383,85 @@ impl P0 {
     }
 }
 
+impl<V: Variant> Drop for P0<V> {
+    fn drop(&mut self) {
+        if let Some((_, share)) = self.previous.as_mut() {
+            share.zeroize();
+        }
+        zeroize_refresh::<V>(&mut self.refresh);
+    }
+}
+
 /// Track commitments distributed by dealers.
-pub struct P1 {
+pub struct P1<V: Variant = MinPk> {
     me: PublicKey,
     threshold: u32,
-    previous: Option<poly::Public>,
+    previous: Option<poly::Public<V>>,
+    refresh: Option<(poly::Public<V>, Share)>,
     concurrency: usize,
 
     dealers_ordered: HashMap<PublicKey, u32>,
     recipients_ordered: HashMap<PublicKey, u32>,
+    allocation: Allocation,
 
-    commitments: HashMap<PublicKey, poly::Public>,
+    commitments: HashMap<PublicKey, poly::Public<V>>,
 
-    valid: BTreeMap<u32, (poly::Public, Share)>,
+    valid: BTreeMap<u32, (poly::Public<V>, BTreeMap<u32, Share>)>,
 }
 
-impl P1 {
+impl<V: Variant> P1<V> {
     /// Create a new contributor for a DKG/Resharing procedure.
     pub fn new(
         me: PublicKey,
         threshold: u32,
-        previous: Option<poly::Public>,
-        mut dealers: Vec<PublicKey>,
+        previous: Option<poly::Public<V>>,
+        dealers: Vec<PublicKey>,
         mut recipients: Vec<PublicKey>,
         concurrency: usize,
+    ) -> Self {
+        recipients.sort();
+        let allocation = Allocation::uniform(recipients.len() as u32);
+        Self::init(
+            me,
+            threshold,
+            previous,
+            dealers,
+            recipients,
+            allocation,
+            concurrency,
+        )
+    }
+
+    /// Create a new contributor for a weighted DKG procedure.
+    ///
+    /// If any weight is zero, this will panic.
+    pub fn new_weighted(
+        me: PublicKey,
+        threshold: u32,
+        dealers: Vec<PublicKey>,
+        recipients: Vec<(PublicKey, u32)>,
+        concurrency: usize,
+    ) -> Self {
+        let (recipients, allocation) = weighted::sort(recipients);
+        Self::init(
+            me,
+            threshold,
+            None,
+            dealers,
+            recipients,
+            allocation,
+            concurrency,
+        )
+    }
+
+    fn init(
+        me: PublicKey,
+        threshold: u32,
+        previous: Option<poly::Public<V>>,
+        mut dealers: Vec<PublicKey>,
+        recipients: Vec<PublicKey>,
+        allocation: Allocation,
+        concurrency: usize,
     ) -> Self {
         dealers.sort();
         let dealers_ordered = dealers
@@ -145,7 +469,6 @@ impl P1 {
             .enumerate()
             .map(|(i, pk)| (pk.clone(), i as u32))
             .collect();
-        recipients.sort();
         let recipients_ordered = recipients
             .iter()
             .enumerate()
@@ -155,9 +478,11 @@ impl P1 {
             me,
             threshold,
             previous,
+            refresh: None,
             concurrency,
             dealers_ordered,
             recipients_ordered,
+            allocation,
             commitments: HashMap::new(),
             valid: BTreeMap::new(),
         }
@@ -172,7 +497,11 @@ impl P1 {
     }
 
     /// Verify and track a commitment from a dealer.
-    pub fn commitment(&mut self, dealer: PublicKey, commitment: poly::Public) -> Result<(), Error> {
+    pub fn commitment(
+        &mut self,
+        dealer: PublicKey,
+        commitment: poly::Public<V>,
+    ) -> Result<(), Error> {
         // Ensure contributor is valid
         let idx = match self.dealers_ordered.get(&dealer) {
             Some(contributor) => *contributor,
@@ -180,7 +509,15 @@ impl P1 {
         };
 
         // Verify that commitment is valid
-        ops::verify_commitment(self.previous.as_ref(), idx, &commitment, self.threshold)?;
+        match self.refresh {
+            Some(_) => ops::verify_refresh_commitment::<V>(&commitment, self.threshold)?,
+            None => ops::verify_commitment::<V>(
+                self.previous.as_ref(),
+                idx,
+                &commitment,
+                self.threshold,
+            )?,
+        }
 
         // Store commitment
         self.commitments.insert(dealer, commitment);
@@ -197,8 +534,42 @@ impl P1 {
         self.commitments.len()
     }
 
+    /// Serializes the state of the contributor (to be resumed with `deserialize`).
+    pub fn serialize(&self) -> Zeroizing<Vec<u8>> {
+        write_state::<V>(
+            codec::CONTRIBUTOR_P1,
+            &self.me,
+            self.threshold,
+            self.previous.as_ref(),
+            self.refresh.as_ref(),
+            self.concurrency,
+            &self.dealers_ordered,
+            &self.recipients_ordered,
+            &self.allocation,
+            &self.commitments,
+            &self.valid,
+        )
+    }
+
+    /// Deserializes the state of a contributor.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let state = read_state::<V>(bytes, codec::CONTRIBUTOR_P1)?;
+        Some(Self {
+            me: state.me,
+            threshold: state.threshold,
+            previous: state.previous,
+            refresh: state.refresh,
+            concurrency: state.concurrency,
+            dealers_ordered: state.dealers_ordered,
+            recipients_ordered: state.recipients_ordered,
+            allocation: state.allocation,
+            commitments: state.commitments,
+            valid: state.valid,
+        })
+    }
+
     /// If there exist at least `required()` commitments, proceed to `P2`.
-    pub fn finalize(self) -> Option<P2> {
+    pub fn finalize(mut self) -> Option<P2<V>> {
         // Ensure there are enough commitments to proceed
         if self.commitments.len() < self.required() as usize {
             return None;
@@ -206,34 +577,45 @@ impl P1 {
 
         // Proceed to next phase
         Some(P2 {
-            me: self.me,
+            me: self.me.clone(),
             threshold: self.threshold,
-            previous: self.previous,
+            previous: self.previous.take(),
+            refresh: self.refresh.take(),
             concurrency: self.concurrency,
-            dealers_ordered: self.dealers_ordered,
-            recipients_ordered: self.recipients_ordered,
-            commitments: self.commitments,
-            valid: self.valid,
+            dealers_ordered: std::mem::take(&mut self.dealers_ordered),
+            recipients_ordered: std::mem::take(&mut self.recipients_ordered),
+            allocation: self.allocation.clone(),
+            commitments: std::mem::take(&mut self.commitments),
+            valid: std::mem::take(&mut self.valid),
         })
     }
 }
 
+impl<V: Variant> Drop for P1<V> {
+    fn drop(&mut self) {
+        zeroize_refresh::<V>(&mut self.refresh);
+        zeroize_valid::<V>(&mut self.valid);
+    }
+}
+
 /// Track shares distributed by dealers.
-pub struct P2 {
+pub struct P2<V: Variant = MinPk> {
     me: PublicKey,
     threshold: u32,
-    previous: Option<poly::Public>,
+    previous: Option<poly::Public<V>>,
+    refresh: Option<(poly::Public<V>, Share)>,
     concurrency: usize,
 
     dealers_ordered: HashMap<PublicKey, u32>,
     recipients_ordered: HashMap<PublicKey, u32>,
+    allocation: Allocation,
 
-    commitments: HashMap<PublicKey, poly::Public>,
+    commitments: HashMap<PublicKey, poly::Public<V>>,
 
-    valid: BTreeMap<u32, (poly::Public, Share)>,
+    valid: BTreeMap<u32, (poly::Public<V>, BTreeMap<u32, Share>)>,
 }
 
-impl P2 {
+impl<V: Variant> P2<V> {
     /// Required number of commitments to continue procedure.
     pub fn required(&self) -> u32 {
         match &self.previous {
@@ -251,7 +633,8 @@ impl P2 {
         };
 
         // Ensure share is for us
-        if share.index != self.recipients_ordered[&self.me] {
+        let me = self.recipients_ordered[&self.me];
+        if !self.allocation.indices(me).contains(&share.index) {
             return Err(Error::MisdirectedShare);
         }
 
@@ -260,7 +643,7 @@ impl P2 {
             Some(commitment) => commitment.clone(),
             None => return Err(Error::MissingCommitment),
         };
-        ops::verify_share(
+        ops::verify_share::<V>(
             self.previous.as_ref(),
             idx,
             &commitment,
@@ -271,19 +654,119 @@ impl P2 {
 
         // Store share for later use
         //
-        // If we receive multiple shares from the same dealer, we will
+        // If we receive multiple shares (at the same index) from the same dealer, we will
         // only keep the last.
-        self.valid.insert(idx, (commitment, share));
+        let (stored, shares) = self
+            .valid
+            .entry(idx)
+            .or_insert_with(|| (commitment.clone(), BTreeMap::new()));
+        *stored = commitment;
+        shares.insert(share.index, share);
+        Ok(())
+    }
+
+    /// Verify and track all shares from a dealer (one for each index owned by us) at once.
+    ///
+    /// This is equivalent to calling `share` for each share but verifies all shares with a single
+    /// multi-scalar multiplication over the dealer's commitment (see `ops::verify_shares`), which
+    /// is significantly faster when we own many indices. If the batch is invalid, each share is
+    /// verified individually to identify the invalid share (and no shares are tracked).
+    pub fn shares(&mut self, dealer: PublicKey, shares: Vec<Share>) -> Result<(), Error> {
+        // Ensure contributor is valid
+        let idx = match self.dealers_ordered.get(&dealer) {
+            Some(contributor) => *contributor,
+            None => return Err(Error::DealerInvalid),
+        };
+
+        // Ensure shares are for us
+        let me = self.recipients_ordered[&self.me];
+        let indices = self.allocation.indices(me);
+        if shares.iter().any(|share| !indices.contains(&share.index)) {
+            return Err(Error::MisdirectedShare);
+        }
+
+        // Verify that shares are valid
+        let commitment = match self.commitments.get(&dealer) {
+            Some(commitment) => commitment.clone(),
+            None => return Err(Error::MissingCommitment),
+        };
+        if ops::verify_shares::<V, _>(
+            &mut OsRng,
+            self.previous.as_ref(),
+            idx,
+            &commitment,
+            self.threshold,
+            &shares,
+        )
+        .is_err()
+        {
+            for share in &shares {
+                ops::verify_share::<V>(
+                    self.previous.as_ref(),
+                    idx,
+                    &commitment,
+                    self.threshold,
+                    share.index,
+                    share,
+                )?;
+            }
+        }
+
+        // Store shares for later use
+        let (stored, stored_shares) = self
+            .valid
+            .entry(idx)
+            .or_insert_with(|| (commitment.clone(), BTreeMap::new()));
+        *stored = commitment;
+        for share in shares {
+            stored_shares.insert(share.index, share);
+        }
         Ok(())
     }
 
-    /// If we are tracking shares for all provided `commitments`, recover
-    /// the new group public polynomial and our share.
-    pub fn finalize(mut self, commitments: Vec<u32>) -> Result<Output, Error> {
+    /// Serializes the state of the contributor (to be resumed with `deserialize`).
+    pub fn serialize(&self) -> Zeroizing<Vec<u8>> {
+        write_state::<V>(
+            codec::CONTRIBUTOR_P2,
+            &self.me,
+            self.threshold,
+            self.previous.as_ref(),
+            self.refresh.as_ref(),
+            self.concurrency,
+            &self.dealers_ordered,
+            &self.recipients_ordered,
+            &self.allocation,
+            &self.commitments,
+            &self.valid,
+        )
+    }
+
+    /// Deserializes the state of a contributor.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let state = read_state::<V>(bytes, codec::CONTRIBUTOR_P2)?;
+        Some(Self {
+            me: state.me,
+            threshold: state.threshold,
+            previous: state.previous,
+            refresh: state.refresh,
+            concurrency: state.concurrency,
+            dealers_ordered: state.dealers_ordered,
+            recipients_ordered: state.recipients_ordered,
+            allocation: state.allocation,
+            commitments: state.commitments,
+            valid: state.valid,
+        })
+    }
+
+    /// If we are tracking shares (at all of our indices) for all provided `commitments`,
+    /// recover the new group public polynomial and our shares.
+    pub fn finalize(mut self, commitments: Vec<u32>) -> Result<Output<V>, Error> {
         // Ensure we have all required shares
+        let indices = self.allocation.indices(self.recipients_ordered[&self.me]);
         for dealer in &commitments {
-            if !self.valid.contains_key(dealer) {
-                return Err(Error::MissingShare);
+            match self.valid.get(dealer) {
+                Some((_, shares)) if shares.len() == indices.len() => {}
+                _ => return Err(Error::MissingShare),
             }
         }
 
@@ -291,7 +774,9 @@ impl P2 {
         let commitments: HashSet<_> = commitments.into_iter().collect();
         for dealer in self.valid.keys().cloned().collect::<Vec<_>>() {
             if !commitments.contains(&dealer) {
-                self.valid.remove(&dealer);
+                if let Some((_, mut shares)) = self.valid.remove(&dealer) {
+                    shares.values_mut().for_each(Zeroize::zeroize);
+                }
             }
         }
 
@@ -302,17 +787,26 @@ impl P2 {
             return Err(Error::InsufficientDealings);
         }
 
-        // Construct secret
-        let mut public = poly::Public::zero();
+        // Construct secrets
+        let mut public = poly::Public::<V>::zero();
         let mut t_commitments = Vec::new();
-        let mut secret = group::Private::zero();
-        match self.previous {
+        let mut secrets = Vec::with_capacity(indices.len());
+        match self.previous.take() {
             None => {
                 // Add all valid commitments/shares
-                for share in self.valid.values() {
-                    public.add(&share.0);
-                    t_commitments.push(share.0.clone());
-                    secret.add(&share.1.private);
+                for (commitment, _) in self.valid.values() {
+                    public.add(commitment);
+                    t_commitments.push(commitment.clone());
+                }
+                for index in indices {
+                    let mut secret = group::Private::zero();
+                    for (_, shares) in self.valid.values() {
+                        secret.add(&shares[&index].private);
+                    }
+                    secrets.push(Share {
+                        index,
+                        private: secret,
+                    });
                 }
             }
             Some(previous) => {
@@ -321,45 +815,194 @@ impl P2 {
                 // While it is tempting to remove this work (given we only need the secret
                 // to generate a threshold signature), this polynomial is required to verify
                 // dealings of future resharings.
-                let commitments: BTreeMap<u32, poly::Public> = self
+                let commitments: BTreeMap<u32, poly::Public<V>> = self
                     .valid
                     .iter()
                     .take(required as usize)
                     .map(|(dealer, (commitment, _))| (*dealer, commitment.clone()))
                     .collect();
                 t_commitments = commitments.values().cloned().collect();
-                public =
-                    ops::recover_public(&previous, commitments, self.threshold, self.concurrency)?;
+                public = ops::recover_public::<V>(
+                    &previous,
+                    commitments,
+                    self.threshold,
+                    self.concurrency,
+                )?;
 
-                // Recover share via interpolation
-                let shares = self
-                    .valid
-                    .into_iter()
-                    .take(required as usize)
-                    .map(|(dealer, (_, share))| Eval {
-                        index: dealer,
-                        value: share.private,
-                    })
-                    .collect::<Vec<_>>();
-                secret = match poly::Private::recover(required, shares) {
-                    Ok(share) => share,
-                    Err(_) => return Err(Error::ShareInterpolationFailed),
-                };
+                // Recover shares via interpolation
+                for index in indices {
+                    let shares = self
+                        .valid
+                        .iter()
+                        .take(required as usize)
+                        .map(|(dealer, (_, shares))| Eval {
+                            index: *dealer,
+                            value: shares[&index].private,
+                        })
+                        .collect::<Vec<_>>();
+                    let secret = match poly::Private::recover(required, shares) {
+                        Ok(share) => share,
+                        Err(_) => return Err(Error::ShareInterpolationFailed),
+                    };
+                    secrets.push(Share {
+                        index,
+                        private: secret,
+                    });
+                }
             }
         }
 
-        // Return the public polynomial and share
+        // If refreshing, offset the previous group polynomial and share
+        if let Some((mut previous, mut share)) = self.refresh.take() {
+            previous.add(&public);
+            public = previous;
+            for secret in secrets.iter_mut() {
+                secret.private.add(&share.private);
+            }
+            share.zeroize();
+        }
+
+        // Return the public polynomial and shares
         Ok(Output {
             public,
             commitments: t_commitments,
-            share: Share {
-                index: self.recipients_ordered[&self.me],
-                private: secret,
-            },
+            shares: secrets,
         })
     }
 }
 
+impl<V: Variant> Drop for P2<V> {
+    fn drop(&mut self) {
+        zeroize_refresh::<V>(&mut self.refresh);
+        zeroize_valid::<V>(&mut self.valid);
+    }
+}
+
+/// Zeroize the share of the previous group polynomial (if refreshing).
+fn zeroize_refresh<V: Variant>(refresh: &mut Option<(poly::Public<V>, Share)>) {
+    if let Some((_, share)) = refresh.as_mut() {
+        share.zeroize();
+    }
+}
+
+/// Zeroize all shares received from dealers.
+fn zeroize_valid<V: Variant>(valid: &mut BTreeMap<u32, (poly::Public<V>, BTreeMap<u32, Share>)>) {
+    for (_, shares) in valid.values_mut() {
+        shares.values_mut().for_each(Zeroize::zeroize);
+    }
+}
+
+/// State shared by `P1` and `P2`.
+struct State<V: Variant> {
+    me: PublicKey,
+    threshold: u32,
+    previous: Option<poly::Public<V>>,
+    refresh: Option<(poly::Public<V>, Share)>,
+    concurrency: usize,
+    dealers_ordered: HashMap<PublicKey, u32>,
+    recipients_ordered: HashMap<PublicKey, u32>,
+    allocation: Allocation,
+    commitments: HashMap<PublicKey, poly::Public<V>>,
+    valid: BTreeMap<u32, (poly::Public<V>, BTreeMap<u32, Share>)>,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_state<V: Variant>(
+    tag: u8,
+    me: &PublicKey,
+    threshold: u32,
+    previous: Option<&poly::Public<V>>,
+    refresh: Option<&(poly::Public<V>, Share)>,
+    concurrency: usize,
+    dealers_ordered: &HashMap<PublicKey, u32>,
+    recipients_ordered: &HashMap<PublicKey, u32>,
+    allocation: &Allocation,
+    commitments: &HashMap<PublicKey, poly::Public<V>>,
+    valid: &BTreeMap<u32, (poly::Public<V>, BTreeMap<u32, Share>)>,
+) -> Zeroizing<Vec<u8>> {
+    let mut writer = Writer::new(tag);
+    writer.public_key(me);
+    writer.u32(threshold);
+    writer.option_poly(previous);
+    write_refresh::<V>(&mut writer, refresh);
+    writer.usize(concurrency);
+    writer.public_keys(&codec::participants(dealers_ordered));
+    writer.public_keys(&codec::participants(recipients_ordered));
+    writer.allocation(allocation);
+    writer.commitments(commitments);
+    writer.u32(valid.len() as u32);
+    for (dealer, (commitment, shares)) in valid {
+        writer.u32(*dealer);
+        writer.poly(commitment);
+        writer.u32(shares.len() as u32);
+        for share in shares.values() {
+            writer.share(share);
+        }
+    }
+    Zeroizing::new(writer.finish())
+}
+
+fn read_state<V: Variant>(bytes: &[u8], tag: u8) -> Option<State<V>> {
+    let mut reader = Reader::new(bytes, tag)?;
+    let me = reader.public_key()?;
+    let threshold = reader.u32()?;
+    let previous = reader.option_poly()?;
+    let refresh = read_refresh::<V>(&mut reader)?;
+    let concurrency = reader.usize()?;
+    let dealers_ordered = codec::ordered(&reader.public_keys()?);
+    let recipients_ordered = codec::ordered(&reader.public_keys()?);
+    let recipient = *recipients_ordered.get(&me)?;
+    let allocation = reader.allocation(recipients_ordered.len())?;
+    let indices = allocation.indices(recipient);
+    let commitments = reader.commitments(&dealers_ordered)?;
+    let mut valid = BTreeMap::new();
+    for _ in 0..reader.u32()? {
+        let dealer = reader.u32()?;
+        if dealer as usize >= dealers_ordered.len() {
+            return None;
+        }
+        let commitment = reader.poly()?;
+        let mut shares = BTreeMap::new();
+        for _ in 0..reader.u32()? {
+            let share = reader.share()?;
+            if !indices.contains(&share.index) || shares.insert(share.index, share).is_some() {
+                return None;
+            }
+        }
+        if valid.insert(dealer, (commitment, shares)).is_some() {
+            return None;
+        }
+    }
+    reader.finish()?;
+    Some(State {
+        me,
+        threshold,
+        previous,
+        refresh,
+        concurrency,
+        dealers_ordered,
+        recipients_ordered,
+        allocation,
+        commitments,
+        valid,
+    })
+}
+
+fn write_refresh<V: Variant>(writer: &mut Writer, refresh: Option<&(poly::Public<V>, Share)>) {
+    writer.bool(refresh.is_some());
+    if let Some((public, share)) = refresh {
+        writer.poly(public);
+        writer.share(share);
+    }
+}
+
+fn read_refresh<V: Variant>(reader: &mut Reader) -> Option<Option<(poly::Public<V>, Share)>> {
+    match reader.bool()? {
+        true => Some(Some((reader.poly()?, reader.share()?))),
+        false => Some(None),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -378,7 +1021,7 @@ mod tests {
         let mut commitments = Vec::new();
         for i in 0..n {
             let me = contributors[i as usize].clone();
-            let contributor = P0::new(
+            let contributor = P0::<MinPk>::new(
                 me,
                 t,
                 None,
@@ -420,7 +1063,7 @@ mod tests {
             for j in 0..n {
                 // Get recipient share
                 let (shares, _) = contributor_shares.get(&i).unwrap();
-                let share = shares[j as usize];
+                let share = shares[j as usize].clone();
 
                 // Send share to recipient
                 let (_, recipient) = contributor_shares.get_mut(&j).unwrap();
@@ -443,7 +1086,7 @@ mod tests {
                     assert_eq!(output.public, *group);
                 }
                 None => {
-                    group = Some(output.public);
+                    group = Some(output.public.clone());
                 }
             }
         }
@@ -0,0 +1,347 @@
+//! Passphrase-encrypted storage of the output of a DKG/Resharing procedure.
+//!
+//! A keystore records everything a contributor needs to participate in threshold signing
+//! (and future resharing) after a round completes: the round, the threshold, the ordered
+//! participants, the group polynomial (and the commitments it was recovered from), and the
+//! contributor's shares (more than one in weighted mode). Everything except the shares is stored
+//! in plaintext (so the contents of a keystore can be inspected without the passphrase) but is
+//! authenticated by the encryption of the shares (so it cannot be modified).
+//!
+//! # Format
+//!
+//! All integers are big-endian and all lists are prefixed with their length (`u32`).
+//!
+//! ```text
+//! magic ("CWDKGKEY") || version (u8)
+//! round (u64) || threshold (u32) || participants (list of length-prefixed public keys)
+//! public (list of points) || commitments (list of lists of points) || indices (list of u32)
+//! memory (u32) || iterations (u32) || parallelism (u32) || salt (16 bytes)
+//! nonce (12 bytes) || encrypted shares (32 bytes per index + 16 bytes)
+//! ```
+//!
+//! The shares are encrypted together with ChaCha20-Poly1305 (using everything that precedes them as
+//! associated data) under a key derived from the passphrase with Argon2id (using the recorded parameters).
+//!
+//! When loading a keystore, each share is verified against the group polynomial (with `ops::verify_share`).
+
+use crate::bls12381::{
+    dkg::{contributor::Output, ops, Error},
+    primitives::{
+        group::{Element, MinPk, Private, Share, Variant},
+        poly,
+    },
+};
+use crate::keystore::{self, Envelope, Reader, TAG_LENGTH};
+use crate::PublicKey;
+use rand::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
+
+pub use crate::keystore::Params;
+
+/// Identifies a keystore.
+const MAGIC: &[u8] = b"CWDKGKEY";
+
+/// Version of the keystore format.
+const VERSION: u8 = 1;
+
+/// Output of a DKG/Resharing procedure and the context it was generated in.
+#[derive(Clone)]
+pub struct Keystore<V: Variant = MinPk> {
+    pub round: u64,
+    pub threshold: u32,
+    pub participants: Vec<PublicKey>,
+    pub output: Output<V>,
+}
+
+impl<V: Variant> Keystore<V> {
+    /// Encrypts the shares under a key derived from `passphrase` and serializes the keystore.
+    pub fn encrypt<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        passphrase: &[u8],
+        params: Params,
+    ) -> Result<Vec<u8>, Error> {
+        // Encode plaintext header
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        bytes.extend_from_slice(&self.round.to_be_bytes());
+        bytes.extend_from_slice(&self.threshold.to_be_bytes());
+        bytes.extend_from_slice(&(self.participants.len() as u32).to_be_bytes());
+        for participant in &self.participants {
+            bytes.extend_from_slice(&(participant.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(participant);
+        }
+        write_poly::<V>(&mut bytes, &self.output.public);
+        bytes.extend_from_slice(&(self.output.commitments.len() as u32).to_be_bytes());
+        for commitment in &self.output.commitments {
+            write_poly::<V>(&mut bytes, commitment);
+        }
+        bytes.extend_from_slice(&(self.output.shares.len() as u32).to_be_bytes());
+        for share in &self.output.shares {
+            bytes.extend_from_slice(&share.index.to_be_bytes());
+        }
+        let envelope = Envelope::new(rng, params);
+        envelope.write(&mut bytes);
+
+        // Encrypt shares
+        let mut private = Zeroizing::new(Vec::with_capacity(
+            self.output.shares.len() * Private::size(),
+        ));
+        for share in &self.output.shares {
+            private.extend_from_slice(&share.private.serialize());
+        }
+        let ciphertext = envelope
+            .seal(passphrase, &bytes, &private)
+            .map_err(map_error)?;
+        bytes.extend_from_slice(&ciphertext);
+        Ok(bytes)
+    }
+
+    /// Deserializes a keystore, decrypts the shares, and verifies that each share is on
+    /// the group polynomial.
+    pub fn decrypt(bytes: &[u8], passphrase: &[u8]) -> Result<Self, Error> {
+        // Decode plaintext header
+        let mut reader = Reader::new(bytes, || Error::InvalidKeystore);
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(Error::InvalidKeystore);
+        }
+        if reader.u8()? != VERSION {
+            return Err(Error::UnsupportedKeystoreVersion);
+        }
+        let round = reader.u64()?;
+        let threshold = reader.u32()?;
+        let mut participants = Vec::new();
+        for _ in 0..reader.u32()? {
+            let len = reader.u32()? as usize;
+            participants.push(PublicKey::copy_from_slice(reader.take(len)?));
+        }
+        let public = read_poly::<V>(&mut reader)?;
+        let mut commitments = Vec::new();
+        for _ in 0..reader.u32()? {
+            commitments.push(read_poly::<V>(&mut reader)?);
+        }
+        let mut indices: Vec<u32> = Vec::new();
+        for _ in 0..reader.u32()? {
+            let index = reader.u32()?;
+            if indices.last().is_some_and(|last| *last >= index) {
+                return Err(Error::InvalidKeystore);
+            }
+            indices.push(index);
+        }
+        if indices.is_empty() {
+            return Err(Error::InvalidKeystore);
+        }
+        let envelope = Envelope::read(&mut reader)?;
+        let aad = reader.read();
+        let ciphertext = reader.take(indices.len() * Private::size() + TAG_LENGTH)?;
+        reader.finish()?;
+
+        // Decrypt shares
+        let private = envelope
+            .open(passphrase, aad, ciphertext)
+            .map_err(map_error)?;
+        let mut shares = Vec::with_capacity(indices.len());
+        for (index, private) in indices
+            .into_iter()
+            .zip(private.chunks_exact(Private::size()))
+        {
+            let private = Private::deserialize(private).ok_or(Error::InvalidKeystore)?;
+            let share = Share { index, private };
+
+            // Verify share is on the group polynomial
+            ops::verify_share::<V>(None, 0, &public, threshold, index, &share)?;
+            shares.push(share);
+        }
+        Ok(Self {
+            round,
+            threshold,
+            participants,
+            output: Output {
+                public,
+                commitments,
+                shares,
+            },
+        })
+    }
+}
+
+fn write_poly<V: Variant>(bytes: &mut Vec<u8>, poly: &poly::Public<V>) {
+    bytes.extend_from_slice(&poly.required().to_be_bytes());
+    bytes.extend_from_slice(&poly.serialize());
+}
+
+fn read_poly<V: Variant>(reader: &mut Reader<'_, Error>) -> Result<poly::Public<V>, Error> {
+    let required = reader.u32()?;
+    let len = (required as usize)
+        .checked_mul(V::Public::size())
+        .ok_or(Error::InvalidKeystore)?;
+    poly::Public::<V>::deserialize(reader.take(len)?, required).ok_or(Error::InvalidKeystore)
+}
+
+fn map_error(e: keystore::Error) -> Error {
+    match e {
+        keystore::Error::InvalidParams => Error::InvalidKeystoreParams,
+        _ => Error::KeystoreDecryptionFailed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bls12381::primitives::group::{MinSig, Scalar},
+        ed25519::insecure_signer,
+        Scheme,
+    };
+    use rand::thread_rng;
+
+    /// Cheap parameters (to keep tests fast).
+    const PARAMS: Params = Params {
+        memory: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn keystore<V: Variant>() -> Keystore<V> {
+        let (n, t) = (4, 3);
+        let mut participants = (0..n)
+            .map(|i| insecure_signer(i as u16).me())
+            .collect::<Vec<_>>();
+        participants.sort();
+        let (public, shares) = ops::generate_shares::<V>(None, n, t);
+        Keystore {
+            round: 7,
+            threshold: t,
+            participants,
+            output: Output {
+                public: public.clone(),
+                commitments: vec![public],
+                shares: vec![shares[2].clone()],
+            },
+        }
+    }
+
+    fn encrypt_and_decrypt<V: Variant>() {
+        let mut rng = thread_rng();
+        let keystore = keystore::<V>();
+        let bytes = keystore.encrypt(&mut rng, b"passphrase", PARAMS).unwrap();
+        let loaded = Keystore::<V>::decrypt(&bytes, b"passphrase").unwrap();
+        assert_eq!(loaded.round, keystore.round);
+        assert_eq!(loaded.threshold, keystore.threshold);
+        assert_eq!(loaded.participants, keystore.participants);
+        assert_eq!(loaded.output.public, keystore.output.public);
+        assert_eq!(loaded.output.commitments, keystore.output.commitments);
+        assert!(loaded.output.shares == keystore.output.shares);
+
+        // Wrong passphrase
+        assert!(matches!(
+            Keystore::<V>::decrypt(&bytes, b"wrong"),
+            Err(Error::KeystoreDecryptionFailed)
+        ));
+    }
+
+    #[test]
+    fn test_encrypt_and_decrypt() {
+        encrypt_and_decrypt::<MinPk>();
+    }
+
+    #[test]
+    fn test_encrypt_and_decrypt_min_sig() {
+        encrypt_and_decrypt::<MinSig>();
+    }
+
+    #[test]
+    fn test_tampered() {
+        let mut rng = thread_rng();
+        let bytes = keystore::<MinPk>()
+            .encrypt(&mut rng, b"passphrase", PARAMS)
+            .unwrap();
+
+        // Modify the round (authenticated)
+        let mut tampered = bytes.clone();
+        tampered[MAGIC.len() + 1] ^= 1;
+        assert!(matches!(
+            Keystore::<MinPk>::decrypt(&tampered, b"passphrase"),
+            Err(Error::KeystoreDecryptionFailed)
+        ));
+
+        // Unknown version
+        let mut tampered = bytes.clone();
+        tampered[MAGIC.len()] += 1;
+        assert!(matches!(
+            Keystore::<MinPk>::decrypt(&tampered, b"passphrase"),
+            Err(Error::UnsupportedKeystoreVersion)
+        ));
+
+        // Not a keystore
+        let mut tampered = bytes.clone();
+        tampered[0] ^= 1;
+        assert!(matches!(
+            Keystore::<MinPk>::decrypt(&tampered, b"passphrase"),
+            Err(Error::InvalidKeystore)
+        ));
+
+        // Truncated
+        assert!(matches!(
+            Keystore::<MinPk>::decrypt(&bytes[..bytes.len() - 1], b"passphrase"),
+            Err(Error::InvalidKeystore)
+        ));
+
+        // Wrong variant
+        assert!(matches!(
+            Keystore::<MinSig>::decrypt(&bytes, b"passphrase"),
+            Err(Error::InvalidKeystore)
+        ));
+    }
+
+    #[test]
+    fn test_share_not_on_commitment() {
+        let mut rng = thread_rng();
+        let mut keystore = keystore::<MinPk>();
+        keystore.output.shares[0].private = Scalar::rand(&mut rng);
+        let bytes = keystore.encrypt(&mut rng, b"passphrase", PARAMS).unwrap();
+        assert!(matches!(
+            Keystore::<MinPk>::decrypt(&bytes, b"passphrase"),
+            Err(Error::ShareWrongCommitment)
+        ));
+    }
+
+    #[test]
+    fn test_excessive_params() {
+        let mut rng = thread_rng();
+        let keystore = keystore::<MinPk>();
+        let params = Params {
+            memory: crate::keystore::MAX_MEMORY + 1,
+            ..PARAMS
+        };
+        assert!(matches!(
+            keystore.encrypt(&mut rng, b"passphrase", params),
+            Err(Error::InvalidKeystoreParams)
+        ));
+
+        // Keystores with excessive parameters are rejected before deriving a key
+        let mut bytes = keystore.encrypt(&mut rng, b"passphrase", PARAMS).unwrap();
+        let offset = bytes.len() - 16 - 12 - Private::size() - TAG_LENGTH - 12;
+        assert_eq!(bytes[offset..offset + 4], PARAMS.memory.to_be_bytes());
+        bytes[offset..offset + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(
+            Keystore::<MinPk>::decrypt(&bytes, b"passphrase"),
+            Err(Error::InvalidKeystoreParams)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let mut rng = thread_rng();
+        let params = Params {
+            memory: 0,
+            iterations: 0,
+            parallelism: 0,
+        };
+        assert!(matches!(
+            keystore::<MinPk>().encrypt(&mut rng, b"passphrase", params),
+            Err(Error::InvalidKeystoreParams)
+        ));
+    }
+}
@@ -132,14 +132,67 @@
 //! reshare). Like above, the contributor will recover the group polynomial. Unlike above, the
 //! contributor will also recover its new share of the secret (rather than just adding all shares together).
 //!
+//! # Persistence
+//!
+//! Every phase of the arbiter and contributor (and their outputs) can be serialized (with `serialize`)
+//! and later resumed (with `deserialize`), so that a participant can checkpoint its state to disk and
+//! recover from a crash without abandoning the round. The encoding is prefixed with a version (and the
+//! type of the encoded state) and `deserialize` rejects any encoding it does not understand. Dealers
+//! should wrap the commitment and shares returned by `contributor::P0::finalize` in a `contributor::Dealing`
+//! and persist it before sending their commitment to the arbiter (otherwise they will be unable to distribute
+//! shares consistent with it).
+//!
+//! Contributor state contains secrets and is returned in a buffer that is zeroized when dropped.
+//!
+//! Once a round completes, the `contributor::Output` should be stored in a passphrase-encrypted
+//! [keystore] (rather than with the encoding above).
+//!
+//! # Publicly Verifiable Mode
+//!
+//! When the arbiter is a blockchain, it can be configured (via `arbiter::P0::require_encrypted_shares`)
+//! to instead have each dealer publish its shares encrypted to all recipients (alongside a proof that each
+//! ciphertext decrypts to a share consistent with the dealer's commitment). In this mode, each recipient
+//! registers an encryption key with the arbiter during Phase 0 (any recipient that does not is disqualified).
+//! During Phase 1, each dealer posts its encrypted shares (see [pvss]) to the arbiter. If the dealing is valid,
+//! it counts as an ack from every recipient. If it is invalid, the dealer is disqualified. Because every valid
+//! dealing is acknowledged by construction, no complaints are required and no reveals will be requested (for
+//! dealers that post a dealing).
+//!
+//! Recipients recover their shares by decrypting them from the posted dealings (rather than receiving them
+//! directly from each dealer).
+//!
+//! # Weighted Mode
+//!
+//! When participants are stake-weighted, the arbiter and contributors can be constructed with
+//! `new_weighted` (instead of `new`). Each recipient then owns as many share indices as its weight and the
+//! threshold is expressed in weight. Dealers send each recipient all of the shares it owns and a recipient
+//! acks (or complains about) a dealer once for all of them. After the round, each recipient signs with all
+//! of its shares and partial signatures are aggregated per signer (see [weighted]).
+//!
+//! # Refresh Mode
+//!
+//! To periodically re-randomize shares without changing the participants (or the group secret), the arbiter and
+//! contributors can be constructed with `new_refresh` (instead of `new`). Every participant deals shares of zero
+//! and, once the round completes, adds the shares it received to its existing share (and the included commitments
+//! to the existing group polynomial). Shares from before a refresh cannot be combined with shares from after it,
+//! so any shares leaked before the refresh become useless.
+//!
+//! The constant term of each refresh commitment is the identity (which cannot be encoded as a point), so refresh
+//! commitments should be sent with `ops::serialize_refresh_commitment` (rather than `poly::Public::serialize`).
+//!
 //! # Example
 //!
 //! For a complete example of how to instantiate this crate, checkout [commonware-vrf](https://docs.rs/commonware-vrf).
 
 pub mod arbiter;
+mod codec;
 pub mod contributor;
+pub mod keystore;
 pub mod ops;
+pub mod pvss;
+pub mod simulation;
 pub mod utils;
+pub mod weighted;
 
 #[derive(Debug)]
 pub enum Error {
@@ -163,6 +216,21 @@ pub enum Error {
     SelfComplaint,
     DuplicateCommitment,
     DuplicateAck,
+    MissingProofOfPossession,
+    InvalidProofOfPossession,
+    DuplicateProofOfPossession,
+    DuplicateEncryptionKey,
+    UnexpectedDealing,
+    UnexpectedAck,
+    UnexpectedComplaint,
+    DuplicateDealing,
+    InvalidDealing,
+    ShareDecryptionFailed,
+    InvalidKeystore,
+    InvalidKeystoreParams,
+    UnsupportedKeystoreVersion,
+    KeystoreDecryptionFailed,
+    InsufficientWeight,
 }
 
 impl std::fmt::Display for Error {
@@ -188,6 +256,21 @@ impl std::fmt::Display for Error {
             Error::SelfComplaint => write!(f, "self complaint"),
             Error::DuplicateCommitment => write!(f, "duplicate commitment"),
             Error::DuplicateAck => write!(f, "duplicate ack"),
+            Error::MissingProofOfPossession => write!(f, "missing proof of possession"),
+            Error::InvalidProofOfPossession => write!(f, "invalid proof of possession"),
+            Error::DuplicateProofOfPossession => write!(f, "duplicate proof of possession"),
+            Error::DuplicateEncryptionKey => write!(f, "duplicate encryption key"),
+            Error::UnexpectedDealing => write!(f, "unexpected dealing"),
+            Error::UnexpectedAck => write!(f, "unexpected ack"),
+            Error::UnexpectedComplaint => write!(f, "unexpected complaint"),
+            Error::DuplicateDealing => write!(f, "duplicate dealing"),
+            Error::InvalidDealing => write!(f, "invalid dealing"),
+            Error::ShareDecryptionFailed => write!(f, "share decryption failed"),
+            Error::InvalidKeystore => write!(f, "invalid keystore"),
+            Error::InvalidKeystoreParams => write!(f, "invalid keystore params"),
+            Error::UnsupportedKeystoreVersion => write!(f, "unsupported keystore version"),
+            Error::KeystoreDecryptionFailed => write!(f, "keystore decryption failed"),
+            Error::InsufficientWeight => write!(f, "insufficient weight"),
         }
     }
 }
@@ -198,11 +281,15 @@ impl std::error::Error for Error {}
 mod tests {
     use super::*;
     use crate::bls12381::dkg::{arbiter, contributor};
-    use crate::bls12381::primitives::group::Private;
-    use crate::{ed25519::insecure_signer, Scheme};
-    use std::collections::HashMap;
-
-    fn run_dkg_and_reshare(
+    use crate::bls12381::primitives::{
+        group::{Element, MinPk, MinSig, Private, Share, Variant, G1},
+        ops::{aggregate, partial_sign, verify},
+        poly,
+    };
+    use crate::{bls12381::scheme as bls12381, ed25519::insecure_signer, PublicKey, Scheme};
+    use std::collections::{BTreeMap, HashMap};
+
+    fn run_dkg_and_reshare<V: Variant>(
         n_0: u32,
         t_0: u32,
         dealers_0: u32,
@@ -224,7 +311,7 @@ mod tests {
         let mut contributor_cons = HashMap::new();
         for con in &contributors {
             let me = con.clone();
-            let p0 = contributor::P0::new(
+            let p0 = contributor::P0::<V>::new(
                 me,
                 t_0,
                 None,
@@ -238,7 +325,7 @@ mod tests {
         }
 
         // Inform arbiter of commitments
-        let mut arb = arbiter::P0::new(
+        let mut arb = arbiter::P0::<V>::new(
             t_0,
             None,
             contributors.clone(),
@@ -285,7 +372,7 @@ mod tests {
         for (dealer, dealer_key, _) in arb.commitments().iter() {
             for (idx, recipient) in contributors.iter().enumerate() {
                 let (_, shares) = contributor_shares.get(dealer_key).unwrap().clone();
-                let share = shares[idx];
+                let share = shares[idx].clone();
                 contributor_cons
                     .get_mut(recipient)
                     .unwrap()
@@ -357,10 +444,10 @@ mod tests {
         let mut reshare_contributor_shares = HashMap::new();
         for contributor in contributors.iter() {
             let output = results.get(contributor).unwrap();
-            let p0 = contributor::P0::new(
+            let p0 = contributor::P0::<V>::new(
                 contributor.clone(),
                 t_1,
-                Some((output.public.clone(), output.share)),
+                Some((output.public.clone(), output.share().unwrap().clone())),
                 reshare_dealers.clone(),
                 reshare_recipients.clone(),
                 concurrency,
@@ -372,7 +459,7 @@ mod tests {
 
         let mut reshare_contributor_cons = HashMap::new();
         for con in &reshare_recipients {
-            let p1 = contributor::P1::new(
+            let p1 = contributor::P1::<V>::new(
                 con.clone(),
                 t_1,
                 Some(output.public.clone()),
@@ -384,7 +471,7 @@ mod tests {
         }
 
         // Inform arbiter of commitments
-        let mut arb = arbiter::P0::new(
+        let mut arb = arbiter::P0::<V>::new(
             t_1,
             Some(output.public.clone()),
             reshare_dealers.clone(),
@@ -434,7 +521,7 @@ mod tests {
                 reshare_contributor_cons
                     .get_mut(recipient)
                     .unwrap()
-                    .share(dealer_key.clone(), shares[idx])
+                    .share(dealer_key.clone(), shares[idx].clone())
                     .unwrap();
 
                 // Skip ack for self
@@ -470,6 +557,7 @@ mod tests {
         let output = result.unwrap();
 
         // Distribute final commitments to contributors and recover public key
+        let mut partials = Vec::new();
         for contributor in reshare_recipients.iter() {
             let result = reshare_contributor_cons
                 .remove(contributor)
@@ -477,28 +565,39 @@ mod tests {
                 .finalize(output.commitments.clone())
                 .unwrap();
             assert_eq!(result.public, output.public);
+            partials.push(partial_sign::<V>(result.share().unwrap(), b"test"));
         }
+
+        // Generate threshold signature over the reshared key
+        let signature = aggregate::<V>(t_1, partials).unwrap();
+        verify::<V>(&poly::public::<V>(&output.public), b"test", &signature)
+            .expect("signature should be valid");
     }
 
     #[test]
     fn test_dkg_and_reshare_all_active() {
-        run_dkg_and_reshare(5, 3, 5, 10, 7, 5, 4);
+        run_dkg_and_reshare::<MinPk>(5, 3, 5, 10, 7, 5, 4);
     }
 
     #[test]
     fn test_dkg_and_reshare_min_active() {
-        run_dkg_and_reshare(5, 3, 3, 10, 7, 3, 4);
+        run_dkg_and_reshare::<MinPk>(5, 3, 3, 10, 7, 3, 4);
     }
 
     #[test]
     fn test_dkg_and_reshare_all_active_large() {
-        run_dkg_and_reshare(20, 13, 15, 30, 21, 15, 4);
+        run_dkg_and_reshare::<MinPk>(20, 13, 15, 30, 21, 15, 4);
     }
 
     #[test]
     #[should_panic]
     fn test_dkg_and_reshare_insufficient_active() {
-        run_dkg_and_reshare(5, 3, 3, 10, 7, 2, 4);
+        run_dkg_and_reshare::<MinPk>(5, 3, 3, 10, 7, 2, 4);
+    }
+
+    #[test]
+    fn test_dkg_and_reshare_min_sig() {
+        run_dkg_and_reshare::<MinSig>(5, 3, 5, 10, 7, 5, 4);
     }
 
     fn run_dkg_reveal(defiant: bool) {
@@ -516,7 +615,7 @@ mod tests {
         let mut contributor_shares = HashMap::new();
         let mut contributor_cons = HashMap::new();
         for con in &contributors {
-            let p0 = contributor::P0::new(
+            let p0 = contributor::P0::<MinPk>::new(
                 con.clone(),
                 t,
                 None,
@@ -530,7 +629,8 @@ mod tests {
         }
 
         // Inform arbiter of commitments
-        let mut arb = arbiter::P0::new(t, None, contributors.clone(), contributors.clone(), 1);
+        let mut arb =
+            arbiter::P0::<MinPk>::new(t, None, contributors.clone(), contributors.clone(), 1);
         for contributor in contributors.iter() {
             let (public, _) = contributor_shares.get(contributor).unwrap();
             arb.commitment(contributor.clone(), public.clone()).unwrap();
@@ -571,7 +671,7 @@ mod tests {
                 contributor_cons
                     .get_mut(recipient)
                     .unwrap()
-                    .share(dealer_key.clone(), shares[idx])
+                    .share(dealer_key.clone(), shares[idx].clone())
                     .unwrap();
 
                 // Purposely skip ack
@@ -602,8 +702,8 @@ mod tests {
         // Reval missing share
         if !defiant {
             let dealer = contributors[0].clone();
-            let share = contributor_shares.get(&dealer).unwrap().1[1];
-            arb.reveal(dealer.clone(), share).unwrap();
+            let share = contributor_shares.get(&dealer).unwrap().1[1].clone();
+            arb.reveal(dealer.clone(), share.clone()).unwrap();
 
             // Recover public key on arbiter
             let (result, disqualified) = arb.finalize();
@@ -614,7 +714,7 @@ mod tests {
             for (idx, contributor) in contributors.iter().enumerate() {
                 let mut contributor = contributor_cons.remove(contributor).unwrap();
                 if idx == 1 {
-                    contributor.share(dealer.clone(), share).unwrap();
+                    contributor.share(dealer.clone(), share.clone()).unwrap();
                 }
                 let result = contributor.finalize(output.commitments.clone()).unwrap();
                 assert_eq!(result.public, output.public);
@@ -651,6 +751,91 @@ mod tests {
         run_dkg_reveal(true);
     }
 
+    #[test]
+    fn test_dkg_reveal_invalid() {
+        let (n, t) = (7, 5);
+
+        // Create contributors (must be in sorted order)
+        let mut contributors = Vec::new();
+        for i in 0..n {
+            let signer = insecure_signer(i as u16).me();
+            contributors.push(signer);
+        }
+        contributors.sort();
+
+        // Inform arbiter of commitments
+        let mut contributor_shares = HashMap::new();
+        let mut arb =
+            arbiter::P0::<MinPk>::new(t, None, contributors.clone(), contributors.clone(), 1);
+        for con in &contributors {
+            let (_, public, shares) = contributor::P0::<MinPk>::new(
+                con.clone(),
+                t,
+                None,
+                contributors.clone(),
+                contributors.clone(),
+                1,
+            )
+            .finalize();
+            arb.commitment(con.clone(), public).unwrap();
+            contributor_shares.insert(con.clone(), shares);
+        }
+        let (result, disqualified) = arb.finalize();
+        assert!(disqualified.is_empty());
+        let mut arb = result.unwrap();
+
+        // Dealers 0 and 1 are not acked by recipients 0, 1, and 2
+        for (dealer, dealer_key, _) in arb.commitments() {
+            for (idx, recipient) in contributors.iter().enumerate() {
+                if *recipient == dealer_key || (dealer < 2 && idx < 3) {
+                    continue;
+                }
+                arb.ack(recipient.clone(), dealer).unwrap();
+            }
+        }
+        let (result, disqualified) = arb.finalize();
+        assert!(disqualified.is_empty());
+        let (mut arb, mut requests) = result.unwrap();
+        requests.sort();
+        assert_eq!(requests, vec![(0, 1), (0, 2), (1, 0), (1, 2)]);
+
+        // Dealer 0 reveals valid shares and dealer 1 reveals an invalid share (which immediately
+        // disqualifies it)
+        for (dealer, recipient) in requests {
+            let dealer_key = contributors[dealer as usize].clone();
+            let mut share =
+                contributor_shares.get(&dealer_key).unwrap()[recipient as usize].clone();
+            match (dealer, recipient) {
+                (1, 0) => {
+                    share.private = contributor_shares.get(&contributors[0]).unwrap()[0].private;
+                    assert!(matches!(
+                        arb.reveal(dealer_key, share),
+                        Err(Error::ShareWrongCommitment)
+                    ));
+                }
+                (1, _) => {
+                    assert!(matches!(
+                        arb.reveal(dealer_key, share),
+                        Err(Error::ContributorDisqualified)
+                    ));
+                }
+                _ => arb.reveal(dealer_key, share).unwrap(),
+            }
+        }
+
+        // Ensure dealer that revealed an invalid share is disqualified
+        let (result, disqualified) = arb.finalize();
+        assert_eq!(disqualified.len(), 1);
+        assert!(disqualified.contains(&contributors[1]));
+        let output = result.unwrap();
+        let mut commitments = output.commitments.clone();
+        commitments.sort();
+        assert_eq!(commitments, vec![0, 2, 3, 4, 5, 6]);
+        let mut resolutions = output.resolutions.keys().cloned().collect::<Vec<_>>();
+        resolutions.sort();
+        assert_eq!(resolutions, vec![(0, 1), (0, 2)]);
+    }
+
     #[test]
     fn test_dkg_complaint() {
         let (n, t) = (5, 4);
@@ -668,7 +853,7 @@ mod tests {
         let mut contributor_cons = HashMap::new();
         for con in &contributors {
             // Generate private key
-            let p0 = contributor::P0::new(
+            let p0 = contributor::P0::<MinPk>::new(
                 con.clone(),
                 t,
                 None,
@@ -689,7 +874,8 @@ mod tests {
         }
 
         // Inform arbiter of commitments
-        let mut arb = arbiter::P0::new(t, None, contributors.clone(), contributors.clone(), 1);
+        let mut arb =
+            arbiter::P0::<MinPk>::new(t, None, contributors.clone(), contributors.clone(), 1);
         for contributor in contributors.iter() {
             let (public, _) = contributor_shares.get(contributor).unwrap();
             arb.commitment(contributor.clone(), public.clone()).unwrap();
@@ -727,11 +913,11 @@ mod tests {
         for (dealer, dealer_key, _) in arb.commitments().iter() {
             for (idx, recipient) in contributors.iter().enumerate() {
                 let (_, shares) = contributor_shares.get(dealer_key).unwrap().clone();
-                let share = shares[idx];
+                let share = shares[idx].clone();
                 match contributor_cons
                     .get_mut(recipient)
                     .unwrap()
-                    .share(dealer_key.clone(), share)
+                    .share(dealer_key.clone(), share.clone())
                 {
                     Err(Error::ShareWrongCommitment) => {}
                     _ => {
@@ -747,4 +933,948 @@ mod tests {
         assert!(result.is_none());
         assert!(disqualified.len() == n as usize);
     }
+
+    #[test]
+    fn test_dkg_proof_of_possession() {
+        let (n, t) = (5, 4);
+
+        // Create contributors with BLS12-381 identities (must be in sorted order)
+        let mut signers = (0..n)
+            .map(|i| bls12381::insecure_signer(i as u16))
+            .collect::<Vec<_>>();
+        signers.sort_by_key(|s| s.me());
+        let contributors = signers.iter().map(|s| s.me()).collect::<Vec<_>>();
+
+        // Create commitments
+        let mut commitments = HashMap::new();
+        for con in &contributors {
+            let p0 = contributor::P0::<MinPk>::new(
+                con.clone(),
+                t,
+                None,
+                contributors.clone(),
+                contributors.clone(),
+                1,
+            );
+            let (_, public, _) = p0.finalize();
+            commitments.insert(con.clone(), public);
+        }
+
+        // Require proofs of possession
+        let mut arb =
+            arbiter::P0::<MinPk>::new(t, None, contributors.clone(), contributors.clone(), 1);
+        arb.require_proof_of_possession();
+
+        // Reject commitment without proof
+        let commitment = commitments.get(&contributors[0]).unwrap().clone();
+        assert!(matches!(
+            arb.commitment(contributors[0].clone(), commitment.clone()),
+            Err(Error::MissingProofOfPossession)
+        ));
+
+        // Accept commitment after proof
+        arb.proof_of_possession(contributors[0].clone(), signers[0].proof_of_possession())
+            .unwrap();
+        assert!(matches!(
+            arb.proof_of_possession(contributors[0].clone(), signers[0].proof_of_possession()),
+            Err(Error::DuplicateProofOfPossession)
+        ));
+        arb.commitment(contributors[0].clone(), commitment).unwrap();
+
+        // Disqualify dealer that presents a proof for another key
+        assert!(matches!(
+            arb.proof_of_possession(contributors[1].clone(), signers[2].proof_of_possession()),
+            Err(Error::InvalidProofOfPossession)
+        ));
+        let commitment = commitments.get(&contributors[1]).unwrap().clone();
+        assert!(matches!(
+            arb.commitment(contributors[1].clone(), commitment),
+            Err(Error::ContributorDisqualified)
+        ));
+
+        // Remaining dealers present valid proofs
+        for (signer, contributor) in signers.iter().zip(contributors.iter()).skip(2) {
+            arb.proof_of_possession(contributor.clone(), signer.proof_of_possession())
+                .unwrap();
+            let commitment = commitments.get(contributor).unwrap().clone();
+            arb.commitment(contributor.clone(), commitment).unwrap();
+        }
+        let (result, disqualified) = arb.finalize();
+        assert!(result.is_some());
+        assert_eq!(disqualified.len(), 1);
+        assert!(disqualified.contains(&contributors[1]));
+    }
+
+    #[test]
+    fn test_dkg_encrypted_shares() {
+        let (n, t) = (5, 3);
+        let mut rng = rand::thread_rng();
+
+        // Create contributors (must be in sorted order)
+        let mut contributors = Vec::new();
+        for i in 0..n {
+            let signer = insecure_signer(i as u16).me();
+            contributors.push(signer);
+        }
+        contributors.sort();
+
+        // Create shares and encryption keys
+        let mut contributor_shares = HashMap::new();
+        let mut contributor_cons = HashMap::new();
+        let mut contributor_keys = HashMap::new();
+        for con in &contributors {
+            let p0 = contributor::P0::<MinPk>::new(
+                con.clone(),
+                t,
+                None,
+                contributors.clone(),
+                contributors.clone(),
+                1,
+            );
+            let (p1, public, shares) = p0.finalize();
+            contributor_shares.insert(con.clone(), (public, shares));
+            contributor_cons.insert(con.clone(), p1.unwrap());
+            contributor_keys.insert(con.clone(), pvss::keypair(&mut rng));
+        }
+
+        // Inform arbiter of encryption keys (except the last contributor) and commitments
+        let mut arb =
+            arbiter::P0::<MinPk>::new(t, None, contributors.clone(), contributors.clone(), 1);
+        arb.require_encrypted_shares();
+        for contributor in contributors.iter().take(n as usize - 1) {
+            let (_, key) = contributor_keys.get(contributor).unwrap();
+            arb.encryption_key(contributor.clone(), *key).unwrap();
+        }
+        let (_, key) = contributor_keys.get(&contributors[0]).unwrap();
+        assert!(matches!(
+            arb.encryption_key(contributors[0].clone(), *key),
+            Err(Error::DuplicateEncryptionKey)
+        ));
+        for contributor in contributors.iter() {
+            let (public, _) = contributor_shares.get(contributor).unwrap();
+            arb.commitment(contributor.clone(), public.clone()).unwrap();
+        }
+        let (result, disqualified) = arb.finalize();
+
+        // Verify disqualifications
+        assert_eq!(disqualified.len(), 1);
+        assert!(disqualified.contains(&contributors[4]));
+        let mut arb = result.unwrap();
+
+        // Send select commitments to contributors
+        for (_, dealer, commitment) in arb.commitments().iter() {
+            for contributor in contributors.iter() {
+                contributor_cons
+                    .get_mut(contributor)
+                    .unwrap()
+                    .commitment(dealer.clone(), commitment.clone())
+                    .unwrap();
+            }
+        }
+
+        // Finalize contributor P1
+        let mut p2 = HashMap::new();
+        for contributor in contributors.iter() {
+            let output = contributor_cons
+                .remove(contributor)
+                .unwrap()
+                .finalize()
+                .unwrap();
+            p2.insert(contributor.clone(), output);
+        }
+        let mut contributor_cons = p2;
+
+        // Post encrypted shares to the arbiter (the second dealer corrupts a share)
+        let mut dealings = HashMap::new();
+        for (dealer, dealer_key, commitment) in arb.commitments().iter() {
+            let (_, shares) = contributor_shares.get(dealer_key).unwrap();
+            let mut encrypted = Vec::new();
+            for (idx, recipient) in contributors.iter().enumerate().take(n as usize - 1) {
+                if recipient == dealer_key {
+                    continue;
+                }
+                let mut share = shares[idx].clone();
+                if *dealer == 1 && idx == 0 {
+                    share.private = Private::rand(&mut rng);
+                }
+                let (_, key) = contributor_keys.get(recipient).unwrap();
+                encrypted.push(pvss::encrypt::<MinPk, _>(
+                    &mut rng, *dealer, commitment, &share, key,
+                ));
+            }
+            let result = arb.dealing(dealer_key.clone(), &encrypted);
+            if *dealer == 1 {
+                assert!(matches!(result, Err(Error::InvalidDealing)));
+                continue;
+            }
+            result.unwrap();
+            assert!(matches!(
+                arb.dealing(dealer_key.clone(), &encrypted),
+                Err(Error::DuplicateDealing)
+            ));
+            dealings.insert(dealer_key.clone(), encrypted);
+        }
+
+        // Finalize arb P1 (without any acks or complaints)
+        let (result, disqualified) = arb.finalize();
+        assert_eq!(disqualified.len(), 2);
+        assert!(disqualified.contains(&contributors[1]));
+        let (arb, requests) = result.unwrap();
+        assert!(requests.is_empty());
+        let (result, _) = arb.finalize();
+        let output = result.unwrap();
+        let mut commitments = output.commitments.clone();
+        commitments.sort();
+        assert_eq!(commitments, vec![0, 2, 3]);
+
+        // Decrypt shares and recover public key and shares on contributors
+        let mut partials = Vec::new();
+        for (idx, contributor) in contributors.iter().enumerate().take(n as usize - 1) {
+            let mut p2 = contributor_cons.remove(contributor).unwrap();
+            let (decryption, _) = contributor_keys.get(contributor).unwrap();
+            for dealer in output.commitments.iter() {
+                let dealer_key = &contributors[*dealer as usize];
+                let share = if dealer_key == contributor {
+                    contributor_shares.get(dealer_key).unwrap().1[idx].clone()
+                } else {
+                    let encrypted = dealings
+                        .get(dealer_key)
+                        .unwrap()
+                        .iter()
+                        .find(|encrypted| encrypted.index == idx as u32)
+                        .unwrap();
+                    pvss::decrypt(decryption, encrypted).unwrap()
+                };
+                p2.share(dealer_key.clone(), share).unwrap();
+            }
+            let result = p2.finalize(output.commitments.clone()).unwrap();
+            assert_eq!(result.public, output.public);
+            partials.push(partial_sign::<MinPk>(result.share().unwrap(), b"test"));
+        }
+
+        // Verify the recovered shares can generate a threshold signature
+        let signature = aggregate::<MinPk>(t, partials).unwrap();
+        verify::<MinPk>(&poly::public::<MinPk>(&output.public), b"test", &signature).unwrap();
+    }
+
+    #[test]
+    fn test_dkg_unexpected_dealing() {
+        let (n, t) = (4, 3);
+        let mut contributors = Vec::new();
+        for i in 0..n {
+            let signer = insecure_signer(i as u16).me();
+            contributors.push(signer);
+        }
+        contributors.sort();
+
+        // Encrypted shares are rejected unless required
+        let mut arb =
+            arbiter::P0::<MinPk>::new(t, None, contributors.clone(), contributors.clone(), 1);
+        for con in &contributors {
+            let (_, commitment, _) = contributor::P0::<MinPk>::new(
+                con.clone(),
+                t,
+                None,
+                contributors.clone(),
+                contributors.clone(),
+                1,
+            )
+            .finalize();
+            arb.commitment(con.clone(), commitment).unwrap();
+        }
+        let (result, _) = arb.finalize();
+        let mut arb = result.unwrap();
+        assert!(matches!(
+            arb.dealing(contributors[0].clone(), &[]),
+            Err(Error::UnexpectedDealing)
+        ));
+    }
+
+    #[test]
+    fn test_dkg_encrypted_shares_missing_dealing() {
+        let (n, t) = (4, 3);
+        let mut rng = rand::thread_rng();
+        let mut contributors = Vec::new();
+        for i in 0..n {
+            let signer = insecure_signer(i as u16).me();
+            contributors.push(signer);
+        }
+        contributors.sort();
+
+        // All contributors register encryption keys and commitments
+        let mut arb =
+            arbiter::P0::<MinPk>::new(t, None, contributors.clone(), contributors.clone(), 1);
+        arb.require_encrypted_shares();
+        let mut contributor_shares = HashMap::new();
+        let mut contributor_keys = HashMap::new();
+        for con in &contributors {
+            let (_, commitment, shares) = contributor::P0::<MinPk>::new(
+                con.clone(),
+                t,
+                None,
+                contributors.clone(),
+                contributors.clone(),
+                1,
+            )
+            .finalize();
+            let (_, key) = pvss::keypair(&mut rng);
+            arb.encryption_key(con.clone(), key).unwrap();
+            arb.commitment(con.clone(), commitment.clone()).unwrap();
+            contributor_shares.insert(con.clone(), (commitment, shares));
+            contributor_keys.insert(con.clone(), key);
+        }
+        let (result, disqualified) = arb.finalize();
+        assert!(disqualified.is_empty());
+        let mut arb = result.unwrap();
+
+        // All dealers except the last post a dealing of encrypted shares
+        for (dealer, dealer_key, commitment) in arb.commitments() {
+            if dealer == n - 1 {
+                continue;
+            }
+            let (_, shares) = contributor_shares.get(&dealer_key).unwrap();
+            let mut encrypted = Vec::new();
+            for (idx, recipient) in contributors.iter().enumerate() {
+                if *recipient == dealer_key {
+                    continue;
+                }
+                let key = contributor_keys.get(recipient).unwrap();
+                encrypted.push(pvss::encrypt::<MinPk, _>(
+                    &mut rng,
+                    dealer,
+                    &commitment,
+                    &shares[idx],
+                    key,
+                ));
+            }
+            arb.dealing(dealer_key, &encrypted).unwrap();
+        }
+
+        // The last dealer attempts to collect plaintext acks (and complaints) instead
+        let (commitment, shares) = contributor_shares
+            .get(&contributors[n as usize - 1])
+            .unwrap();
+        assert_eq!(commitment.required(), t);
+        for recipient in contributors.iter().take(n as usize - 1) {
+            assert!(matches!(
+                arb.ack(recipient.clone(), n - 1),
+                Err(Error::UnexpectedAck)
+            ));
+        }
+        assert!(matches!(
+            arb.complaint(contributors[0].clone(), n - 1, &shares[0]),
+            Err(Error::UnexpectedComplaint)
+        ));
+
+        // The last dealer is disqualified for not posting a dealing
+        let (result, disqualified) = arb.finalize();
+        assert_eq!(disqualified.len(), 1);
+        assert!(disqualified.contains(&contributors[n as usize - 1]));
+        let (arb, requests) = result.unwrap();
+        assert!(requests.is_empty());
+        let (result, _) = arb.finalize();
+        let mut commitments = result.unwrap().commitments;
+        commitments.sort();
+        assert_eq!(commitments, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_weighted_dkg() {
+        // Create contributors (weights are assigned in sorted order)
+        let (t, concurrency) = (3, 4);
+        let mut contributors = (0..4)
+            .map(|i| insecure_signer(i as u16).me())
+            .collect::<Vec<_>>();
+        contributors.sort();
+        let weights = [2, 1, 1, 1];
+        let recipients = contributors
+            .iter()
+            .cloned()
+            .zip(weights)
+            .collect::<Vec<_>>();
+        let allocation = weighted::Allocation::new(&weights).unwrap();
+
+        // Create shares
+        let mut contributor_shares = HashMap::new();
+        let mut contributor_cons = HashMap::new();
+        for con in &contributors {
+            let p0 = contributor::P0::<MinPk>::new_weighted(
+                con.clone(),
+                t,
+                contributors.clone(),
+                recipients.clone(),
+                concurrency,
+            );
+            let (p1, public, shares) = p0.finalize();
+            assert_eq!(shares.len(), allocation.total() as usize);
+            contributor_shares.insert(con.clone(), (public, shares));
+            contributor_cons.insert(con.clone(), p1.unwrap());
+        }
+
+        // Inform arbiter of commitments
+        let mut arb = arbiter::P0::<MinPk>::new_weighted(
+            t,
+            contributors.clone(),
+            recipients.clone(),
+            concurrency,
+        );
+        for contributor in contributors.iter() {
+            let (public, _) = contributor_shares.get(contributor).unwrap();
+            arb.commitment(contributor.clone(), public.clone()).unwrap();
+        }
+        let (result, disqualified) = arb.finalize();
+        assert!(disqualified.is_empty());
+        let mut arb = result.unwrap();
+
+        // Send commitments to contributors
+        for (_, dealer, commitment) in arb.commitments().iter() {
+            for contributor in contributors.iter() {
+                contributor_cons
+                    .get_mut(contributor)
+                    .unwrap()
+                    .commitment(dealer.clone(), commitment.clone())
+                    .unwrap();
+            }
+        }
+        let mut contributor_cons = contributor_cons
+            .into_iter()
+            .map(|(con, p1)| (con, p1.finalize().unwrap()))
+            .collect::<HashMap<_, _>>();
+
+        // Distribute shares to contributors and send acks to arbiter
+        //
+        // Recipient 3 (weight 1) does not ack dealer 0, recipient 0 (weight 2) does not
+        // ack dealer 1, and recipients 0 and 1 (weight 3) do not ack dealer 2.
+        let missing = [(0, 3), (1, 0), (2, 0), (2, 1)];
+        for (dealer, dealer_key, _) in arb.commitments().iter() {
+            let (_, shares) = contributor_shares.get(dealer_key).unwrap();
+            for (idx, recipient) in contributors.iter().enumerate() {
+                if missing.contains(&(*dealer, idx)) {
+                    continue;
+                }
+
+                // Send all shares owned by the recipient
+                let p2 = contributor_cons.get_mut(recipient).unwrap();
+                let owned = allocation
+                    .indices(idx as u32)
+                    .map(|index| shares[index as usize].clone())
+                    .collect::<Vec<_>>();
+                if *dealer == 3 && owned.len() > 1 {
+                    // A batch with a single invalid share is rejected
+                    let mut invalid = owned.clone();
+                    invalid[1].private = invalid[0].private;
+                    assert!(matches!(
+                        p2.shares(dealer_key.clone(), invalid),
+                        Err(Error::ShareWrongCommitment)
+                    ));
+                }
+                p2.shares(dealer_key.clone(), owned).unwrap();
+
+                // Shares owned by other recipients are rejected
+                let other = allocation.indices((idx as u32 + 1) % 4).start;
+                assert!(matches!(
+                    p2.share(dealer_key.clone(), shares[other as usize].clone()),
+                    Err(Error::MisdirectedShare)
+                ));
+                if dealer_key != recipient {
+                    arb.ack(recipient.clone(), *dealer).unwrap();
+                }
+            }
+        }
+
+        // Dealer 2 is disqualified (acked weight plus its own weight is below the threshold)
+        let (result, disqualified) = arb.finalize();
+        assert_eq!(disqualified.len(), 1);
+        assert!(disqualified.contains(&contributors[2]));
+        let (mut arb, mut requests) = result.unwrap();
+
+        // Missing shares are requested for each index owned by the recipient
+        requests.sort();
+        assert_eq!(requests, vec![(0, 4), (1, 0), (1, 1)]);
+        let mut reveals = Vec::new();
+        for (dealer, index) in requests {
+            let dealer_key = contributors[dealer as usize].clone();
+            let share = contributor_shares.get(&dealer_key).unwrap().1[index as usize].clone();
+            arb.reveal(dealer_key.clone(), share.clone()).unwrap();
+            reveals.push((dealer_key, share));
+        }
+        let (result, disqualified) = arb.finalize();
+        assert_eq!(disqualified.len(), 1);
+        let output = result.unwrap();
+        assert_eq!(output.commitments.len(), 3);
+
+        // Deliver revealed shares and recover shares
+        for (dealer_key, share) in reveals {
+            let recipient = &contributors[allocation.owner(share.index).unwrap() as usize];
+            contributor_cons
+                .get_mut(recipient)
+                .unwrap()
+                .share(dealer_key, share)
+                .unwrap();
+        }
+        let mut partials = BTreeMap::new();
+        for (idx, contributor) in contributors.iter().enumerate() {
+            let result = contributor_cons
+                .remove(contributor)
+                .unwrap()
+                .finalize(output.commitments.clone())
+                .unwrap();
+            assert_eq!(result.public, output.public);
+            let indices = result
+                .shares
+                .iter()
+                .map(|share| share.index)
+                .collect::<Vec<_>>();
+            assert_eq!(indices, allocation.indices(idx as u32).collect::<Vec<_>>());
+            assert_eq!(result.share().is_some(), indices.len() == 1);
+            partials.insert(
+                idx as u32,
+                weighted::partial_sign::<MinPk>(&result.shares, b"test"),
+            );
+        }
+
+        // Recipients 0 and 1 (weight 3) can generate a signature
+        let mut signers = partials.clone();
+        signers.retain(|signer, _| *signer < 2);
+        let (result, invalid) = weighted::aggregate::<MinPk>(&allocation, t, signers);
+        assert!(invalid.is_empty());
+        verify::<MinPk>(output.public.constant(), b"test", &result.unwrap()).unwrap();
+
+        // Recipients 1 and 2 (weight 2) cannot
+        let mut signers = partials.clone();
+        signers.retain(|signer, _| *signer == 1 || *signer == 2);
+        let (result, invalid) = weighted::aggregate::<MinPk>(&allocation, t, signers);
+        assert!(matches!(result, Err(Error::InsufficientWeight)));
+        assert!(invalid.is_empty());
+
+        // Recipient 0 cannot submit a partial for only some of its shares
+        let mut signers = partials.clone();
+        signers.retain(|signer, _| *signer < 2);
+        signers.get_mut(&0).unwrap().pop();
+        let (result, invalid) = weighted::aggregate::<MinPk>(&allocation, t, signers);
+        assert!(matches!(result, Err(Error::InsufficientWeight)));
+        assert_eq!(invalid, vec![0]);
+
+        // Recipient 0 is skipped if recipients 1, 2, and 3 (weight 3) sign
+        let mut signers = partials.clone();
+        signers.get_mut(&0).unwrap().pop();
+        let (result, invalid) = weighted::aggregate::<MinPk>(&allocation, t, signers);
+        assert_eq!(invalid, vec![0]);
+        verify::<MinPk>(output.public.constant(), b"test", &result.unwrap()).unwrap();
+
+        // Recipient 1 cannot submit a partial for a share owned by recipient 0
+        let mut signers = partials.clone();
+        signers.get_mut(&1).unwrap()[0].index = 0;
+        let (result, invalid) = weighted::aggregate::<MinPk>(&allocation, t, signers);
+        assert_eq!(invalid, vec![1]);
+        verify::<MinPk>(output.public.constant(), b"test", &result.unwrap()).unwrap();
+    }
+
+    fn run_refresh(
+        previous: &poly::Public<MinPk>,
+        shares: &[Share],
+        participants: &[PublicKey],
+    ) -> (poly::Public<MinPk>, Vec<Share>) {
+        // Create shares of zero
+        let mut contributor_shares = HashMap::new();
+        let mut contributor_cons = HashMap::new();
+        for (idx, con) in participants.iter().enumerate() {
+            let p0 = contributor::P0::<MinPk>::new_refresh(
+                con.clone(),
+                (previous.clone(), shares[idx].clone()),
+                participants.to_vec(),
+                1,
+            );
+            let (p1, commitment, shares) = p0.finalize();
+            assert_eq!(*commitment.constant(), G1::zero());
+            contributor_shares.insert(con.clone(), (commitment, shares));
+            contributor_cons.insert(con.clone(), p1.unwrap());
+        }
+
+        // Inform arbiter of commitments
+        let mut arb = arbiter::P0::<MinPk>::new_refresh(previous.clone(), participants.to_vec(), 1);
+        for con in participants {
+            let (commitment, _) = contributor_shares.get(con).unwrap();
+            arb.commitment(con.clone(), commitment.clone()).unwrap();
+        }
+        let (result, disqualified) = arb.finalize();
+        assert!(disqualified.is_empty());
+        let mut arb = result.unwrap();
+
+        // Distribute commitments and shares
+        for (_, dealer, commitment) in arb.commitments().iter() {
+            for con in participants {
+                contributor_cons
+                    .get_mut(con)
+                    .unwrap()
+                    .commitment(dealer.clone(), commitment.clone())
+                    .unwrap();
+            }
+        }
+        let mut contributor_cons = contributor_cons
+            .into_iter()
+            .map(|(con, p1)| (con, p1.finalize().unwrap()))
+            .collect::<HashMap<_, _>>();
+        for (dealer, dealer_key, _) in arb.commitments().iter() {
+            let (_, shares) = contributor_shares.get(dealer_key).unwrap();
+            for (idx, recipient) in participants.iter().enumerate() {
+                contributor_cons
+                    .get_mut(recipient)
+                    .unwrap()
+                    .share(dealer_key.clone(), shares[idx].clone())
+                    .unwrap();
+                if dealer_key != recipient {
+                    arb.ack(recipient.clone(), *dealer).unwrap();
+                }
+            }
+        }
+
+        // Finalize arbiter
+        let (result, _) = arb.finalize();
+        let (arb, requests) = result.unwrap();
+        assert!(requests.is_empty());
+        let (result, disqualified) = arb.finalize();
+        assert!(disqualified.is_empty());
+        let output = result.unwrap();
+
+        // Recover refreshed shares
+        let refreshed = participants
+            .iter()
+            .map(|con| {
+                let result = contributor_cons
+                    .remove(con)
+                    .unwrap()
+                    .finalize(output.commitments.clone())
+                    .unwrap();
+                assert_eq!(result.public, output.public);
+                result.share().unwrap().clone()
+            })
+            .collect();
+        (output.public, refreshed)
+    }
+
+    #[test]
+    fn test_dkg_refresh() {
+        // Create initial shares
+        let (n, t) = (5, 3);
+        let mut participants = (0..n)
+            .map(|i| insecure_signer(i as u16).me())
+            .collect::<Vec<_>>();
+        participants.sort();
+        let (previous, shares) = ops::generate_shares::<MinPk>(None, n, t);
+
+        // Refresh shares
+        let (public, refreshed) = run_refresh(&previous, &shares, &participants);
+
+        // The group public key is unchanged but the polynomial (and all shares) are not
+        assert_eq!(public.constant(), previous.constant());
+        assert_ne!(public, previous);
+        for (old, new) in shares.iter().zip(refreshed.iter()) {
+            assert_eq!(old.index, new.index);
+            assert!(old.private != new.private);
+            ops::verify_share::<MinPk>(None, 0, &public, t, new.index, new).unwrap();
+        }
+
+        // Refreshed shares recover the same secret
+        let recover = |shares: Vec<&Share>| {
+            let evals = shares
+                .into_iter()
+                .map(|share| poly::Eval {
+                    index: share.index,
+                    value: share.private,
+                })
+                .collect::<Vec<_>>();
+            poly::Private::recover(t, evals).unwrap()
+        };
+        let secret = recover(shares.iter().take(3).collect());
+        assert_eq!(recover(refreshed.iter().skip(2).collect()), secret);
+
+        // Refreshed shares generate signatures under the same public key
+        let partials = refreshed
+            .iter()
+            .take(3)
+            .map(|share| partial_sign::<MinPk>(share, b"test"))
+            .collect::<Vec<_>>();
+        let signature = aggregate::<MinPk>(t, partials).unwrap();
+        verify::<MinPk>(previous.constant(), b"test", &signature).unwrap();
+
+        // Mixing old and new shares fails recovery
+        let mixed = vec![&shares[0], &shares[1], &refreshed[2]];
+        assert_ne!(recover(mixed), secret);
+        let mixed = vec![&shares[0], &refreshed[1], &refreshed[2]];
+        assert_ne!(recover(mixed), secret);
+        let partials = vec![
+            partial_sign::<MinPk>(&shares[0], b"test"),
+            partial_sign::<MinPk>(&refreshed[1], b"test"),
+            partial_sign::<MinPk>(&refreshed[2], b"test"),
+        ];
+        let signature = aggregate::<MinPk>(t, partials).unwrap();
+        assert!(verify::<MinPk>(previous.constant(), b"test", &signature).is_err());
+
+        // Shares can be refreshed again
+        let (public_2, refreshed_2) = run_refresh(&public, &refreshed, &participants);
+        assert_eq!(public_2.constant(), previous.constant());
+        assert_eq!(recover(refreshed_2.iter().take(3).collect()), secret);
+        let mixed = vec![&refreshed[0], &refreshed_2[1], &refreshed_2[2]];
+        assert_ne!(recover(mixed), secret);
+    }
+
+    #[test]
+    fn test_dkg_refresh_invalid_commitment() {
+        let (n, t) = (4, 3);
+        let mut participants = (0..n)
+            .map(|i| insecure_signer(i as u16).me())
+            .collect::<Vec<_>>();
+        participants.sort();
+        let (previous, shares) = ops::generate_shares::<MinPk>(None, n, t);
+
+        // A commitment with a non-zero constant term would change the group secret
+        let (commitment, _) = ops::generate_shares::<MinPk>(None, n, t);
+        let mut arb = arbiter::P0::<MinPk>::new_refresh(previous.clone(), participants.clone(), 1);
+        assert!(matches!(
+            arb.commitment(participants[0].clone(), commitment.clone()),
+            Err(Error::UnexpectedPolynomial)
+        ));
+        assert!(matches!(
+            arb.commitment(participants[0].clone(), commitment.clone()),
+            Err(Error::ContributorDisqualified)
+        ));
+
+        // Contributors reject it as well
+        let p0 = contributor::P0::<MinPk>::new_refresh(
+            participants[1].clone(),
+            (previous.clone(), shares[1].clone()),
+            participants.clone(),
+            1,
+        );
+        let (p1, _, _) = p0.finalize();
+        let mut p1 = p1.unwrap();
+        assert!(matches!(
+            p1.commitment(participants[0].clone(), commitment),
+            Err(Error::UnexpectedPolynomial)
+        ));
+
+        // Refresh state survives a restart
+        let (refresh, _) = ops::generate_refresh_shares::<MinPk>(n, t);
+        p1.commitment(participants[0].clone(), refresh.clone())
+            .unwrap();
+        let p1 = contributor::P1::<MinPk>::deserialize(&p1.serialize()).unwrap();
+        assert!(p1.has(participants[0].clone()));
+        let mut arb = arbiter::P0::<MinPk>::deserialize(&arb.serialize()).unwrap();
+        let (commitment, _) = ops::generate_shares::<MinPk>(None, n, t);
+        assert!(matches!(
+            arb.commitment(participants[2].clone(), commitment),
+            Err(Error::UnexpectedPolynomial)
+        ));
+        arb.commitment(participants[1].clone(), refresh.clone())
+            .unwrap();
+
+        // Refresh commitments are sent without their (unencodable) constant term
+        let bytes = ops::serialize_refresh_commitment::<MinPk>(&refresh);
+        assert_eq!(
+            ops::deserialize_refresh_commitment::<MinPk>(&bytes, t).unwrap(),
+            refresh
+        );
+        assert!(ops::deserialize_refresh_commitment::<MinPk>(&bytes, t - 1).is_none());
+        assert!(poly::Public::<MinPk>::deserialize(&refresh.serialize(), t).is_none());
+    }
+
+    #[test]
+    fn test_dkg_resume() {
+        let (n, t) = (4, 3);
+
+        // Create contributors (must be in sorted order)
+        let mut contributors = Vec::new();
+        for i in 0..n {
+            let signer = insecure_signer(i as u16).me();
+            contributors.push(signer);
+        }
+        contributors.sort();
+
+        // Create shares (restarting each dealer before and after dealing)
+        let mut contributor_dealings = HashMap::new();
+        let mut contributor_cons = HashMap::new();
+        for con in &contributors {
+            let p0 = contributor::P0::<MinPk>::new(
+                con.clone(),
+                t,
+                None,
+                contributors.clone(),
+                contributors.clone(),
+                1,
+            );
+            let p0 = contributor::P0::<MinPk>::deserialize(&p0.serialize()).unwrap();
+            let (p1, commitment, shares) = p0.finalize();
+            let dealing = contributor::Dealing::<MinPk> { commitment, shares };
+            let serialized = dealing.serialize();
+            let dealing = contributor::Dealing::<MinPk>::deserialize(&serialized).unwrap();
+            assert_eq!(dealing.serialize(), serialized);
+            let p1 = p1.unwrap();
+            let serialized = p1.serialize();
+            let p1 = contributor::P1::<MinPk>::deserialize(&serialized).unwrap();
+            assert_eq!(p1.serialize(), serialized);
+            contributor_dealings.insert(con.clone(), dealing);
+            contributor_cons.insert(con.clone(), p1);
+        }
+
+        // Inform arbiter of commitments (restarting after each one)
+        let mut arb =
+            arbiter::P0::<MinPk>::new(t, None, contributors.clone(), contributors.clone(), 1);
+        for contributor in contributors.iter() {
+            let dealing = contributor_dealings.get(contributor).unwrap();
+            arb.commitment(contributor.clone(), dealing.commitment.clone())
+                .unwrap();
+            let serialized = arb.serialize();
+            arb = arbiter::P0::deserialize(&serialized).unwrap();
+            assert_eq!(arb.serialize(), serialized);
+            assert!(matches!(
+                arb.commitment(contributor.clone(), dealing.commitment.clone()),
+                Err(Error::DuplicateCommitment)
+            ));
+        }
+        let (result, disqualified) = arb.finalize();
+        assert!(disqualified.is_empty());
+        let serialized = result.unwrap().serialize();
+        let mut arb = arbiter::P1::<MinPk>::deserialize(&serialized).unwrap();
+        assert_eq!(arb.serialize(), serialized);
+
+        // Send commitments to contributors (restarting after each one)
+        for (_, dealer, commitment) in arb.commitments().iter() {
+            for contributor in contributors.iter() {
+                let mut p1 = contributor_cons.remove(contributor).unwrap();
+                p1.commitment(dealer.clone(), commitment.clone()).unwrap();
+                let p1 = contributor::P1::<MinPk>::deserialize(&p1.serialize()).unwrap();
+                contributor_cons.insert(contributor.clone(), p1);
+            }
+        }
+        let mut p2 = HashMap::new();
+        for contributor in contributors.iter() {
+            let output = contributor_cons
+                .remove(contributor)
+                .unwrap()
+                .finalize()
+                .unwrap();
+            p2.insert(contributor.clone(), output);
+        }
+        let mut contributor_cons = p2;
+
+        // Distribute shares to contributors (restarting after each one) and send acks to arbiter
+        for (dealer, dealer_key, _) in arb.commitments().iter() {
+            let dealing = contributor_dealings.get(dealer_key).unwrap();
+            for (idx, recipient) in contributors.iter().enumerate() {
+                let mut p2 = contributor_cons.remove(recipient).unwrap();
+                p2.share(dealer_key.clone(), dealing.shares[idx].clone())
+                    .unwrap();
+                let serialized = p2.serialize();
+                let p2 = contributor::P2::<MinPk>::deserialize(&serialized).unwrap();
+                assert_eq!(p2.serialize(), serialized);
+                contributor_cons.insert(recipient.clone(), p2);
+                if dealer_key == recipient {
+                    continue;
+                }
+                arb.ack(recipient.clone(), *dealer).unwrap();
+            }
+            arb = arbiter::P1::deserialize(&arb.serialize()).unwrap();
+        }
+
+        // Finalize arbiter (restarting before recovery)
+        let (result, _) = arb.finalize();
+        let (arb, requests) = result.unwrap();
+        assert!(requests.is_empty());
+        let serialized = arb.serialize();
+        let arb = arbiter::P2::<MinPk>::deserialize(&serialized).unwrap();
+        assert_eq!(arb.serialize(), serialized);
+        let (result, disqualified) = arb.finalize();
+        assert!(disqualified.is_empty());
+        let output = result.unwrap();
+        let serialized = output.serialize();
+        let output = arbiter::Output::<MinPk>::deserialize(&serialized).unwrap();
+        assert_eq!(output.serialize(), serialized);
+
+        // Recover shares on contributors (restarting after recovery)
+        let mut partials = Vec::new();
+        for contributor in contributors.iter() {
+            let result = contributor_cons
+                .remove(contributor)
+                .unwrap()
+                .finalize(output.commitments.clone())
+                .unwrap();
+            let result = contributor::Output::<MinPk>::deserialize(&result.serialize()).unwrap();
+            assert_eq!(result.public, output.public);
+            partials.push(partial_sign::<MinPk>(result.share().unwrap(), b"test"));
+        }
+        let signature = aggregate::<MinPk>(t, partials).unwrap();
+        verify::<MinPk>(&poly::public::<MinPk>(&output.public), b"test", &signature).unwrap();
+    }
+
+    #[test]
+    fn test_deserialize_invalid() {
+        let (n, t) = (4, 3);
+        let mut contributors = Vec::new();
+        for i in 0..n {
+            let signer = insecure_signer(i as u16).me();
+            contributors.push(signer);
+        }
+        contributors.sort();
+        let (commitment, shares) = ops::generate_shares::<MinPk>(None, n, t);
+
+        // Resharing contributor (holding a secret)
+        let p0 = contributor::P0::<MinPk>::new(
+            contributors[0].clone(),
+            t,
+            Some((commitment, shares[0].clone())),
+            contributors.clone(),
+            contributors.clone(),
+            1,
+        );
+        let serialized = p0.serialize();
+        let p0 = contributor::P0::<MinPk>::deserialize(&serialized).unwrap();
+        assert_eq!(p0.serialize(), serialized);
+
+        // Unknown version
+        let mut bytes = serialized.to_vec();
+        bytes[0] += 1;
+        assert!(contributor::P0::<MinPk>::deserialize(&bytes).is_none());
+
+        // Wrong type
+        assert!(contributor::P1::<MinPk>::deserialize(&serialized).is_none());
+        assert!(arbiter::P0::<MinPk>::deserialize(&serialized).is_none());
+
+        // Wrong variant
+        assert!(contributor::P0::<MinSig>::deserialize(&serialized).is_none());
+
+        // Truncated or extended
+        assert!(
+            contributor::P0::<MinPk>::deserialize(&serialized[..serialized.len() - 1]).is_none()
+        );
+        let mut bytes = serialized.to_vec();
+        bytes.push(0);
+        assert!(contributor::P0::<MinPk>::deserialize(&bytes).is_none());
+    }
+
+    #[test]
+    fn test_verify_shares() {
+        let (n, t) = (10, 7);
+        let (commitment, mut shares) = ops::generate_shares::<MinPk>(None, n, t);
+        let mut rng = rand::thread_rng();
+        ops::verify_shares::<MinPk, _>(&mut rng, None, 0, &commitment, t, &shares).unwrap();
+
+        // Corrupt a share
+        shares[3].private = Private::rand(&mut rng);
+        assert!(matches!(
+            ops::verify_shares::<MinPk, _>(&mut rng, None, 0, &commitment, t, &shares),
+            Err(Error::ShareWrongCommitment)
+        ));
+
+        // Swap the indices of two shares
+        let (commitment, mut shares) = ops::generate_shares::<MinPk>(None, n, t);
+        shares.swap(1, 2);
+        let (index_1, index_2) = (shares[1].index, shares[2].index);
+        shares[1].index = index_2;
+        shares[2].index = index_1;
+        assert!(matches!(
+            ops::verify_shares::<MinPk, _>(&mut rng, None, 0, &commitment, t, &shares),
+            Err(Error::ShareWrongCommitment)
+        ));
+    }
 }
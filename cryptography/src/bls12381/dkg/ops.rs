@@ -2,8 +2,12 @@
 
 use crate::bls12381::{
     dkg::Error,
-    primitives::{group::Share, poly},
+    primitives::{
+        group::{Element, Scalar, Share, Variant},
+        poly,
+    },
 };
+use rand::{rngs::OsRng, CryptoRng, RngCore};
 use rayon::prelude::*;
 use rayon::ThreadPoolBuilder;
 use std::collections::BTreeMap;
@@ -14,9 +18,23 @@ use std::collections::BTreeMap;
 /// * `share` - The dealer's share of the previous DKG (if any)
 /// * `n` - The total number of participants in the round
 /// * `t` - The threshold number of participants required to reconstruct the secret
-pub fn generate_shares(share: Option<Share>, n: u32, t: u32) -> (poly::Public, Vec<Share>) {
+pub fn generate_shares<V: Variant>(
+    share: Option<Share>,
+    n: u32,
+    t: u32,
+) -> (poly::Public<V>, Vec<Share>) {
+    generate_shares_from::<V, _>(&mut OsRng, share, n, t)
+}
+
+/// Generate shares and a commitment (sampling the secret polynomial from the provided RNG).
+pub fn generate_shares_from<V: Variant, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    share: Option<Share>,
+    n: u32,
+    t: u32,
+) -> (poly::Public<V>, Vec<Share>) {
     // Generate a secret polynomial and commit to it
-    let mut secret = poly::new(t - 1);
+    let mut secret = poly::new_from(t - 1, rng);
     if let Some(share) = share {
         // Set the free coefficient of the secret polynomial to the secret
         // of the previous DKG
@@ -24,7 +42,35 @@ pub fn generate_shares(share: Option<Share>, n: u32, t: u32) -> (poly::Public, V
     }
 
     // Commit to polynomial and generate shares
-    let commitment = poly::Public::commit(secret.clone());
+    deal::<V>(secret, n)
+}
+
+/// Generate shares of zero and a commitment (to refresh existing shares).
+///
+/// Adding these shares to existing shares (and the commitment to the existing public polynomial)
+/// re-randomizes them without changing the secret.
+///
+/// # Arguments
+/// * `n` - The total number of participants in the round
+/// * `t` - The threshold number of participants required to reconstruct the secret
+pub fn generate_refresh_shares<V: Variant>(n: u32, t: u32) -> (poly::Public<V>, Vec<Share>) {
+    generate_refresh_shares_from::<V, _>(&mut OsRng, n, t)
+}
+
+/// Generate shares of zero and a commitment (sampling the secret polynomial from the provided RNG).
+pub fn generate_refresh_shares_from<V: Variant, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    n: u32,
+    t: u32,
+) -> (poly::Public<V>, Vec<Share>) {
+    let mut secret = poly::new_from(t - 1, rng);
+    secret.set(0, Scalar::zero());
+    deal::<V>(secret, n)
+}
+
+/// Commit to a secret polynomial and evaluate it for `n` participants.
+fn deal<V: Variant>(secret: poly::Private, n: u32) -> (poly::Public<V>, Vec<Share>) {
+    let commitment = poly::Public::<V>::commit(secret.clone());
     let shares = (0..n)
         .map(|i| {
             let eval = secret.evaluate(i);
@@ -39,10 +85,10 @@ pub fn generate_shares(share: Option<Share>, n: u32, t: u32) -> (poly::Public, V
 
 /// Verify that a given commitment is valid for a dealer. If a previous
 /// polynomial is provided, verify that the commitment is on that polynomial.
-pub fn verify_commitment(
-    previous: Option<&poly::Public>,
+pub fn verify_commitment<V: Variant>(
+    previous: Option<&poly::Public<V>>,
     dealer: u32,
-    commitment: &poly::Public,
+    commitment: &poly::Public<V>,
     t: u32,
 ) -> Result<(), Error> {
     if let Some(previous) = previous {
@@ -57,23 +103,60 @@ pub fn verify_commitment(
     Ok(())
 }
 
+/// Verify that a given commitment is valid for a refresh (commits to a polynomial
+/// of the expected degree with a zero constant term).
+pub fn verify_refresh_commitment<V: Variant>(
+    commitment: &poly::Public<V>,
+    t: u32,
+) -> Result<(), Error> {
+    if *commitment.constant() != V::Public::zero() {
+        return Err(Error::UnexpectedPolynomial);
+    }
+    verify_commitment::<V>(None, 0, commitment, t)
+}
+
+/// Serializes a refresh commitment (omitting its zero constant term, which cannot be
+/// encoded as a point).
+pub fn serialize_refresh_commitment<V: Variant>(commitment: &poly::Public<V>) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(commitment.degree() as usize * V::Public::size());
+    for coeff in commitment.coefficients().iter().skip(1) {
+        bytes.extend_from_slice(&coeff.serialize());
+    }
+    bytes
+}
+
+/// Deserializes a refresh commitment (with `t` coefficients) encoded with
+/// `serialize_refresh_commitment`.
+pub fn deserialize_refresh_commitment<V: Variant>(bytes: &[u8], t: u32) -> Option<poly::Public<V>> {
+    let expected = t.checked_sub(1)?;
+    if bytes.len() != (expected as usize).checked_mul(V::Public::size())? {
+        return None;
+    }
+    let mut coeffs = Vec::with_capacity(t as usize);
+    coeffs.push(V::Public::zero());
+    for chunk in bytes.chunks_exact(V::Public::size()) {
+        coeffs.push(V::Public::deserialize(chunk)?);
+    }
+    Some(poly::Public::<V>::from(coeffs))
+}
+
 /// Verify that a given share is valid for a specified recipient.
-pub fn verify_share(
-    previous: Option<&poly::Public>,
+pub fn verify_share<V: Variant>(
+    previous: Option<&poly::Public<V>>,
     dealer: u32,
-    commitment: &poly::Public,
+    commitment: &poly::Public<V>,
     t: u32,
     recipient: u32,
     share: &Share,
 ) -> Result<(), Error> {
     // Verify that commitment is on previous public polynomial (if provided)
-    verify_commitment(previous, dealer, commitment, t)?;
+    verify_commitment::<V>(previous, dealer, commitment, t)?;
 
     // Check if share is valid
     if share.index != recipient {
         return Err(Error::MisdirectedShare);
     }
-    let expected = share.public();
+    let expected = share.public::<V>();
     let given = commitment.evaluate(share.index);
     if given.value != expected {
         return Err(Error::ShareWrongCommitment);
@@ -81,15 +164,55 @@ pub fn verify_share(
     Ok(())
 }
 
+/// Verify that a batch of shares (each for a different recipient) are valid for a dealer.
+///
+/// Rather than evaluating the commitment for each share, this compares a random linear
+/// combination of all shares against the same combination of evaluations of the commitment
+/// (computed with a single multi-scalar multiplication). This is significantly faster than calling
+/// `verify_share` for each share once more than a few shares are provided but does not identify
+/// which share is invalid (if any).
+pub fn verify_shares<V: Variant, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    previous: Option<&poly::Public<V>>,
+    dealer: u32,
+    commitment: &poly::Public<V>,
+    t: u32,
+    shares: &[Share],
+) -> Result<(), Error> {
+    // Verify that commitment is on previous public polynomial (if provided)
+    verify_commitment::<V>(previous, dealer, commitment, t)?;
+
+    // Combine shares with random weights
+    let weights = (0..shares.len())
+        .map(|_| Scalar::rand(rng))
+        .collect::<Vec<_>>();
+    let mut secret = Scalar::zero();
+    for (share, weight) in shares.iter().zip(weights.iter()) {
+        let mut private = share.private;
+        private.mul(weight);
+        secret.add(&private);
+    }
+    let mut expected = V::Public::one();
+    expected.mul(&secret);
+
+    // Compare against the same combination of evaluations
+    let indices = shares.iter().map(|s| s.index).collect::<Vec<_>>();
+    let given = commitment.evaluate_linear_combination(&indices, &weights);
+    if given != expected {
+        return Err(Error::ShareWrongCommitment);
+    }
+    Ok(())
+}
+
 /// Construct a new public polynomial by summing all commitments.
-pub fn construct_public(
-    commitments: Vec<poly::Public>,
+pub fn construct_public<V: Variant>(
+    commitments: Vec<poly::Public<V>>,
     required: u32,
-) -> Result<poly::Public, Error> {
+) -> Result<poly::Public<V>, Error> {
     if commitments.len() < required as usize {
         return Err(Error::InsufficientDealings);
     }
-    let mut public = poly::Public::zero();
+    let mut public = poly::Public::<V>::zero();
     for commitment in commitments {
         public.add(&commitment);
     }
@@ -100,12 +223,12 @@ pub fn construct_public(
 /// polynomials.
 ///
 /// It is assumed that the required number of commitments are provided.
-pub fn recover_public(
-    previous: &poly::Public,
-    commitments: BTreeMap<u32, poly::Public>,
+pub fn recover_public<V: Variant>(
+    previous: &poly::Public<V>,
+    commitments: BTreeMap<u32, poly::Public<V>>,
     threshold: u32,
     concurrency: usize,
-) -> Result<poly::Public, Error> {
+) -> Result<poly::Public<V>, Error> {
     // Ensure we have enough commitments to interpolate
     let required = previous.required();
     if commitments.len() < required as usize {
@@ -118,6 +241,14 @@ pub fn recover_public(
         .build()
         .expect("unable to build thread pool");
 
+    // Compute Lagrange coefficients once for the first `required` dealers
+    let commitments = commitments
+        .into_iter()
+        .take(required as usize)
+        .collect::<BTreeMap<_, _>>();
+    let indices = commitments.keys().copied().collect::<Vec<_>>();
+    let weights = poly::Weights::new(&indices).map_err(|_| Error::PublicKeyInterpolationFailed)?;
+
     // Perform interpolation over each coefficient
     let new = match pool.install(|| {
         (0..threshold)
@@ -130,14 +261,14 @@ pub fn recover_public(
                         value: commitment.get(coeff),
                     })
                     .collect();
-                match poly::Public::recover(required, evals) {
+                match poly::Public::<V>::recover_with_weights(&weights, &evals) {
                     Ok(point) => Ok(point),
                     Err(_) => Err(Error::PublicKeyInterpolationFailed),
                 }
             })
             .collect::<Result<Vec<_>, _>>()
     }) {
-        Ok(points) => poly::Public::from(points),
+        Ok(points) => poly::Public::<V>::from(points),
         Err(e) => return Err(e),
     };
 
@@ -0,0 +1,571 @@
+//! Publicly verifiable encryption of shares (PVSS).
+//!
+//! Instead of distributing shares over private channels (and relying on acks, complaints, and
+//! reveals to resolve disputes), a dealer can publish each share encrypted to its recipient
+//! alongside a proof that the ciphertext decrypts to the share committed to by the dealer's
+//! public polynomial. Anyone (most importantly the arbiter) can verify an [EncryptedShare]
+//! without learning anything about the share it contains.
+//!
+//! # Construction
+//!
+//! Each recipient generates an encryption keypair `(x, X = g^x)` over G1 (regardless of the
+//! [Variant] used for the DKG/Resharing procedure). To encrypt a share `s` for the recipient at
+//! index `i`, the dealer decomposes `s` into bits `s_0, ..., s_254` and encrypts each bit with
+//! exponential ElGamal: `(R_b, C_b) = (g^{r_b}, X^{r_b} * g^{s_b})`. Each bit ciphertext carries a
+//! (non-interactive) Cramer-Damgård-Schoenmakers OR-proof that it encrypts either `0` or `1`. The dealer
+//! then proves, with a single proof of representation spanning both groups, that the weighted product of
+//! all bit ciphertexts (`R = Π R_b^{2^b}` and `C = Π C_b^{2^b}`) encrypts the discrete log of the
+//! dealer's commitment evaluated at `i`.
+//!
+//! To decrypt, the recipient recovers each bit by checking whether `C_b` is `R_b^x` or `R_b^x * g`.
+//!
+//! # Cost
+//!
+//! Encrypting (or verifying) a share requires ~8 exponentiations per bit (~2,000 in total) and an
+//! encrypted share is ~57KB. This is a good fit for tens of contributors (where avoiding an additional
+//! round of acks, complaints, and reveals is worth it) but not for hundreds.
+//!
+//! # Warning
+//!
+//! The encryption keypair should be dedicated to this purpose (and not reused as a signing key).
+
+use crate::bls12381::{
+    dkg::Error,
+    primitives::{
+        group::{Element, Private, Scalar, Share, Variant, G1},
+        poly,
+    },
+};
+use rand::{CryptoRng, RngCore};
+
+/// Domain separation tag used for the proof that a ciphertext encrypts a bit.
+const DST_BIT: &[u8] = b"COMMONWARE_BLS12381_PVSS_BIT_";
+
+/// Domain separation tag used for the proof that a ciphertext encrypts a committed share.
+const DST_SHARE: &[u8] = b"COMMONWARE_BLS12381_PVSS_SHARE_";
+
+/// Number of bits encrypted for each share (enough to represent any scalar).
+const SHARE_BITS: usize = 255;
+
+/// Serialized size of an encrypted bit (ciphertext and OR-proof).
+const BIT_LENGTH: usize = 2 * G1_LENGTH + 4 * SCALAR_LENGTH;
+
+/// Serialized size of an [EncryptedShare].
+pub const ENCRYPTED_SHARE_LENGTH: usize = 4 + SHARE_BITS * BIT_LENGTH + 3 * SCALAR_LENGTH;
+
+const G1_LENGTH: usize = 48;
+const SCALAR_LENGTH: usize = 32;
+
+/// Key a recipient publishes to receive encrypted shares.
+pub type EncryptionKey = G1;
+
+/// Key a recipient uses to decrypt encrypted shares.
+pub type DecryptionKey = Private;
+
+/// Generate a new encryption keypair.
+pub fn keypair<R: RngCore + CryptoRng>(rng: &mut R) -> (DecryptionKey, EncryptionKey) {
+    let private = Scalar::rand(rng);
+    let mut public = G1::one();
+    public.mul(&private);
+    (private, public)
+}
+
+/// ElGamal encryption of a single bit (and a proof that it is a bit).
+#[derive(Clone, Debug, PartialEq)]
+struct EncryptedBit {
+    randomness: G1,
+    ciphertext: G1,
+
+    // Challenges and responses for the `0` and `1` branches
+    challenges: [Scalar; 2],
+    responses: [Scalar; 2],
+}
+
+/// Share encrypted to a recipient and a proof that it is consistent with the dealer's commitment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EncryptedShare {
+    pub index: u32,
+    bits: Vec<EncryptedBit>,
+
+    // Proof of representation (challenge and responses)
+    challenge: Scalar,
+    responses: [Scalar; 2],
+}
+
+/// Returns `-s`.
+fn negate(s: &Scalar) -> Scalar {
+    let mut ret = Scalar::zero();
+    ret.sub(s);
+    ret
+}
+
+/// Returns `a * x + b * y`.
+fn combine<E: Element>(a: &E, x: &Scalar, b: &E, y: &Scalar) -> E {
+    let mut left = a.clone();
+    left.mul(x);
+    let mut right = b.clone();
+    right.mul(y);
+    left.add(&right);
+    left
+}
+
+/// Returns `[1, 2, 4, ..., 2^(SHARE_BITS - 1)]`.
+fn powers_of_two() -> Vec<Scalar> {
+    let mut powers = Vec::with_capacity(SHARE_BITS);
+    let mut power = Scalar::one();
+    for _ in 0..SHARE_BITS {
+        powers.push(power);
+        power.add(&power.clone());
+    }
+    powers
+}
+
+/// Returns the bits of a scalar (least significant first).
+fn bits(s: &Scalar) -> Vec<bool> {
+    let bytes = s.serialize();
+    (0..SHARE_BITS)
+        .map(|b| (bytes[bytes.len() - 1 - b / 8] >> (b % 8)) & 1 == 1)
+        .collect()
+}
+
+/// Computes the challenge of the OR-proof for bit `b`.
+#[allow(clippy::too_many_arguments)]
+fn bit_challenge(
+    dealer: u32,
+    index: u32,
+    b: usize,
+    key: &EncryptionKey,
+    randomness: &G1,
+    ciphertext: &G1,
+    commitments: &[G1; 4],
+) -> Scalar {
+    let mut message = Vec::with_capacity(12 + 7 * G1_LENGTH);
+    message.extend_from_slice(&dealer.to_be_bytes());
+    message.extend_from_slice(&index.to_be_bytes());
+    message.extend_from_slice(&(b as u32).to_be_bytes());
+    message.extend_from_slice(&key.serialize());
+    message.extend_from_slice(&randomness.serialize());
+    message.extend_from_slice(&ciphertext.serialize());
+    for commitment in commitments {
+        message.extend_from_slice(&commitment.serialize());
+    }
+    Scalar::map(DST_BIT, &message)
+}
+
+/// Computes the challenge of the proof of representation.
+#[allow(clippy::too_many_arguments)]
+fn share_challenge<V: Variant>(
+    dealer: u32,
+    index: u32,
+    key: &EncryptionKey,
+    randomness: &G1,
+    ciphertext: &G1,
+    public: &V::Public,
+    commitments: (&G1, &G1, &V::Public),
+) -> Scalar {
+    let mut message = Vec::new();
+    message.extend_from_slice(&dealer.to_be_bytes());
+    message.extend_from_slice(&index.to_be_bytes());
+    message.extend_from_slice(&key.serialize());
+    message.extend_from_slice(&randomness.serialize());
+    message.extend_from_slice(&ciphertext.serialize());
+    message.extend_from_slice(&public.serialize());
+    message.extend_from_slice(&commitments.0.serialize());
+    message.extend_from_slice(&commitments.1.serialize());
+    message.extend_from_slice(&commitments.2.serialize());
+    Scalar::map(DST_SHARE, &message)
+}
+
+/// Recomputes the commitments of the OR-proof for an encrypted bit.
+fn bit_commitments(key: &EncryptionKey, bit: &EncryptedBit) -> [G1; 4] {
+    let g = G1::one();
+    let mut shifted = g;
+    shifted.mul(&negate(&Scalar::one()));
+    shifted.add(&bit.ciphertext);
+    let statements = [bit.ciphertext, shifted];
+
+    // For each branch `k`, `g^z * R^-c` and `X^z * (C / g^k)^-c`
+    let mut commitments = [G1::zero(); 4];
+    for k in 0..2 {
+        let challenge = negate(&bit.challenges[k]);
+        commitments[2 * k] = combine(&g, &bit.responses[k], &bit.randomness, &challenge);
+        commitments[2 * k + 1] = combine(key, &bit.responses[k], &statements[k], &challenge);
+    }
+    commitments
+}
+
+/// Encrypt a share to a recipient and prove the ciphertext is consistent with the
+/// dealer's commitment.
+///
+/// # Arguments
+/// * `dealer` - The index of the dealer
+/// * `commitment` - The dealer's public polynomial (that `share` was generated from)
+/// * `share` - The share to encrypt
+/// * `key` - The encryption key of the recipient of `share`
+pub fn encrypt<V: Variant, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    dealer: u32,
+    commitment: &poly::Public<V>,
+    share: &Share,
+    key: &EncryptionKey,
+) -> EncryptedShare {
+    let g = G1::one();
+    let powers = powers_of_two();
+
+    // Encrypt each bit and prove it is either 0 or 1
+    let mut total = Scalar::zero();
+    let mut encrypted = Vec::with_capacity(SHARE_BITS);
+    for (b, bit) in bits(&share.private).into_iter().enumerate() {
+        let r = Scalar::rand(rng);
+        let mut randomness = g;
+        randomness.mul(&r);
+        let mut ciphertext = *key;
+        ciphertext.mul(&r);
+        if bit {
+            ciphertext.add(&g);
+        }
+
+        // Simulate the branch we can't prove
+        let real = bit as usize;
+        let fake = 1 - real;
+        let mut challenges = [Scalar::zero(); 2];
+        let mut responses = [Scalar::zero(); 2];
+        challenges[fake] = Scalar::rand(rng);
+        responses[fake] = Scalar::rand(rng);
+        let mut statement = ciphertext;
+        if fake == 1 {
+            let mut shift = g;
+            shift.mul(&negate(&Scalar::one()));
+            statement.add(&shift);
+        }
+        let challenge = negate(&challenges[fake]);
+        let mut commitments = [G1::zero(); 4];
+        commitments[2 * fake] = combine(&g, &responses[fake], &randomness, &challenge);
+        commitments[2 * fake + 1] = combine(key, &responses[fake], &statement, &challenge);
+
+        // Commit to the branch we can prove
+        let w = Scalar::rand(rng);
+        commitments[2 * real] = g;
+        commitments[2 * real].mul(&w);
+        commitments[2 * real + 1] = *key;
+        commitments[2 * real + 1].mul(&w);
+
+        // Split the challenge and respond
+        let mut challenge = bit_challenge(
+            dealer,
+            share.index,
+            b,
+            key,
+            &randomness,
+            &ciphertext,
+            &commitments,
+        );
+        challenge.sub(&challenges[fake]);
+        challenges[real] = challenge;
+        let mut response = challenge;
+        response.mul(&r);
+        response.add(&w);
+        responses[real] = response;
+
+        // Track the aggregate randomness
+        let mut weighted = r;
+        weighted.mul(&powers[b]);
+        total.add(&weighted);
+
+        encrypted.push(EncryptedBit {
+            randomness,
+            ciphertext,
+            challenges,
+            responses,
+        });
+    }
+
+    // Prove the aggregate ciphertext encrypts the share committed to at `share.index`
+    let (randomness, ciphertext) = aggregate(&encrypted, &powers);
+    let public = commitment.evaluate(share.index).value;
+    let (w1, w2) = (Scalar::rand(rng), Scalar::rand(rng));
+    let mut t1 = g;
+    t1.mul(&w1);
+    let t2 = combine(key, &w1, &g, &w2);
+    let mut t3 = V::Public::one();
+    t3.mul(&w2);
+    let challenge = share_challenge::<V>(
+        dealer,
+        share.index,
+        key,
+        &randomness,
+        &ciphertext,
+        &public,
+        (&t1, &t2, &t3),
+    );
+    let mut z1 = challenge;
+    z1.mul(&total);
+    z1.add(&w1);
+    let mut z2 = challenge;
+    z2.mul(&share.private);
+    z2.add(&w2);
+    EncryptedShare {
+        index: share.index,
+        bits: encrypted,
+        challenge,
+        responses: [z1, z2],
+    }
+}
+
+/// Computes `(Π R_b^{2^b}, Π C_b^{2^b})`.
+fn aggregate(bits: &[EncryptedBit], powers: &[Scalar]) -> (G1, G1) {
+    let randomness = bits.iter().map(|bit| bit.randomness).collect::<Vec<_>>();
+    let ciphertexts = bits.iter().map(|bit| bit.ciphertext).collect::<Vec<_>>();
+    (G1::msm(&randomness, powers), G1::msm(&ciphertexts, powers))
+}
+
+/// Verify that an encrypted share decrypts (under the key corresponding to `key`) to a
+/// share that is consistent with the dealer's commitment.
+///
+/// This does not verify that `commitment` is valid for `dealer` (see `ops::verify_commitment`).
+pub fn verify<V: Variant>(
+    dealer: u32,
+    commitment: &poly::Public<V>,
+    key: &EncryptionKey,
+    encrypted: &EncryptedShare,
+) -> Result<(), Error> {
+    if encrypted.bits.len() != SHARE_BITS {
+        return Err(Error::InvalidDealing);
+    }
+
+    // Verify each ciphertext encrypts a bit
+    for (b, bit) in encrypted.bits.iter().enumerate() {
+        let commitments = bit_commitments(key, bit);
+        let expected = bit_challenge(
+            dealer,
+            encrypted.index,
+            b,
+            key,
+            &bit.randomness,
+            &bit.ciphertext,
+            &commitments,
+        );
+        let mut challenge = bit.challenges[0];
+        challenge.add(&bit.challenges[1]);
+        if challenge != expected {
+            return Err(Error::InvalidDealing);
+        }
+    }
+
+    // Verify the bits encrypt the committed share
+    let g = G1::one();
+    let (randomness, ciphertext) = aggregate(&encrypted.bits, &powers_of_two());
+    let public = commitment.evaluate(encrypted.index).value;
+    let challenge = negate(&encrypted.challenge);
+    let [z1, z2] = &encrypted.responses;
+    let t1 = combine(&g, z1, &randomness, &challenge);
+    let mut t2 = combine(key, z1, &ciphertext, &challenge);
+    let mut shift = g;
+    shift.mul(z2);
+    t2.add(&shift);
+    let t3 = combine(&V::Public::one(), z2, &public, &challenge);
+    let expected = share_challenge::<V>(
+        dealer,
+        encrypted.index,
+        key,
+        &randomness,
+        &ciphertext,
+        &public,
+        (&t1, &t2, &t3),
+    );
+    if encrypted.challenge != expected {
+        return Err(Error::InvalidDealing);
+    }
+    Ok(())
+}
+
+/// Decrypt an encrypted share.
+///
+/// This does not verify the share (which should be done against the dealer's commitment
+/// before it is used).
+pub fn decrypt(key: &DecryptionKey, encrypted: &EncryptedShare) -> Result<Share, Error> {
+    let g = G1::one();
+    let mut private = Scalar::zero();
+    for (bit, power) in encrypted.bits.iter().zip(powers_of_two()) {
+        let mut shared = bit.randomness;
+        shared.mul(key);
+        if shared == bit.ciphertext {
+            continue;
+        }
+        shared.add(&g);
+        if shared != bit.ciphertext {
+            return Err(Error::ShareDecryptionFailed);
+        }
+        private.add(&power);
+    }
+    Ok(Share {
+        index: encrypted.index,
+        private,
+    })
+}
+
+impl EncryptedShare {
+    /// Canonically serializes the encrypted share.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(ENCRYPTED_SHARE_LENGTH);
+        bytes.extend_from_slice(&self.index.to_be_bytes());
+        for bit in &self.bits {
+            bytes.extend_from_slice(&bit.randomness.serialize());
+            bytes.extend_from_slice(&bit.ciphertext.serialize());
+            for s in bit.challenges.iter().chain(bit.responses.iter()) {
+                bytes.extend_from_slice(&s.serialize());
+            }
+        }
+        bytes.extend_from_slice(&self.challenge.serialize());
+        for s in &self.responses {
+            bytes.extend_from_slice(&s.serialize());
+        }
+        bytes
+    }
+
+    /// Deserializes a canonically encoded encrypted share.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != ENCRYPTED_SHARE_LENGTH {
+            return None;
+        }
+        let index = u32::from_be_bytes(bytes[..4].try_into().unwrap());
+        let scalar = |offset: usize| Scalar::deserialize(&bytes[offset..offset + SCALAR_LENGTH]);
+        let mut bits = Vec::with_capacity(SHARE_BITS);
+        for b in 0..SHARE_BITS {
+            let offset = 4 + b * BIT_LENGTH;
+            let randomness = G1::deserialize(&bytes[offset..offset + G1_LENGTH])?;
+            let offset = offset + G1_LENGTH;
+            let ciphertext = G1::deserialize(&bytes[offset..offset + G1_LENGTH])?;
+            let offset = offset + G1_LENGTH;
+            bits.push(EncryptedBit {
+                randomness,
+                ciphertext,
+                challenges: [scalar(offset)?, scalar(offset + SCALAR_LENGTH)?],
+                responses: [
+                    scalar(offset + 2 * SCALAR_LENGTH)?,
+                    scalar(offset + 3 * SCALAR_LENGTH)?,
+                ],
+            });
+        }
+        let offset = 4 + SHARE_BITS * BIT_LENGTH;
+        Some(Self {
+            index,
+            bits,
+            challenge: scalar(offset)?,
+            responses: [
+                scalar(offset + SCALAR_LENGTH)?,
+                scalar(offset + 2 * SCALAR_LENGTH)?,
+            ],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls12381::{
+        dkg::ops::generate_shares,
+        primitives::group::{MinPk, MinSig},
+    };
+    use rand::thread_rng;
+
+    fn encrypt_and_decrypt<V: Variant>() {
+        let mut rng = thread_rng();
+        let (commitment, shares) = generate_shares::<V>(None, 2, 2);
+        let (private, public) = keypair(&mut rng);
+        let encrypted = encrypt::<V, _>(&mut rng, 0, &commitment, &shares[1], &public);
+        verify::<V>(0, &commitment, &public, &encrypted).unwrap();
+        assert!(decrypt(&private, &encrypted).unwrap() == shares[1]);
+
+        // Wrong dealer
+        assert!(matches!(
+            verify::<V>(1, &commitment, &public, &encrypted),
+            Err(Error::InvalidDealing)
+        ));
+
+        // Wrong key
+        let (other_private, other_public) = keypair(&mut rng);
+        assert!(matches!(
+            verify::<V>(0, &commitment, &other_public, &encrypted),
+            Err(Error::InvalidDealing)
+        ));
+        assert!(matches!(
+            decrypt(&other_private, &encrypted),
+            Err(Error::ShareDecryptionFailed)
+        ));
+
+        // Wrong commitment
+        let (other_commitment, _) = generate_shares::<V>(None, 2, 2);
+        assert!(matches!(
+            verify::<V>(0, &other_commitment, &public, &encrypted),
+            Err(Error::InvalidDealing)
+        ));
+
+        // Wrong index
+        let mut tampered = encrypted.clone();
+        tampered.index = 0;
+        assert!(matches!(
+            verify::<V>(0, &commitment, &public, &tampered),
+            Err(Error::InvalidDealing)
+        ));
+    }
+
+    #[test]
+    fn test_encrypt_and_decrypt() {
+        encrypt_and_decrypt::<MinPk>();
+    }
+
+    #[test]
+    fn test_encrypt_and_decrypt_min_sig() {
+        encrypt_and_decrypt::<MinSig>();
+    }
+
+    #[test]
+    fn test_encrypt_wrong_share() {
+        // Encrypt a share that is not on the commitment
+        let mut rng = thread_rng();
+        let (commitment, mut shares) = generate_shares::<MinPk>(None, 2, 2);
+        let (_, public) = keypair(&mut rng);
+        shares[1].private = Scalar::rand(&mut rng);
+        let encrypted = encrypt::<MinPk, _>(&mut rng, 0, &commitment, &shares[1], &public);
+        assert!(matches!(
+            verify::<MinPk>(0, &commitment, &public, &encrypted),
+            Err(Error::InvalidDealing)
+        ));
+    }
+
+    #[test]
+    fn test_tampered_bit() {
+        let mut rng = thread_rng();
+        let (commitment, shares) = generate_shares::<MinPk>(None, 2, 2);
+        let (_, public) = keypair(&mut rng);
+        let mut encrypted = encrypt::<MinPk, _>(&mut rng, 0, &commitment, &shares[0], &public);
+
+        // Flip the encrypted bit (proofs no longer match)
+        let g = G1::one();
+        let bit = &mut encrypted.bits[7];
+        let mut shift = g;
+        if bits(&shares[0].private)[7] {
+            shift.mul(&negate(&Scalar::one()));
+        }
+        bit.ciphertext.add(&shift);
+        assert!(matches!(
+            verify::<MinPk>(0, &commitment, &public, &encrypted),
+            Err(Error::InvalidDealing)
+        ));
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut rng = thread_rng();
+        let (commitment, shares) = generate_shares::<MinPk>(None, 1, 1);
+        let (_, public) = keypair(&mut rng);
+        let encrypted = encrypt::<MinPk, _>(&mut rng, 0, &commitment, &shares[0], &public);
+        let serialized = encrypted.serialize();
+        assert_eq!(serialized.len(), ENCRYPTED_SHARE_LENGTH);
+        let deserialized = EncryptedShare::deserialize(&serialized).unwrap();
+        assert_eq!(encrypted, deserialized);
+        verify::<MinPk>(0, &commitment, &public, &deserialized).unwrap();
+        assert!(EncryptedShare::deserialize(&serialized[1..]).is_none());
+    }
+}
@@ -0,0 +1,399 @@
+//! Deterministic simulation of a DKG procedure.
+//!
+//! [run] drives an arbiter (`arbiter::P0` through `arbiter::P2`) and `n` contributors (`contributor::P0`
+//! through `contributor::P2`) through a complete DKG, where each contributor either behaves honestly or
+//! commits a [Fault]. All randomness is derived from a seed (and all messages are delivered in order of
+//! contributor index), so the same [Config] always produces the same [Outcome].
+//!
+//! Contributors are identified by their index in the sorted list of participants (which uses keys generated
+//! with `ed25519::insecure_signer`).
+//!
+//! # Example
+//!
+//! ```rust
+//! use commonware_cryptography::bls12381::{
+//!     dkg::simulation::{run, Config, Fault},
+//!     primitives::group::MinPk,
+//! };
+//! use std::collections::BTreeMap;
+//!
+//! // Contributor 1 sends invalid shares to 2 recipients
+//! let mut faults = BTreeMap::new();
+//! faults.insert(1, Fault::RogueShares(2));
+//! let outcome = run::<MinPk>(&Config { n: 5, t: 3, seed: 0, faults, concurrency: 1 });
+//! assert!(outcome.output.is_ok());
+//! assert_eq!(outcome.disqualified.into_iter().collect::<Vec<_>>(), vec![1]);
+//! ```
+
+use crate::bls12381::{
+    dkg::{arbiter, contributor, ops, Error},
+    primitives::group::{self, MinPk, Variant},
+};
+use crate::{ed25519::insecure_signer, PublicKey, Scheme};
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Misbehavior of a contributor.
+///
+/// Faults that target `k` recipients target the `k` contributors following the faulty
+/// contributor (in index order, wrapping around).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// Sends shares inconsistent with its commitment to `k` recipients (which complain).
+    RogueShares(u32),
+    /// Does not send shares to `k` recipients (but reveals them when requested).
+    WithheldShares(u32),
+    /// Does not send shares to `k` recipients (and refuses to reveal them when requested).
+    DefiantReveals(u32),
+    /// Posts a commitment of the wrong degree.
+    InvalidCommitment,
+    /// Complains about a valid share from the next dealer.
+    FalseComplaint,
+}
+
+/// Configuration of a simulation.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Number of contributors (each acting as both dealer and recipient).
+    pub n: u32,
+    /// Number of shares required to recover the secret.
+    pub t: u32,
+    /// Seed used to derive all randomness.
+    pub seed: u64,
+    /// Fault committed by each contributor (by index). Contributors not present are honest.
+    pub faults: BTreeMap<u32, Fault>,
+    /// Maximum number of threads used by the arbiter and contributors.
+    pub concurrency: usize,
+}
+
+/// Result of a simulation.
+pub struct Outcome<V: Variant = MinPk> {
+    /// Output of the arbiter (with commitments sorted by dealer).
+    pub output: Result<arbiter::Output<V>, Error>,
+    /// Contributors disqualified by the arbiter.
+    pub disqualified: BTreeSet<u32>,
+    /// Output of each contributor that recovered its share.
+    pub shares: BTreeMap<u32, contributor::Output<V>>,
+}
+
+/// Returns whether `fault` (committed by `dealer`) targets `recipient` (for faults that target
+/// `k` recipients).
+fn targets(n: u32, dealer: u32, recipient: u32, k: u32) -> bool {
+    let distance = (recipient + n - dealer) % n;
+    distance != 0 && distance <= k
+}
+
+/// Runs a DKG procedure with the provided configuration.
+pub fn run<V: Variant>(config: &Config) -> Outcome<V> {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let (n, t) = (config.n, config.t);
+    let fault = |i: u32| config.faults.get(&i).copied();
+
+    // Create contributors (in sorted order)
+    let mut participants = (0..n)
+        .map(|i| insecure_signer(i as u16).me())
+        .collect::<Vec<_>>();
+    participants.sort();
+    let index = participants
+        .iter()
+        .enumerate()
+        .map(|(i, pk)| (pk.clone(), i as u32))
+        .collect::<HashMap<PublicKey, u32>>();
+    let disqualified =
+        |set: HashSet<PublicKey>| set.iter().map(|pk| index[pk]).collect::<BTreeSet<_>>();
+
+    // Generate commitments and shares
+    let mut dealings = Vec::with_capacity(n as usize);
+    let mut contributors = BTreeMap::new();
+    for (i, me) in participants.iter().enumerate() {
+        let p0 = contributor::P0::<V>::new(
+            me.clone(),
+            t,
+            None,
+            participants.clone(),
+            participants.clone(),
+            config.concurrency,
+        );
+        let (p1, commitment, shares) = p0.finalize_from(&mut rng);
+        dealings.push((commitment, shares));
+        contributors.insert(i as u32, p1.unwrap());
+    }
+
+    // Post commitments to the arbiter
+    let mut arb = arbiter::P0::<V>::new(
+        t,
+        None,
+        participants.clone(),
+        participants.clone(),
+        config.concurrency,
+    );
+    for (i, dealer) in participants.iter().enumerate() {
+        let commitment = match fault(i as u32) {
+            Some(Fault::InvalidCommitment) => {
+                ops::generate_shares_from::<V, _>(&mut rng, None, n, t + 1).0
+            }
+            _ => dealings[i].0.clone(),
+        };
+        let _ = arb.commitment(dealer.clone(), commitment);
+    }
+    let (result, dq) = arb.finalize();
+    let mut arb = match result {
+        Some(arb) => arb,
+        None => return failed(Error::InsufficientDealings, disqualified(dq)),
+    };
+
+    // Distribute commitments to contributors
+    let mut commitments = arb.commitments();
+    commitments.sort_by_key(|(dealer, _, _)| *dealer);
+    for (_, dealer, commitment) in &commitments {
+        for p1 in contributors.values_mut() {
+            p1.commitment(dealer.clone(), commitment.clone())
+                .expect("arbiter only distributes valid commitments");
+        }
+    }
+    let mut contributors = contributors
+        .into_iter()
+        .map(|(i, p1)| {
+            let p2 = p1
+                .finalize()
+                .expect("arbiter only proceeds with enough commitments");
+            (i, p2)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    // Distribute shares to recipients and submit acks/complaints to the arbiter
+    for (dealer, dealer_key, _) in &commitments {
+        let shares = &dealings[*dealer as usize].1;
+        for (recipient, recipient_key) in participants.iter().enumerate() {
+            let recipient = recipient as u32;
+            let mut share = shares[recipient as usize].clone();
+            match fault(*dealer) {
+                Some(Fault::WithheldShares(k)) | Some(Fault::DefiantReveals(k))
+                    if targets(n, *dealer, recipient, k) =>
+                {
+                    continue;
+                }
+                Some(Fault::RogueShares(k)) if targets(n, *dealer, recipient, k) => {
+                    share.private = group::Private::rand(&mut rng);
+                }
+                _ => {}
+            }
+
+            // Deliver the share
+            let p2 = contributors.get_mut(&recipient).unwrap();
+            let valid = p2.share(dealer_key.clone(), share.clone()).is_ok();
+            if recipient == *dealer {
+                continue;
+            }
+
+            // Submit an ack or complaint
+            let complain = !valid
+                || (fault(recipient) == Some(Fault::FalseComplaint)
+                    && *dealer == (recipient + 1) % n);
+            let _ = match complain {
+                true => arb.complaint(recipient_key.clone(), *dealer, &share),
+                false => arb.ack(recipient_key.clone(), *dealer),
+            };
+        }
+    }
+    let (result, dq) = arb.finalize();
+    let (mut arb, mut requests) = match result {
+        Some(transition) => transition,
+        None => return failed(Error::InsufficientDealings, disqualified(dq)),
+    };
+
+    // Reveal requested shares
+    requests.sort();
+    for (dealer, recipient) in requests {
+        if matches!(fault(dealer), Some(Fault::DefiantReveals(_))) {
+            continue;
+        }
+        let share = dealings[dealer as usize].1[recipient as usize].clone();
+        let _ = arb.reveal(participants[dealer as usize].clone(), share);
+    }
+    let (result, dq) = arb.finalize();
+    let disqualified = disqualified(dq);
+    let mut output = match result {
+        Ok(output) => output,
+        Err(e) => return failed(e, disqualified),
+    };
+    output.commitments.sort();
+
+    // Deliver reveals and recover shares
+    let mut resolutions = output.resolutions.iter().collect::<Vec<_>>();
+    resolutions.sort_by_key(|(key, _)| **key);
+    for ((dealer, recipient), share) in resolutions {
+        if let Some(p2) = contributors.get_mut(recipient) {
+            let _ = p2.share(participants[*dealer as usize].clone(), share.clone());
+        }
+    }
+    let shares = contributors
+        .into_iter()
+        .filter_map(|(i, p2)| {
+            p2.finalize(output.commitments.clone())
+                .ok()
+                .map(|output| (i, output))
+        })
+        .collect();
+    Outcome {
+        output: Ok(output),
+        disqualified,
+        shares,
+    }
+}
+
+/// Returns the outcome of a simulation that could not complete.
+fn failed<V: Variant>(error: Error, disqualified: BTreeSet<u32>) -> Outcome<V> {
+    Outcome {
+        output: Err(error),
+        disqualified,
+        shares: BTreeMap::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls12381::primitives::{
+        group::MinSig,
+        ops::{aggregate, partial_sign, verify},
+    };
+
+    fn config(n: u32, t: u32, seed: u64, faults: &[(u32, Fault)]) -> Config {
+        Config {
+            n,
+            t,
+            seed,
+            faults: faults.iter().copied().collect(),
+            concurrency: 1,
+        }
+    }
+
+    /// Runs a simulation and verifies that the recovered shares can generate a threshold signature.
+    fn simulate<V: Variant>(config: &Config) -> Outcome<V> {
+        let outcome = run::<V>(config);
+        if let Ok(output) = &outcome.output {
+            let partials = outcome
+                .shares
+                .values()
+                .map(|result| {
+                    assert_eq!(result.public, output.public);
+                    partial_sign::<V>(result.share().unwrap(), b"test")
+                })
+                .collect::<Vec<_>>();
+            let signature = aggregate::<V>(config.t, partials).unwrap();
+            verify::<V>(output.public.constant(), b"test", &signature).unwrap();
+        }
+        outcome
+    }
+
+    fn disqualified(outcome: &Outcome<impl Variant>) -> Vec<u32> {
+        outcome.disqualified.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_honest() {
+        let outcome = simulate::<MinPk>(&config(5, 3, 0, &[]));
+        assert!(outcome.disqualified.is_empty());
+        assert_eq!(outcome.shares.len(), 5);
+        assert_eq!(outcome.output.unwrap().commitments, vec![0, 1, 2, 3, 4]);
+
+        let outcome = simulate::<MinSig>(&config(5, 3, 0, &[]));
+        assert_eq!(outcome.shares.len(), 5);
+    }
+
+    #[test]
+    fn test_determinism() {
+        let faults = [(1, Fault::WithheldShares(1)), (3, Fault::RogueShares(2))];
+        let a = simulate::<MinPk>(&config(7, 5, 42, &faults));
+        let b = simulate::<MinPk>(&config(7, 5, 42, &faults));
+        let (a_output, b_output) = (a.output.unwrap(), b.output.unwrap());
+        assert_eq!(a_output.serialize(), b_output.serialize());
+        assert_eq!(a.disqualified, b.disqualified);
+        for (a, b) in a.shares.values().zip(b.shares.values()) {
+            assert_eq!(a.serialize(), b.serialize());
+        }
+
+        // A different seed generates a different group
+        let c = simulate::<MinPk>(&config(7, 5, 43, &faults));
+        assert_ne!(c.output.unwrap().public, a_output.public);
+    }
+
+    #[test]
+    fn test_rogue_shares() {
+        let outcome = simulate::<MinPk>(&config(5, 3, 0, &[(1, Fault::RogueShares(2))]));
+        assert_eq!(disqualified(&outcome), vec![1]);
+        assert_eq!(outcome.shares.len(), 5);
+    }
+
+    #[test]
+    fn test_withheld_shares() {
+        let outcome = simulate::<MinPk>(&config(5, 3, 0, &[(1, Fault::WithheldShares(1))]));
+        assert!(outcome.disqualified.is_empty());
+        let output = outcome.output.unwrap();
+        assert_eq!(output.resolutions.len(), 1);
+        assert!(output.resolutions.contains_key(&(1, 2)));
+        assert_eq!(outcome.shares.len(), 5);
+    }
+
+    #[test]
+    fn test_withheld_too_many_shares() {
+        // Dealer 1 does not collect `t - 1` acks
+        let outcome = simulate::<MinPk>(&config(5, 3, 0, &[(1, Fault::WithheldShares(3))]));
+        assert_eq!(disqualified(&outcome), vec![1]);
+        assert_eq!(outcome.shares.len(), 5);
+    }
+
+    #[test]
+    fn test_defiant_reveals() {
+        let outcome = simulate::<MinPk>(&config(5, 3, 0, &[(1, Fault::DefiantReveals(1))]));
+        assert_eq!(disqualified(&outcome), vec![1]);
+        let output = outcome.output.unwrap();
+        assert!(!output.commitments.contains(&1));
+        assert_eq!(outcome.shares.len(), 5);
+    }
+
+    #[test]
+    fn test_invalid_commitment() {
+        let outcome = simulate::<MinPk>(&config(5, 3, 0, &[(4, Fault::InvalidCommitment)]));
+        assert_eq!(disqualified(&outcome), vec![4]);
+        assert_eq!(outcome.output.unwrap().commitments, vec![0, 1, 2, 3]);
+
+        // The disqualified dealer still recovers a share
+        assert_eq!(outcome.shares.len(), 5);
+    }
+
+    #[test]
+    fn test_false_complaint() {
+        let outcome = simulate::<MinPk>(&config(5, 3, 0, &[(2, Fault::FalseComplaint)]));
+        assert_eq!(disqualified(&outcome), vec![2]);
+        assert_eq!(outcome.output.unwrap().commitments, vec![0, 1, 3, 4]);
+    }
+
+    #[test]
+    fn test_combined_faults() {
+        let faults = [
+            (0, Fault::InvalidCommitment),
+            (2, Fault::RogueShares(1)),
+            (4, Fault::DefiantReveals(1)),
+            (5, Fault::WithheldShares(2)),
+            (6, Fault::FalseComplaint),
+        ];
+        let outcome = simulate::<MinPk>(&config(10, 4, 7, &faults));
+        assert_eq!(disqualified(&outcome), vec![0, 2, 4, 6]);
+        assert_eq!(outcome.output.unwrap().commitments, vec![1, 3, 5, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_insufficient_dealings() {
+        let faults = [
+            (0, Fault::InvalidCommitment),
+            (1, Fault::InvalidCommitment),
+            (2, Fault::InvalidCommitment),
+        ];
+        let outcome = simulate::<MinPk>(&config(5, 3, 0, &faults));
+        assert!(matches!(outcome.output, Err(Error::InsufficientDealings)));
+        assert_eq!(disqualified(&outcome), vec![0, 1, 2]);
+        assert!(outcome.shares.is_empty());
+    }
+}
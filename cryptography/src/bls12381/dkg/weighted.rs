@@ -0,0 +1,205 @@
+//! Weighted threshold sharing.
+//!
+//! By default, each recipient of a DKG owns exactly one share (at its index in the sorted list of
+//! recipients). In weighted mode (`arbiter::P0::new_weighted` and `contributor::P0::new_weighted`),
+//! a recipient with weight `w` instead owns `w` consecutive share indices (assigned in the order of
+//! the sorted recipients) and the threshold is expressed in weight (the number of shares required to
+//! recover the secret).
+//!
+//! Acks (and complaints) are still submitted once per recipient (covering all of its indices) and a
+//! recipient produces one partial signature per share it owns (with `partial_sign`). Partial signatures
+//! are then aggregated per signer (with `aggregate`), which only accepts the partial signatures of a
+//! signer if they cover all (and only) the indices it owns.
+//!
+//! Resharing is not supported in weighted mode.
+
+use crate::{
+    bls12381::{
+        dkg::Error,
+        primitives::{
+            group::{Share, Variant},
+            ops,
+            poly::Eval,
+        },
+    },
+    PublicKey,
+};
+use std::{collections::BTreeMap, ops::Range};
+
+/// Assignment of share indices to recipients (by their index in the sorted list of recipients).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Allocation {
+    // The first share index of each recipient (and the total number of shares)
+    offsets: Vec<u32>,
+}
+
+impl Allocation {
+    /// Creates an allocation where recipient `i` owns `weights[i]` shares.
+    ///
+    /// Returns `None` if any weight is zero or the total weight overflows.
+    pub fn new(weights: &[u32]) -> Option<Self> {
+        let mut offsets = Vec::with_capacity(weights.len() + 1);
+        let mut total = 0u32;
+        offsets.push(total);
+        for weight in weights {
+            if *weight == 0 {
+                return None;
+            }
+            total = total.checked_add(*weight)?;
+            offsets.push(total);
+        }
+        Some(Self { offsets })
+    }
+
+    /// Creates an allocation where each of `n` recipients owns a single share.
+    pub fn uniform(n: u32) -> Self {
+        Self {
+            offsets: (0..=n).collect(),
+        }
+    }
+
+    /// Returns the weight of each recipient.
+    pub fn weights(&self) -> Vec<u32> {
+        self.offsets.windows(2).map(|w| w[1] - w[0]).collect()
+    }
+
+    /// Returns the number of recipients.
+    pub fn recipients(&self) -> u32 {
+        (self.offsets.len() - 1) as u32
+    }
+
+    /// Returns the total number of shares.
+    pub fn total(&self) -> u32 {
+        *self.offsets.last().unwrap()
+    }
+
+    /// Returns the weight of a recipient (or `0` if it does not exist).
+    pub fn weight(&self, recipient: u32) -> u32 {
+        let range = self.indices(recipient);
+        range.end - range.start
+    }
+
+    /// Returns the share indices owned by a recipient (empty if it does not exist).
+    pub fn indices(&self, recipient: u32) -> Range<u32> {
+        let recipient = recipient as usize;
+        if recipient + 1 >= self.offsets.len() {
+            return 0..0;
+        }
+        self.offsets[recipient]..self.offsets[recipient + 1]
+    }
+
+    /// Returns the recipient that owns a share index.
+    pub fn owner(&self, index: u32) -> Option<u32> {
+        if index >= self.total() {
+            return None;
+        }
+        let recipient = self.offsets.partition_point(|offset| *offset <= index) - 1;
+        Some(recipient as u32)
+    }
+}
+
+/// Sorts weighted recipients and assigns them share indices (in sorted order).
+///
+/// If any weight is zero (or the total weight overflows), this will panic.
+pub(super) fn sort(mut recipients: Vec<(PublicKey, u32)>) -> (Vec<PublicKey>, Allocation) {
+    recipients.sort();
+    let weights = recipients
+        .iter()
+        .map(|(_, weight)| *weight)
+        .collect::<Vec<_>>();
+    let allocation = Allocation::new(&weights).expect("weights must be non-zero");
+    let recipients = recipients.into_iter().map(|(pk, _)| pk).collect();
+    (recipients, allocation)
+}
+
+/// Signs the provided message with each share owned by a signer.
+pub fn partial_sign<V: Variant>(shares: &[Share], msg: &[u8]) -> Vec<Eval<V::Signature>> {
+    shares
+        .iter()
+        .map(|share| ops::partial_sign::<V>(share, msg))
+        .collect()
+}
+
+/// Aggregates the partial signatures of each signer into a final signature.
+///
+/// The partial signatures of a signer (keyed by its index in the sorted list of recipients)
+/// must cover exactly the share indices it owns. Any signer that does not is skipped (and
+/// returned alongside the result). Valid signers are included (in order) until their
+/// combined weight reaches `threshold`.
+///
+/// Partial signatures are not verified (use `ops::partial_verify` on any partial signature
+/// received from an untrusted party).
+pub fn aggregate<V: Variant>(
+    allocation: &Allocation,
+    threshold: u32,
+    partials: BTreeMap<u32, Vec<Eval<V::Signature>>>,
+) -> (Result<V::Signature, Error>, Vec<u32>) {
+    let mut weight = 0;
+    let mut selected = Vec::new();
+    let mut invalid = Vec::new();
+    for (signer, mut signer_partials) in partials {
+        // Skip any signer that did not submit a partial signature for each of its shares
+        let indices = allocation.indices(signer);
+        signer_partials.sort_by_key(|partial| partial.index);
+        if indices.is_empty()
+            || signer_partials.len() != indices.len()
+            || signer_partials
+                .iter()
+                .zip(indices.clone())
+                .any(|(partial, index)| partial.index != index)
+        {
+            invalid.push(signer);
+            continue;
+        }
+
+        // Include the signer
+        weight += indices.len() as u32;
+        selected.extend(signer_partials);
+        if weight >= threshold {
+            break;
+        }
+    }
+    if weight < threshold {
+        return (Err(Error::InsufficientWeight), invalid);
+    }
+
+    // Each signer owns distinct indices, so recovery cannot fail
+    let signature =
+        ops::aggregate::<V>(threshold, selected).expect("partial signatures are unique");
+    (Ok(signature), invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocation() {
+        let allocation = Allocation::new(&[3, 1, 2]).unwrap();
+        assert_eq!(allocation.recipients(), 3);
+        assert_eq!(allocation.total(), 6);
+        assert_eq!(allocation.weights(), vec![3, 1, 2]);
+        assert_eq!(allocation.indices(0), 0..3);
+        assert_eq!(allocation.indices(1), 3..4);
+        assert_eq!(allocation.indices(2), 4..6);
+        assert!(allocation.indices(3).is_empty());
+        assert_eq!(allocation.weight(3), 0);
+        let owners = (0..7).map(|i| allocation.owner(i)).collect::<Vec<_>>();
+        assert_eq!(
+            owners,
+            vec![Some(0), Some(0), Some(0), Some(1), Some(2), Some(2), None]
+        );
+
+        // Uniform allocations assign each recipient its own index
+        let uniform = Allocation::uniform(4);
+        assert_eq!(uniform, Allocation::new(&[1, 1, 1, 1]).unwrap());
+        for i in 0..4 {
+            assert_eq!(uniform.indices(i), i..i + 1);
+            assert_eq!(uniform.owner(i), Some(i));
+        }
+
+        // Invalid weights
+        assert!(Allocation::new(&[1, 0, 1]).is_none());
+        assert!(Allocation::new(&[u32::MAX, 1]).is_none());
+    }
+}
@@ -0,0 +1,518 @@
+//! Threshold decryption of messages encrypted to the group public key of a DKG.
+//!
+//! A message is encrypted (with hashed ElGamal) to the group public key `poly::public(commitment)`.
+//! To decrypt it, each contributor produces a [PartialDecryption] with its [Share] (`partial_decrypt`)
+//! alongside a proof that it was generated with that share. Any `threshold` valid partial decryptions
+//! can then be combined to decrypt the message (`decrypt`). Fewer than `threshold` contributors learn
+//! nothing about the message, which makes this a good fit for sealed-bid workflows (where bids must
+//! remain hidden until bidding closes).
+//!
+//! # Construction
+//!
+//! Ciphertexts live in the public key group of the [Variant] (G1 for `MinPk`). For a group public key
+//! `Y = g^s`, the sender samples `r`, computes `R = g^r`, and encrypts the message with ChaCha20-Poly1305
+//! under a key derived from `(R, Y^r)`. The sender also proves knowledge of `r` (bound to the encrypted
+//! message) so that contributors never partially decrypt a ciphertext derived from another one
+//! (which would let an attacker use the contributors as a decryption oracle).
+//!
+//! The partial decryption of the contributor at index `i` is `D_i = R^{s_i}` with a proof that
+//! `log_g(Y_i) = log_R(D_i)` (where `Y_i` is the public polynomial evaluated at `i`). As with partial
+//! signatures, `D = R^s` is recovered from `threshold` partial decryptions with `Poly::recover`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use commonware_cryptography::bls12381::{
+//!     dkg::ops::generate_shares,
+//!     elgamal::{encrypt, partial_decrypt, partial_verify, decrypt},
+//!     primitives::{group::MinPk, poly::public},
+//! };
+//! use rand::rngs::OsRng;
+//!
+//! // Generate a group public key (and shares)
+//! let (n, t) = (5, 3);
+//! let (commitment, shares) = generate_shares::<MinPk>(None, n, t);
+//!
+//! // Encrypt a bid to the group
+//! let ciphertext = encrypt::<MinPk, _>(&mut OsRng, &public::<MinPk>(&commitment), b"bid: 100");
+//!
+//! // Once bidding closes, generate (and verify) partial decryptions
+//! let partials: Vec<_> = shares
+//!     .iter()
+//!     .map(|s| partial_decrypt::<MinPk>(s, &ciphertext).unwrap())
+//!     .collect();
+//! for p in &partials {
+//!     partial_verify::<MinPk>(&commitment, &ciphertext, p).expect("partial decryption should be valid");
+//! }
+//!
+//! // Combine partial decryptions
+//! assert_eq!(decrypt::<MinPk>(t, &ciphertext, partials).unwrap(), b"bid: 100");
+//! ```
+
+use crate::bls12381::primitives::{
+    group::{Element, MinPk, Scalar, Share, Variant},
+    poly::{self, Eval},
+    Error,
+};
+use chacha20poly1305::{
+    aead::{Aead, Payload},
+    ChaCha20Poly1305, KeyInit,
+};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+/// Domain separation tag used to derive the message key.
+const DST_KEY: &[u8] = b"COMMONWARE_BLS12381_ELGAMAL_KEY_";
+
+/// Domain separation tag used for the proof of knowledge of the encryption randomness.
+const DST_CIPHERTEXT: &[u8] = b"COMMONWARE_BLS12381_ELGAMAL_CIPHERTEXT_";
+
+/// Domain separation tag used for the proof that a partial decryption matches a share.
+const DST_PARTIAL: &[u8] = b"COMMONWARE_BLS12381_ELGAMAL_PARTIAL_";
+
+/// Domain separation tag used to derive the (deterministic) nonce of a partial decryption proof.
+const DST_NONCE: &[u8] = b"COMMONWARE_BLS12381_ELGAMAL_NONCE_";
+
+const SCALAR_LENGTH: usize = 32;
+
+/// Length of the authentication tag appended to the encrypted message.
+const TAG_LENGTH: usize = 16;
+
+/// Message encrypted to the group public key.
+#[derive(Clone, Debug)]
+pub struct Ciphertext<V: Variant = MinPk> {
+    randomness: V::Public,
+    message: Vec<u8>,
+
+    // Proof of knowledge of the encryption randomness
+    challenge: Scalar,
+    response: Scalar,
+}
+
+impl<V: Variant> Ciphertext<V> {
+    /// Verifies the proof of knowledge of the encryption randomness.
+    pub fn verify(&self) -> bool {
+        // Recover the commitment (g^z * R^-c)
+        let mut negated = Scalar::zero();
+        negated.sub(&self.challenge);
+        let commitment = V::Public::msm(
+            &[V::Public::one(), self.randomness],
+            &[self.response, negated],
+        );
+        self.challenge == ciphertext_challenge::<V>(&self.randomness, &commitment, &self.message)
+    }
+
+    /// Canonically serializes the ciphertext.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes =
+            Vec::with_capacity(V::Public::size() + 2 * SCALAR_LENGTH + 4 + self.message.len());
+        bytes.extend_from_slice(&self.randomness.serialize());
+        bytes.extend_from_slice(&self.challenge.serialize());
+        bytes.extend_from_slice(&self.response.serialize());
+        bytes.extend_from_slice(&(self.message.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&self.message);
+        bytes
+    }
+
+    /// Deserializes a canonically encoded ciphertext.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let header = V::Public::size() + 2 * SCALAR_LENGTH + 4;
+        if bytes.len() < header {
+            return None;
+        }
+        let (randomness, rest) = bytes.split_at(V::Public::size());
+        let randomness = V::Public::deserialize(randomness)?;
+        if randomness == V::Public::zero() {
+            return None;
+        }
+        let challenge = Scalar::deserialize(&rest[..SCALAR_LENGTH])?;
+        let response = Scalar::deserialize(&rest[SCALAR_LENGTH..2 * SCALAR_LENGTH])?;
+        let length = u32::from_be_bytes(
+            rest[2 * SCALAR_LENGTH..2 * SCALAR_LENGTH + 4]
+                .try_into()
+                .unwrap(),
+        ) as usize;
+        if length < TAG_LENGTH || bytes.len() - header != length {
+            return None;
+        }
+        Some(Self {
+            randomness,
+            message: bytes[header..].to_vec(),
+            challenge,
+            response,
+        })
+    }
+}
+
+/// Partial decryption of a ciphertext generated by a share.
+#[derive(Clone, Debug)]
+pub struct PartialDecryption<V: Variant = MinPk> {
+    pub index: u32,
+    pub value: V::Public,
+
+    // Proof that the partial decryption matches the share
+    challenge: Scalar,
+    response: Scalar,
+}
+
+impl<V: Variant> PartialDecryption<V> {
+    /// Canonically serializes the partial decryption.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + V::Public::size() + 2 * SCALAR_LENGTH);
+        bytes.extend_from_slice(&self.index.to_be_bytes());
+        bytes.extend_from_slice(&self.value.serialize());
+        bytes.extend_from_slice(&self.challenge.serialize());
+        bytes.extend_from_slice(&self.response.serialize());
+        bytes
+    }
+
+    /// Deserializes a canonically encoded partial decryption.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let value_length = V::Public::size();
+        if bytes.len() != 4 + value_length + 2 * SCALAR_LENGTH {
+            return None;
+        }
+        let index = u32::from_be_bytes(bytes[..4].try_into().unwrap());
+        let value = V::Public::deserialize(&bytes[4..4 + value_length])?;
+        let challenge =
+            Scalar::deserialize(&bytes[4 + value_length..4 + value_length + SCALAR_LENGTH])?;
+        let response = Scalar::deserialize(&bytes[4 + value_length + SCALAR_LENGTH..])?;
+        Some(Self {
+            index,
+            value,
+            challenge,
+            response,
+        })
+    }
+}
+
+/// Computes the challenge of the proof of knowledge of the encryption randomness.
+fn ciphertext_challenge<V: Variant>(
+    randomness: &V::Public,
+    commitment: &V::Public,
+    message: &[u8],
+) -> Scalar {
+    let mut transcript = Vec::with_capacity(2 * V::Public::size() + message.len());
+    transcript.extend_from_slice(&randomness.serialize());
+    transcript.extend_from_slice(&commitment.serialize());
+    transcript.extend_from_slice(message);
+    Scalar::map(DST_CIPHERTEXT, &transcript)
+}
+
+/// Computes the challenge of the proof that `log_g(public) = log_randomness(value)`.
+fn partial_challenge<V: Variant>(
+    public: &V::Public,
+    randomness: &V::Public,
+    value: &V::Public,
+    commitments: [&V::Public; 2],
+) -> Scalar {
+    let mut transcript = Vec::with_capacity(5 * V::Public::size());
+    for element in [public, randomness, value, commitments[0], commitments[1]] {
+        transcript.extend_from_slice(&element.serialize());
+    }
+    Scalar::map(DST_PARTIAL, &transcript)
+}
+
+/// Derives the message key from the encryption randomness and the shared secret.
+fn key<V: Variant>(randomness: &V::Public, shared: &V::Public) -> Zeroizing<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    hasher.update(DST_KEY);
+    hasher.update(randomness.serialize());
+    hasher.update(shared.serialize());
+    Zeroizing::new(hasher.finalize().into())
+}
+
+/// Encrypts the provided message to the group public key (`poly::public(commitment)`).
+pub fn encrypt<V: Variant, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    public: &V::Public,
+    message: &[u8],
+) -> Ciphertext<V> {
+    // Compute R = g^r and the shared secret Y^r
+    let r = Zeroizing::new(Scalar::rand(rng));
+    let mut randomness = V::Public::one();
+    randomness.mul(&r);
+    let mut shared = *public;
+    shared.mul(&r);
+
+    // Encrypt the message
+    let cipher = ChaCha20Poly1305::new(key::<V>(&randomness, &shared).as_ref().into());
+    let message = cipher
+        .encrypt(
+            &Default::default(),
+            Payload {
+                msg: message,
+                aad: &randomness.serialize(),
+            },
+        )
+        .expect("encryption should not fail");
+
+    // Prove knowledge of r
+    let k = Zeroizing::new(Scalar::rand(rng));
+    let mut commitment = V::Public::one();
+    commitment.mul(&k);
+    let challenge = ciphertext_challenge::<V>(&randomness, &commitment, &message);
+    let mut response = challenge;
+    response.mul(&r);
+    response.add(&k);
+    Ciphertext {
+        randomness,
+        message,
+        challenge,
+        response,
+    }
+}
+
+/// Partially decrypts the ciphertext with the key share.
+///
+/// Returns an error if the ciphertext is invalid.
+pub fn partial_decrypt<V: Variant>(
+    private: &Share,
+    ciphertext: &Ciphertext<V>,
+) -> Result<PartialDecryption<V>, Error> {
+    if !ciphertext.verify() {
+        return Err(Error::InvalidCiphertext);
+    }
+
+    // Compute D_i = R^{s_i}
+    let mut value = ciphertext.randomness;
+    value.mul(&private.private);
+
+    // Prove that log_g(Y_i) = log_R(D_i) (the nonce is derived from the share and the
+    // ciphertext, so it is never reused across statements)
+    let mut input = Zeroizing::new(private.private.serialize());
+    input.extend_from_slice(&ciphertext.randomness.serialize());
+    let k = Zeroizing::new(Scalar::map(DST_NONCE, &input));
+    let mut commitment_g = V::Public::one();
+    commitment_g.mul(&k);
+    let mut commitment_r = ciphertext.randomness;
+    commitment_r.mul(&k);
+    let challenge = partial_challenge::<V>(
+        &private.public::<V>(),
+        &ciphertext.randomness,
+        &value,
+        [&commitment_g, &commitment_r],
+    );
+    let mut response = challenge;
+    response.mul(&private.private);
+    response.add(&k);
+    Ok(PartialDecryption {
+        index: private.index,
+        value,
+        challenge,
+        response,
+    })
+}
+
+/// Verifies the partial decryption against the public polynomial.
+pub fn partial_verify<V: Variant>(
+    public: &poly::Public<V>,
+    ciphertext: &Ciphertext<V>,
+    partial: &PartialDecryption<V>,
+) -> Result<(), Error> {
+    // Recover the commitments (g^z * Y_i^-c and R^z * D_i^-c)
+    let public = public.evaluate(partial.index).value;
+    let mut negated = Scalar::zero();
+    negated.sub(&partial.challenge);
+    let commitment_g = V::Public::msm(&[V::Public::one(), public], &[partial.response, negated]);
+    let commitment_r = V::Public::msm(
+        &[ciphertext.randomness, partial.value],
+        &[partial.response, negated],
+    );
+    let challenge = partial_challenge::<V>(
+        &public,
+        &ciphertext.randomness,
+        &partial.value,
+        [&commitment_g, &commitment_r],
+    );
+    if challenge != partial.challenge {
+        return Err(Error::InvalidPartialDecryption);
+    }
+    Ok(())
+}
+
+/// Combines the partial decryptions to decrypt the ciphertext.
+///
+/// Partial decryptions are not verified (use `partial_verify` on any partial
+/// decryption received from an untrusted party).
+pub fn decrypt<V: Variant>(
+    threshold: u32,
+    ciphertext: &Ciphertext<V>,
+    partials: Vec<PartialDecryption<V>>,
+) -> Result<Vec<u8>, Error> {
+    if threshold as usize > partials.len() {
+        return Err(Error::NotEnoughPartialDecryptions);
+    }
+
+    // Recover the shared secret R^s
+    let evals = partials
+        .into_iter()
+        .map(|partial| Eval {
+            index: partial.index,
+            value: partial.value,
+        })
+        .collect();
+    let shared = poly::Public::<V>::recover(threshold, evals)?;
+
+    // Decrypt the message
+    let cipher = ChaCha20Poly1305::new(key::<V>(&ciphertext.randomness, &shared).as_ref().into());
+    cipher
+        .decrypt(
+            &Default::default(),
+            Payload {
+                msg: &ciphertext.message,
+                aad: &ciphertext.randomness.serialize(),
+            },
+        )
+        .map_err(|_| Error::DecryptionFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls12381::{
+        dkg::ops::generate_shares,
+        primitives::{group::MinSig, poly::public},
+    };
+    use rand::thread_rng;
+
+    fn run_threshold_decrypt<V: Variant>() {
+        let mut rng = thread_rng();
+        let (n, t) = (5, 3);
+        let (commitment, shares) = generate_shares::<V>(None, n, t);
+        let message = b"sealed bid";
+        let ciphertext = encrypt::<V, _>(&mut rng, &public::<V>(&commitment), message);
+        assert!(ciphertext.verify());
+
+        // Generate and verify partial decryptions
+        let partials = shares
+            .iter()
+            .map(|s| partial_decrypt::<V>(s, &ciphertext).unwrap())
+            .collect::<Vec<_>>();
+        for partial in &partials {
+            partial_verify::<V>(&commitment, &ciphertext, partial).unwrap();
+        }
+
+        // Any `t` partial decryptions decrypt the message
+        assert_eq!(
+            decrypt::<V>(t, &ciphertext, partials[..t as usize].to_vec()).unwrap(),
+            message
+        );
+        assert_eq!(
+            decrypt::<V>(t, &ciphertext, partials[(n - t) as usize..].to_vec()).unwrap(),
+            message
+        );
+    }
+
+    #[test]
+    fn test_threshold_decrypt() {
+        run_threshold_decrypt::<MinPk>();
+    }
+
+    #[test]
+    fn test_threshold_decrypt_min_sig() {
+        run_threshold_decrypt::<MinSig>();
+    }
+
+    #[test]
+    fn test_decrypt_insufficient() {
+        let mut rng = thread_rng();
+        let (n, t) = (5, 3);
+        let (commitment, shares) = generate_shares::<MinPk>(None, n, t);
+        let ciphertext = encrypt::<MinPk, _>(&mut rng, &public::<MinPk>(&commitment), b"bid");
+        let partials = shares
+            .iter()
+            .take(t as usize - 1)
+            .map(|s| partial_decrypt::<MinPk>(s, &ciphertext).unwrap())
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            decrypt::<MinPk>(t, &ciphertext, partials.clone()),
+            Err(Error::NotEnoughPartialDecryptions)
+        ));
+
+        // Duplicate partial decryptions do not count towards the threshold
+        let mut duplicated = partials.clone();
+        duplicated.push(partials[0].clone());
+        assert!(matches!(
+            decrypt::<MinPk>(t, &ciphertext, duplicated),
+            Err(Error::DuplicateEval)
+        ));
+    }
+
+    #[test]
+    fn test_partial_verify_wrong_share() {
+        let mut rng = thread_rng();
+        let (n, t) = (5, 3);
+        let (commitment, mut shares) = generate_shares::<MinPk>(None, n, t);
+        let ciphertext = encrypt::<MinPk, _>(&mut rng, &public::<MinPk>(&commitment), b"bid");
+
+        // Corrupt a share
+        shares[1].private = Scalar::rand(&mut rng);
+        let partials = shares
+            .iter()
+            .map(|s| partial_decrypt::<MinPk>(s, &ciphertext).unwrap())
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            partial_verify::<MinPk>(&commitment, &ciphertext, &partials[1]),
+            Err(Error::InvalidPartialDecryption)
+        ));
+
+        // A partial decryption cannot be attributed to another index
+        let mut misattributed = partials[0].clone();
+        misattributed.index = 2;
+        assert!(partial_verify::<MinPk>(&commitment, &ciphertext, &misattributed).is_err());
+
+        // A partial decryption of another ciphertext is rejected
+        let other = encrypt::<MinPk, _>(&mut rng, &public::<MinPk>(&commitment), b"bid");
+        assert!(partial_verify::<MinPk>(&commitment, &other, &partials[0]).is_err());
+
+        // Combining an invalid partial decryption fails
+        assert!(matches!(
+            decrypt::<MinPk>(t, &ciphertext, partials[..t as usize].to_vec()),
+            Err(Error::DecryptionFailed)
+        ));
+    }
+
+    #[test]
+    fn test_partial_decrypt_mauled_ciphertext() {
+        let mut rng = thread_rng();
+        let (commitment, shares) = generate_shares::<MinPk>(None, 5, 3);
+        let ciphertext = encrypt::<MinPk, _>(&mut rng, &public::<MinPk>(&commitment), b"bid");
+
+        // Modify the randomness
+        let mut mauled = ciphertext.clone();
+        mauled.randomness.add(&<MinPk as Variant>::Public::one());
+        assert!(matches!(
+            partial_decrypt::<MinPk>(&shares[0], &mauled),
+            Err(Error::InvalidCiphertext)
+        ));
+
+        // Modify the encrypted message
+        let mut mauled = ciphertext.clone();
+        mauled.message[0] ^= 1;
+        assert!(matches!(
+            partial_decrypt::<MinPk>(&shares[0], &mauled),
+            Err(Error::InvalidCiphertext)
+        ));
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut rng = thread_rng();
+        let (commitment, shares) = generate_shares::<MinPk>(None, 5, 3);
+        let ciphertext = encrypt::<MinPk, _>(&mut rng, &public::<MinPk>(&commitment), b"bid");
+        let bytes = ciphertext.serialize();
+        let decoded = Ciphertext::<MinPk>::deserialize(&bytes).unwrap();
+        assert!(decoded.verify());
+        assert_eq!(decoded.serialize(), bytes);
+        assert!(Ciphertext::<MinPk>::deserialize(&bytes[..bytes.len() - 1]).is_none());
+
+        let partial = partial_decrypt::<MinPk>(&shares[0], &ciphertext).unwrap();
+        let bytes = partial.serialize();
+        let decoded = PartialDecryption::<MinPk>::deserialize(&bytes).unwrap();
+        partial_verify::<MinPk>(&commitment, &ciphertext, &decoded).unwrap();
+        assert_eq!(decoded.serialize(), bytes);
+        assert!(PartialDecryption::<MinPk>::deserialize(&bytes[1..]).is_none());
+    }
+}
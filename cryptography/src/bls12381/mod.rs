@@ -84,14 +84,16 @@
 //! ```
 
 pub mod dkg;
+pub mod elgamal;
 pub mod primitives;
 pub mod scheme;
+pub mod tle;
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use dkg::ops::generate_shares;
-    use primitives::group::Private;
+    use primitives::group::{MinPk, Private};
     use primitives::ops::{aggregate, partial_sign, partial_verify, verify};
     use primitives::poly::public;
 
@@ -104,24 +106,24 @@ mod tests {
         //
         // If receiving a share from an untrusted party, the recipient
         // should verify the share is on the public polynomial.
-        let (group, shares) = generate_shares(None, n, t);
+        let (group, shares) = generate_shares::<MinPk>(None, n, t);
 
         // Generate the partial signatures
         let msg = b"hello";
         let partials = shares
             .iter()
-            .map(|s| partial_sign(s, &msg[..]))
+            .map(|s| partial_sign::<MinPk>(s, &msg[..]))
             .collect::<Vec<_>>();
 
         // Each partial sig can be partially verified against the public polynomial
         partials.iter().for_each(|partial| {
-            partial_verify(&group, &msg[..], partial).unwrap();
+            partial_verify::<MinPk>(&group, &msg[..], partial).unwrap();
         });
 
         // Generate and verify the threshold sig
-        let threshold_sig = aggregate(t, partials).unwrap();
-        let threshold_pub = public(&group);
-        verify(&threshold_pub, &msg[..], &threshold_sig).unwrap();
+        let threshold_sig = aggregate::<MinPk>(t, partials).unwrap();
+        let threshold_pub = public::<MinPk>(&group);
+        verify::<MinPk>(&threshold_pub, &msg[..], &threshold_sig).unwrap();
     }
 
     #[test]
@@ -131,7 +133,7 @@ mod tests {
 
         // Create the private key polynomial and evaluate it at `n`
         // points to generate the shares
-        let (group, shares) = generate_shares(None, n, t);
+        let (group, shares) = generate_shares::<MinPk>(None, n, t);
 
         // Only take t-1 shares
         let shares = shares.into_iter().take(t as usize - 1).collect::<Vec<_>>();
@@ -140,18 +142,18 @@ mod tests {
         let msg = b"hello";
         let partials = shares
             .iter()
-            .map(|s| partial_sign(s, &msg[..]))
+            .map(|s| partial_sign::<MinPk>(s, &msg[..]))
             .collect::<Vec<_>>();
 
         // Each partial sig can be partially verified against the public polynomial
         partials.iter().for_each(|partial| {
-            partial_verify(&group, &msg[..], partial).unwrap();
+            partial_verify::<MinPk>(&group, &msg[..], partial).unwrap();
         });
 
         // Generate and verify the threshold sig
-        let threshold_sig = aggregate(t, partials).unwrap();
-        let threshold_pub = public(&group);
-        verify(&threshold_pub, &msg[..], &threshold_sig).unwrap();
+        let threshold_sig = aggregate::<MinPk>(t, partials).unwrap();
+        let threshold_pub = public::<MinPk>(&group);
+        verify::<MinPk>(&threshold_pub, &msg[..], &threshold_sig).unwrap();
     }
 
     #[test]
@@ -161,28 +163,28 @@ mod tests {
 
         // Create the private key polynomial and evaluate it at `n`
         // points to generate the shares
-        let (group, shares) = generate_shares(None, n, t);
+        let (group, shares) = generate_shares::<MinPk>(None, n, t);
 
         // Only take t-1 shares
         let mut shares = shares.into_iter().take(t as usize - 1).collect::<Vec<_>>();
-        shares.push(shares[0]);
+        shares.push(shares[0].clone());
 
         // Generate the partial signatures
         let msg = b"hello";
         let partials = shares
             .iter()
-            .map(|s| partial_sign(s, &msg[..]))
+            .map(|s| partial_sign::<MinPk>(s, &msg[..]))
             .collect::<Vec<_>>();
 
         // Each partial sig can be partially verified against the public polynomial
         partials.iter().for_each(|partial| {
-            partial_verify(&group, &msg[..], partial).unwrap();
+            partial_verify::<MinPk>(&group, &msg[..], partial).unwrap();
         });
 
         // Generate and verify the threshold sig
-        let threshold_sig = aggregate(t, partials).unwrap();
-        let threshold_pub = public(&group);
-        verify(&threshold_pub, &msg[..], &threshold_sig).unwrap();
+        let threshold_sig = aggregate::<MinPk>(t, partials).unwrap();
+        let threshold_pub = public::<MinPk>(&group);
+        verify::<MinPk>(&threshold_pub, &msg[..], &threshold_sig).unwrap();
     }
 
     #[test]
@@ -192,7 +194,7 @@ mod tests {
 
         // Create the private key polynomial and evaluate it at `n`
         // points to generate the shares
-        let (group, mut shares) = generate_shares(None, n, t);
+        let (group, mut shares) = generate_shares::<MinPk>(None, n, t);
 
         // Corrupt a share
         let share = shares.get_mut(3).unwrap();
@@ -202,17 +204,17 @@ mod tests {
         let msg = b"hello";
         let partials = shares
             .iter()
-            .map(|s| partial_sign(s, &msg[..]))
+            .map(|s| partial_sign::<MinPk>(s, &msg[..]))
             .collect::<Vec<_>>();
 
         // Each partial sig can be partially verified against the public polynomial
         partials.iter().for_each(|partial| {
-            partial_verify(&group, &msg[..], partial).unwrap();
+            partial_verify::<MinPk>(&group, &msg[..], partial).unwrap();
         });
 
         // Generate and verify the threshold sig
-        let threshold_sig = aggregate(t, partials).unwrap();
-        let threshold_pub = public(&group);
-        verify(&threshold_pub, &msg[..], &threshold_sig).unwrap();
+        let threshold_sig = aggregate::<MinPk>(t, partials).unwrap();
+        let threshold_pub = public::<MinPk>(&group);
+        verify::<MinPk>(&threshold_pub, &msg[..], &threshold_sig).unwrap();
     }
 }
@@ -12,17 +12,21 @@
 //! functions.
 
 use blst::{
-    blst_bendian_from_scalar, blst_final_exp, blst_fp12, blst_fr, blst_fr_add, blst_fr_from_scalar,
-    blst_fr_from_uint64, blst_fr_inverse, blst_fr_mul, blst_fr_sub, blst_hash_to_g1,
-    blst_hash_to_g2, blst_keygen_v3, blst_miller_loop, blst_p1, blst_p1_add_or_double,
-    blst_p1_affine, blst_p1_compress, blst_p1_from_affine, blst_p1_in_g1, blst_p1_is_inf,
-    blst_p1_mult, blst_p1_to_affine, blst_p1_uncompress, blst_p2, blst_p2_add_or_double,
-    blst_p2_affine, blst_p2_compress, blst_p2_from_affine, blst_p2_in_g2, blst_p2_is_inf,
-    blst_p2_mult, blst_p2_to_affine, blst_p2_uncompress, blst_scalar, blst_scalar_fr_check,
-    blst_scalar_from_bendian, blst_scalar_from_fr, BLS12_381_G1, BLS12_381_G2, BLST_ERROR,
+    blst_bendian_from_fp, blst_bendian_from_scalar, blst_expand_message_xmd, blst_final_exp,
+    blst_fp12, blst_fp12_is_one, blst_fr, blst_fr_add, blst_fr_from_scalar, blst_fr_from_uint64,
+    blst_fr_inverse, blst_fr_mul, blst_fr_sub, blst_hash_to_g1, blst_hash_to_g2, blst_keygen_v3,
+    blst_miller_loop, blst_miller_loop_n, blst_p1, blst_p1_add_or_double, blst_p1_affine,
+    blst_p1_cneg, blst_p1_compress, blst_p1_from_affine, blst_p1_in_g1, blst_p1_is_inf,
+    blst_p1_mult, blst_p1_to_affine, blst_p1_uncompress, blst_p1s_mult_pippenger,
+    blst_p1s_mult_pippenger_scratch_sizeof, blst_p1s_to_affine, blst_p2, blst_p2_add_or_double,
+    blst_p2_affine, blst_p2_cneg, blst_p2_compress, blst_p2_from_affine, blst_p2_in_g2,
+    blst_p2_is_inf, blst_p2_mult, blst_p2_to_affine, blst_p2_uncompress, blst_p2s_mult_pippenger,
+    blst_p2s_mult_pippenger_scratch_sizeof, blst_p2s_to_affine, blst_scalar, blst_scalar_fr_check,
+    blst_scalar_from_be_bytes, blst_scalar_from_bendian, blst_scalar_from_fr, BLS12_381_G1,
+    BLS12_381_G2, BLST_ERROR,
 };
 use rand::RngCore;
-use std::ptr;
+use std::{fmt::Debug, ptr};
 use zeroize::Zeroize;
 
 /// An element of a group.
@@ -47,12 +51,32 @@ pub trait Element: Clone + Eq + PartialEq + Send + Sync {
 
     /// Deserializes a canonically encoded element.
     fn deserialize(bytes: &[u8]) -> Option<Self>;
+
+    /// Computes `points[0] * scalars[0] + ... + points[n-1] * scalars[n-1]`.
+    ///
+    /// This panics if `points` and `scalars` are not the same length.
+    fn msm(points: &[Self], scalars: &[Scalar]) -> Self {
+        assert_eq!(points.len(), scalars.len(), "mismatched lengths");
+        let mut acc = Self::zero();
+        for (point, scalar) in points.iter().zip(scalars.iter()) {
+            let mut point = point.clone();
+            point.mul(scalar);
+            acc.add(&point);
+        }
+        acc
+    }
+
+    /// Converts all elements into a canonical (normalized) representation at once.
+    ///
+    /// This does not change the value of any element but makes subsequent operations
+    /// that require a normalized representation (like `serialize`) cheaper.
+    fn batch_normalize(_elements: &mut [Self]) {}
 }
 
 /// An element of a group that supports message hashing.
 pub trait Point: Element {
-    /// Maps the provided data to a group element.
-    fn map(&mut self, message: &[u8]);
+    /// Maps the provided data to a group element using the given domain separation tag.
+    fn map(&mut self, dst: &[u8], message: &[u8]);
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -60,6 +84,7 @@ pub trait Point: Element {
 pub struct Scalar(blst_fr);
 
 const SCALAR_LENGTH: usize = 32;
+const SCALAR_BITS: usize = 255;
 
 /// `R = 2^256 mod q` in little-endian Montgomery form which is equivalent to 1 in little-endian
 /// non-Montgomery form.
@@ -84,6 +109,9 @@ pub const G1_ELEMENT_BYTE_LENGTH: usize = 48;
 /// Domain separation tag for hashing a message to G1.
 pub const DST_G1: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
 
+/// Domain separation tag for hashing a public key to G1 when generating a proof of possession.
+pub const DST_POP_G1: &[u8] = b"BLS_POP_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_";
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 #[repr(transparent)]
 pub struct G2(blst_p2);
@@ -93,13 +121,170 @@ pub const G2_ELEMENT_BYTE_LENGTH: usize = 96;
 /// Domain separation tag for hashing a message to G2.
 pub const DST_G2: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
 
+/// Domain separation tag for hashing a public key to G2 when generating a proof of possession.
+pub const DST_POP_G2: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct GT(blst_fp12);
 
+/// Length of a serialized GT element.
+pub const GT_ELEMENT_BYTE_LENGTH: usize = 576;
+
 pub type Private = Scalar;
 pub const PRIVATE_KEY_LENGTH: usize = SCALAR_LENGTH;
-pub type Public = G1;
-pub type Signature = G2;
+
+/// Public key of the `MinPk` variant.
+pub type Public = <MinPk as Variant>::Public;
+
+/// Signature of the `MinPk` variant.
+pub type Signature = <MinPk as Variant>::Signature;
+
+/// A BLS12-381 signature variant (determines which group public keys and
+/// signatures belong to).
+pub trait Variant: Clone + Copy + Debug + Send + Sync + 'static {
+    /// The group public keys belong to.
+    type Public: Element + Copy + Debug;
+
+    /// The group signatures (and hashed messages) belong to.
+    type Signature: Point + Copy + Debug;
+
+    /// Domain separation tag for hashing a message to the signature group.
+    const MESSAGE: &'static [u8];
+
+    /// Domain separation tag for hashing a public key to the signature group
+    /// when generating a proof of possession.
+    const PROOF_OF_POSSESSION: &'static [u8];
+
+    /// Checks that `signature` is a valid signature over the hashed message `hm`.
+    fn verify(public: &Self::Public, hm: &Self::Signature, signature: &Self::Signature) -> bool;
+
+    /// Checks that `signature` is a valid aggregate signature where each hashed message
+    /// `hms[i]` was signed by `publics[i]`.
+    ///
+    /// This is computed with a single multi-Miller loop (and final exponentiation).
+    fn verify_multi(
+        publics: &[Self::Public],
+        hms: &[Self::Signature],
+        signature: &Self::Signature,
+    ) -> bool;
+
+    /// Computes the pairing of an element of the public key group and an element of
+    /// the signature group.
+    fn pairing(public: &Self::Public, signature: &Self::Signature) -> GT;
+}
+
+/// Public keys in G1 (48 bytes) and signatures in G2 (96 bytes).
+///
+/// This variant minimizes the size of public keys.
+#[derive(Clone, Copy, Debug)]
+pub struct MinPk;
+
+impl Variant for MinPk {
+    type Public = G1;
+    type Signature = G2;
+
+    const MESSAGE: &'static [u8] = DST_G2;
+    const PROOF_OF_POSSESSION: &'static [u8] = DST_POP_G2;
+
+    fn verify(public: &G1, hm: &G2, signature: &G2) -> bool {
+        // Reference: https://github.com/celo-org/celo-threshold-bls-rs/blob/b0ef82ff79769d085a5a7d3f4fe690b1c8fe6dc9/crates/threshold-bls/src/sig/bls.rs#L120-L127
+        let left = pairing(&<G1 as Element>::one(), signature);
+        let right = pairing(public, hm);
+        left == right
+    }
+
+    fn verify_multi(publics: &[G1], hms: &[G2], signature: &G2) -> bool {
+        if publics.is_empty() || publics.len() != hms.len() {
+            return false;
+        }
+
+        // Check that e(-g, signature) * e(publics[0], hms[0]) * ... * e(publics[n-1], hms[n-1]) == 1
+        let mut generator = <G1 as Element>::one();
+        unsafe { blst_p1_cneg(&mut generator.0, true) };
+        let mut ps = Vec::with_capacity(publics.len() + 1);
+        ps.push(generator);
+        ps.extend_from_slice(publics);
+        let mut qs = Vec::with_capacity(hms.len() + 1);
+        qs.push(*signature);
+        qs.extend_from_slice(hms);
+        pairing_product(&ps, &qs).is_one()
+    }
+
+    fn pairing(public: &G1, signature: &G2) -> GT {
+        pairing(public, signature)
+    }
+}
+
+/// Public keys in G2 (96 bytes) and signatures in G1 (48 bytes).
+///
+/// This variant minimizes the size of signatures (useful when signatures must be
+/// stored or relayed, like in a light client proof).
+#[derive(Clone, Copy, Debug)]
+pub struct MinSig;
+
+impl Variant for MinSig {
+    type Public = G2;
+    type Signature = G1;
+
+    const MESSAGE: &'static [u8] = DST_G1;
+    const PROOF_OF_POSSESSION: &'static [u8] = DST_POP_G1;
+
+    fn verify(public: &G2, hm: &G1, signature: &G1) -> bool {
+        let left = pairing(signature, &<G2 as Element>::one());
+        let right = pairing(hm, public);
+        left == right
+    }
+
+    fn verify_multi(publics: &[G2], hms: &[G1], signature: &G1) -> bool {
+        if publics.is_empty() || publics.len() != hms.len() {
+            return false;
+        }
+
+        // Check that e(signature, -g) * e(hms[0], publics[0]) * ... * e(hms[n-1], publics[n-1]) == 1
+        let mut generator = <G2 as Element>::one();
+        unsafe { blst_p2_cneg(&mut generator.0, true) };
+        let mut ps = Vec::with_capacity(hms.len() + 1);
+        ps.push(*signature);
+        ps.extend_from_slice(hms);
+        let mut qs = Vec::with_capacity(publics.len() + 1);
+        qs.push(generator);
+        qs.extend_from_slice(publics);
+        pairing_product(&ps, &qs).is_one()
+    }
+
+    fn pairing(public: &G2, signature: &G1) -> GT {
+        pairing(signature, public)
+    }
+}
+
+/// Converts the provided points (none of which may be at infinity) into affine form
+/// using a single inversion.
+fn p1s_to_affine(points: &[blst_p1]) -> Vec<blst_p1_affine> {
+    let mut affine = vec![blst_p1_affine::default(); points.len()];
+    let ptrs: [*const blst_p1; 2] = [&points[0], ptr::null()];
+    unsafe { blst_p1s_to_affine(affine.as_mut_ptr(), ptrs.as_ptr(), points.len()) };
+    affine
+}
+
+/// Converts the provided points (none of which may be at infinity) into affine form
+/// using a single inversion.
+fn p2s_to_affine(points: &[blst_p2]) -> Vec<blst_p2_affine> {
+    let mut affine = vec![blst_p2_affine::default(); points.len()];
+    let ptrs: [*const blst_p2; 2] = [&points[0], ptr::null()];
+    unsafe { blst_p2s_to_affine(affine.as_mut_ptr(), ptrs.as_ptr(), points.len()) };
+    affine
+}
+
+/// Returns the concatenated little-endian encodings of the provided scalars.
+fn scalar_bytes(scalars: &[Scalar]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(scalars.len() * SCALAR_LENGTH);
+    for s in scalars {
+        let mut scalar = blst_scalar::default();
+        unsafe { blst_scalar_from_fr(&mut scalar, &s.0) };
+        bytes.extend_from_slice(&scalar.b);
+    }
+    bytes
+}
 
 /// Returns the size in bits of a given blst_scalar (represented in little-endian).
 fn bits(scalar: &blst_scalar) -> usize {
@@ -115,7 +300,7 @@ fn bits(scalar: &blst_scalar) -> usize {
 }
 
 /// A share of a threshold signing key.
-#[derive(Clone, PartialEq, Copy)]
+#[derive(Clone, PartialEq)]
 pub struct Share {
     /// The share's index in the polynomial.
     pub index: u32,
@@ -127,8 +312,8 @@ impl Share {
     /// Returns the public key corresponding to the share.
     ///
     /// This can be verified against the public polynomial.
-    pub fn public(&self) -> Public {
-        let mut public = <Public as Element>::one();
+    pub fn public<V: Variant>(&self) -> V::Public {
+        let mut public = <V::Public as Element>::one();
         public.mul(&self.private);
         public
     }
@@ -169,6 +354,29 @@ impl Scalar {
         Self(ret)
     }
 
+    /// Maps the provided data to a scalar using the given domain separation tag.
+    ///
+    /// This is `hash_to_field` from RFC 9380 (with `L = 48`), so the output is
+    /// (statistically close to) uniform over the scalar field.
+    pub fn map(dst: &[u8], message: &[u8]) -> Self {
+        let mut uniform = [0u8; 48];
+        let mut ret = blst_fr::default();
+        unsafe {
+            blst_expand_message_xmd(
+                uniform.as_mut_ptr(),
+                uniform.len(),
+                message.as_ptr(),
+                message.len(),
+                dst.as_ptr(),
+                dst.len(),
+            );
+            let mut sc = blst_scalar::default();
+            blst_scalar_from_be_bytes(&mut sc, uniform.as_ptr(), uniform.len());
+            blst_fr_from_scalar(&mut ret, &sc);
+        }
+        Self(ret)
+    }
+
     /// Sets the scalar to be the provided integer.
     pub fn set_int(&mut self, i: u32) {
         // blst requires a buffer of 4 uint64 values. Failure to provide one will
@@ -195,6 +403,13 @@ impl Scalar {
     }
 }
 
+impl Zeroize for Share {
+    fn zeroize(&mut self) {
+        self.index.zeroize();
+        self.private.zeroize();
+    }
+}
+
 impl Zeroize for Scalar {
     fn zeroize(&mut self) {
         self.0.l.zeroize();
@@ -316,17 +531,70 @@ impl Element for G1 {
     fn size() -> usize {
         G1_ELEMENT_BYTE_LENGTH
     }
+
+    fn msm(points: &[Self], scalars: &[Scalar]) -> Self {
+        assert_eq!(points.len(), scalars.len(), "mismatched lengths");
+
+        // Skip points at infinity (which contribute nothing to the sum and can't be
+        // converted to affine form with the other points)
+        let (points, scalars): (Vec<_>, Vec<_>) = points
+            .iter()
+            .zip(scalars.iter())
+            .filter(|(p, _)| unsafe { !blst_p1_is_inf(&p.0) })
+            .map(|(p, s)| (p.0, *s))
+            .unzip();
+        let npoints = points.len();
+        if npoints == 0 {
+            return Self::zero();
+        }
+
+        // Convert points to affine form (with a single inversion)
+        let affine = p1s_to_affine(&points);
+
+        // Perform multi-scalar multiplication using Pippenger's algorithm
+        let scalars = scalar_bytes(&scalars);
+        let aptrs: [*const blst_p1_affine; 2] = [affine.as_ptr(), ptr::null()];
+        let sptrs: [*const u8; 2] = [scalars.as_ptr(), ptr::null()];
+        let mut ret = blst_p1::default();
+        unsafe {
+            let mut scratch = vec![0u64; blst_p1s_mult_pippenger_scratch_sizeof(npoints) / 8];
+            blst_p1s_mult_pippenger(
+                &mut ret,
+                aptrs.as_ptr(),
+                npoints,
+                sptrs.as_ptr(),
+                SCALAR_BITS,
+                scratch.as_mut_ptr(),
+            );
+        }
+        Self(ret)
+    }
+
+    fn batch_normalize(elements: &mut [Self]) {
+        // Skip points at infinity (which are already normalized)
+        let indices = (0..elements.len())
+            .filter(|i| unsafe { !blst_p1_is_inf(&elements[*i].0) })
+            .collect::<Vec<_>>();
+        if indices.is_empty() {
+            return;
+        }
+        let points = indices.iter().map(|i| elements[*i].0).collect::<Vec<_>>();
+        let affine = p1s_to_affine(&points);
+        for (i, a) in indices.into_iter().zip(affine.iter()) {
+            unsafe { blst_p1_from_affine(&mut elements[i].0, a) };
+        }
+    }
 }
 
 impl Point for G1 {
-    fn map(&mut self, data: &[u8]) {
+    fn map(&mut self, dst: &[u8], data: &[u8]) {
         unsafe {
             blst_hash_to_g1(
                 &mut self.0,
                 data.as_ptr(),
                 data.len(),
-                DST_G1.as_ptr(),
-                DST_G1.len(),
+                dst.as_ptr(),
+                dst.len(),
                 ptr::null(),
                 0,
             );
@@ -397,17 +665,70 @@ impl Element for G2 {
     fn size() -> usize {
         G2_ELEMENT_BYTE_LENGTH
     }
+
+    fn msm(points: &[Self], scalars: &[Scalar]) -> Self {
+        assert_eq!(points.len(), scalars.len(), "mismatched lengths");
+
+        // Skip points at infinity (which contribute nothing to the sum and can't be
+        // converted to affine form with the other points)
+        let (points, scalars): (Vec<_>, Vec<_>) = points
+            .iter()
+            .zip(scalars.iter())
+            .filter(|(p, _)| unsafe { !blst_p2_is_inf(&p.0) })
+            .map(|(p, s)| (p.0, *s))
+            .unzip();
+        let npoints = points.len();
+        if npoints == 0 {
+            return Self::zero();
+        }
+
+        // Convert points to affine form (with a single inversion)
+        let affine = p2s_to_affine(&points);
+
+        // Perform multi-scalar multiplication using Pippenger's algorithm
+        let scalars = scalar_bytes(&scalars);
+        let aptrs: [*const blst_p2_affine; 2] = [affine.as_ptr(), ptr::null()];
+        let sptrs: [*const u8; 2] = [scalars.as_ptr(), ptr::null()];
+        let mut ret = blst_p2::default();
+        unsafe {
+            let mut scratch = vec![0u64; blst_p2s_mult_pippenger_scratch_sizeof(npoints) / 8];
+            blst_p2s_mult_pippenger(
+                &mut ret,
+                aptrs.as_ptr(),
+                npoints,
+                sptrs.as_ptr(),
+                SCALAR_BITS,
+                scratch.as_mut_ptr(),
+            );
+        }
+        Self(ret)
+    }
+
+    fn batch_normalize(elements: &mut [Self]) {
+        // Skip points at infinity (which are already normalized)
+        let indices = (0..elements.len())
+            .filter(|i| unsafe { !blst_p2_is_inf(&elements[*i].0) })
+            .collect::<Vec<_>>();
+        if indices.is_empty() {
+            return;
+        }
+        let points = indices.iter().map(|i| elements[*i].0).collect::<Vec<_>>();
+        let affine = p2s_to_affine(&points);
+        for (i, a) in indices.into_iter().zip(affine.iter()) {
+            unsafe { blst_p2_from_affine(&mut elements[i].0, a) };
+        }
+    }
 }
 
 impl Point for G2 {
-    fn map(&mut self, data: &[u8]) {
+    fn map(&mut self, dst: &[u8], data: &[u8]) {
         unsafe {
             blst_hash_to_g2(
                 &mut self.0,
                 data.as_ptr(),
                 data.len(),
-                DST_G2.as_ptr(),
-                DST_G2.len(),
+                dst.as_ptr(),
+                dst.len(),
                 ptr::null(),
                 0,
             );
@@ -415,6 +736,29 @@ impl Point for G2 {
     }
 }
 
+impl GT {
+    /// Returns whether the element is the identity.
+    fn is_one(&self) -> bool {
+        unsafe { blst_fp12_is_one(&self.0) }
+    }
+
+    /// Canonically serializes the element (each of the 12 base field coefficients
+    /// in big-endian order).
+    pub fn serialize(&self) -> [u8; GT_ELEMENT_BYTE_LENGTH] {
+        let mut bytes = [0u8; GT_ELEMENT_BYTE_LENGTH];
+        let coefficients = self
+            .0
+            .fp6
+            .iter()
+            .flat_map(|fp6| fp6.fp2.iter())
+            .flat_map(|fp2| fp2.fp.iter());
+        for (chunk, fp) in bytes.chunks_exact_mut(48).zip(coefficients) {
+            unsafe { blst_bendian_from_fp(chunk.as_mut_ptr(), fp) };
+        }
+        bytes
+    }
+}
+
 fn pairing(p: &G1, q: &G2) -> GT {
     // Reference: https://github.com/MystenLabs/fastcrypto/blob/bd4999bd3e901eab34ae3dd96dbe38b86ac646a7/fastcrypto/src/groups/bls12381.rs#L223-L234
     let mut pa = blst_p1_affine::default();
@@ -429,11 +773,34 @@ fn pairing(p: &G1, q: &G2) -> GT {
     GT(res)
 }
 
-pub(super) fn equal(p: &G1, sig: &G2, hm: &G2) -> bool {
-    // Reference: https://github.com/celo-org/celo-threshold-bls-rs/blob/b0ef82ff79769d085a5a7d3f4fe690b1c8fe6dc9/crates/threshold-bls/src/sig/bls.rs#L120-L127
-    let left = pairing(&<G1 as Element>::one(), sig);
-    let right = pairing(p, hm);
-    left == right
+/// Computes the product of pairings `e(p_1, q_1) * ... * e(p_n, q_n)` using a single
+/// multi-Miller loop (and a single final exponentiation).
+fn pairing_product(ps: &[G1], qs: &[G2]) -> GT {
+    assert_eq!(ps.len(), qs.len());
+    let pas = ps
+        .iter()
+        .map(|p| {
+            let mut pa = blst_p1_affine::default();
+            unsafe { blst_p1_to_affine(&mut pa, &p.0) };
+            pa
+        })
+        .collect::<Vec<_>>();
+    let qas = qs
+        .iter()
+        .map(|q| {
+            let mut qa = blst_p2_affine::default();
+            unsafe { blst_p2_to_affine(&mut qa, &q.0) };
+            qa
+        })
+        .collect::<Vec<_>>();
+    let pptrs = pas.iter().map(|p| p as *const _).collect::<Vec<_>>();
+    let qptrs = qas.iter().map(|q| q as *const _).collect::<Vec<_>>();
+    let mut res = blst_fp12::default();
+    unsafe {
+        blst_miller_loop_n(&mut res, qptrs.as_ptr(), pptrs.as_ptr(), ps.len());
+        blst_final_exp(&mut res, &res);
+    }
+    GT(res)
 }
 
 #[cfg(test)]
@@ -464,4 +831,54 @@ mod tests {
         p2.add(&p2.clone());
         assert_eq!(p1, p2);
     }
+
+    fn naive_msm<E: Element>(points: &[E], scalars: &[Scalar]) -> E {
+        let mut acc = E::zero();
+        for (point, scalar) in points.iter().zip(scalars.iter()) {
+            let mut point = point.clone();
+            point.mul(scalar);
+            acc.add(&point);
+        }
+        acc
+    }
+
+    fn msm<E: Element>() {
+        let mut rng = thread_rng();
+        for n in [0, 1, 2, 10, 64] {
+            let mut points = (0..n)
+                .map(|_| {
+                    let mut p = E::one();
+                    p.mul(&Scalar::rand(&mut rng));
+                    p
+                })
+                .collect::<Vec<_>>();
+            let scalars = (0..n).map(|_| Scalar::rand(&mut rng)).collect::<Vec<_>>();
+            assert!(E::msm(&points, &scalars) == naive_msm(&points, &scalars));
+
+            // Include the identity
+            if n > 1 {
+                points[1] = E::zero();
+                assert!(E::msm(&points, &scalars) == naive_msm(&points, &scalars));
+            }
+        }
+    }
+
+    #[test]
+    fn test_msm_g1() {
+        msm::<G1>();
+    }
+
+    #[test]
+    fn test_msm_g2() {
+        msm::<G2>();
+    }
+
+    #[test]
+    fn test_scalar_map() {
+        let a = Scalar::map(b"DST_A", b"message");
+        assert_eq!(a, Scalar::map(b"DST_A", b"message"));
+        assert_ne!(a, Scalar::map(b"DST_B", b"message"));
+        assert_ne!(a, Scalar::map(b"DST_A", b"other message"));
+        assert_ne!(a, Scalar::zero());
+    }
 }
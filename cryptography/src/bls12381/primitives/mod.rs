@@ -9,11 +9,18 @@
 //! * <https://github.com/filecoin-project/blstrs> + <https://github.com/MystenLabs/fastcrypto>: Implenting operations over
 //!   the BLS12-381 scalar field with <https://github.com/supranational/blst>.
 //!
+//! # Variants
+//!
+//! All signature operations (and the DKG) are generic over a `Variant`, which determines
+//! which group public keys and signatures belong to. `MinPk` (public keys in G1, signatures
+//! in G2) minimizes the size of public keys and `MinSig` (public keys in G2, signatures
+//! in G1) minimizes the size of signatures.
+//!
 //! # Example
 //!
 //! ```rust
 //! use commonware_cryptography::bls12381::{
-//!     primitives::{ops::{partial_sign, partial_verify, aggregate, verify}, poly::public},
+//!     primitives::{group::MinSig, ops::{partial_sign, partial_verify, aggregate, verify}, poly::public},
 //!     dkg::ops::{generate_shares},
 //! };
 //!
@@ -21,23 +28,23 @@
 //! let (n, t) = (5, 4);
 //!
 //! // Generate commitment and shares
-//! let (commitment, shares) = generate_shares(None, n, t);
+//! let (commitment, shares) = generate_shares::<MinSig>(None, n, t);
 //!
 //! // Generate partial signatures from shares
 //! let msg = b"hello world";
-//! let partials: Vec<_> = shares.iter().map(|s| partial_sign(s, msg)).collect();
+//! let partials: Vec<_> = shares.iter().map(|s| partial_sign::<MinSig>(s, msg)).collect();
 //!
 //! // Verify partial signatures
 //! for p in &partials {
-//!     partial_verify(&commitment, msg, p).expect("signature should be valid");
+//!     partial_verify::<MinSig>(&commitment, msg, p).expect("signature should be valid");
 //! }
 //!
 //! // Aggregate partial signatures
-//! let threshold_sig = aggregate(t, partials).unwrap();
+//! let threshold_sig = aggregate::<MinSig>(t, partials).unwrap();
 //!
 //! // Verify threshold signature
-//! let threshold_pub = public(&commitment);
-//! verify(&threshold_pub, msg, &threshold_sig).expect("signature should be valid");
+//! let threshold_pub = public::<MinSig>(&commitment);
+//! verify::<MinSig>(&threshold_pub, msg, &threshold_sig).expect("signature should be valid");
 //! ```
 
 pub mod group;
@@ -51,6 +58,11 @@ pub enum Error {
     InvalidRecovery,
     NoInverse,
     DuplicateEval,
+    DuplicateMessage,
+    NotEnoughPartialDecryptions,
+    InvalidCiphertext,
+    InvalidPartialDecryption,
+    DecryptionFailed,
 }
 
 impl std::fmt::Display for Error {
@@ -61,6 +73,11 @@ impl std::fmt::Display for Error {
             Error::InvalidRecovery => write!(f, "invalid recovery"),
             Error::NoInverse => write!(f, "no inverse"),
             Error::DuplicateEval => write!(f, "duplicate eval"),
+            Error::DuplicateMessage => write!(f, "duplicate message"),
+            Error::NotEnoughPartialDecryptions => write!(f, "not enough partial decryptions"),
+            Error::InvalidCiphertext => write!(f, "invalid ciphertext"),
+            Error::InvalidPartialDecryption => write!(f, "invalid partial decryption"),
+            Error::DecryptionFailed => write!(f, "decryption failed"),
         }
     }
 }
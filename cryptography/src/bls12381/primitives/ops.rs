@@ -1,20 +1,31 @@
 //! Digital signatures over the BLS12-381 curve.
+//!
+//! All operations are generic over the signature `Variant` (`MinPk` places public keys
+//! in G1 and signatures in G2, `MinSig` places public keys in G2 and signatures in G1).
 
 use super::{
-    group::{self, equal, Element, Point, Share},
+    group::{self, Element, Point, Share, Variant},
     poly::{self, Eval},
     Error,
 };
-use rand::RngCore;
+use rand::{CryptoRng, RngCore};
+use std::collections::HashSet;
 
 /// Returns a new keypair derived from the provided randomness.
-pub fn keypair<R: RngCore>(rng: &mut R) -> (group::Private, group::Public) {
+pub fn keypair<V: Variant, R: RngCore>(rng: &mut R) -> (group::Private, V::Public) {
     let private = group::Private::rand(rng);
-    let mut public = group::Public::one();
+    let mut public = V::Public::one();
     public.mul(&private);
     (private, public)
 }
 
+/// Hashes the provided message to the signature group.
+fn hash_message<V: Variant>(msg: &[u8]) -> V::Signature {
+    let mut hm = V::Signature::zero();
+    hm.map(V::MESSAGE, msg);
+    hm
+}
+
 /// Signs the provided message with the private key.
 ///
 /// The message is hashed according to RFC 9380.
@@ -23,30 +34,169 @@ pub fn keypair<R: RngCore>(rng: &mut R) -> (group::Private, group::Public) {
 ///
 /// Signatures produced by this function are deterministic and are safe
 /// to use in a consensus-critical context.
-pub fn sign(private: &group::Private, msg: &[u8]) -> group::Signature {
-    let mut s = group::Signature::zero();
-    s.map(msg);
+pub fn sign<V: Variant>(private: &group::Private, msg: &[u8]) -> V::Signature {
+    let mut s = hash_message::<V>(msg);
     s.mul(private);
     s
 }
 
 /// Verifies the signature with the provided public key.
-pub fn verify(
-    public: &group::Public,
+pub fn verify<V: Variant>(
+    public: &V::Public,
     msg: &[u8],
-    signature: &group::Signature,
+    signature: &V::Signature,
+) -> Result<(), Error> {
+    let hm = hash_message::<V>(msg);
+    if !V::verify(public, &hm, signature) {
+        return Err(Error::InvalidSignature);
+    }
+    Ok(())
+}
+
+/// Generates a proof of possession for the private key.
+///
+/// The proof of possession is a signature over the serialized public key (hashed with
+/// a distinct domain separation tag to prevent it from being confused with a signature
+/// over some message).
+pub fn sign_proof_of_possession<V: Variant>(private: &group::Private) -> V::Signature {
+    let mut public = V::Public::one();
+    public.mul(private);
+    let mut s = V::Signature::zero();
+    s.map(V::PROOF_OF_POSSESSION, &public.serialize());
+    s.mul(private);
+    s
+}
+
+/// Verifies a proof of possession for the provided public key.
+pub fn verify_proof_of_possession<V: Variant>(
+    public: &V::Public,
+    signature: &V::Signature,
+) -> Result<(), Error> {
+    let mut hm = V::Signature::zero();
+    hm.map(V::PROOF_OF_POSSESSION, &public.serialize());
+    if !V::verify(public, &hm, signature) {
+        return Err(Error::InvalidSignature);
+    }
+    Ok(())
+}
+
+/// Verifies a batch of signatures (over arbitrary messages and public keys) at once.
+///
+/// Each signature (and public key) is multiplied by a random scalar before being combined and then
+/// checked with a single multi-Miller loop over `n + 1` pairs and a single final exponentiation
+/// (rather than `2n` pairings). Without this randomization, an adversary could craft invalid
+/// signatures that cancel each other out in the sum, so `rng` must be cryptographically secure.
+///
+/// If the batch is invalid, this function does not identify which signature is invalid.
+pub fn batch_verify<V: Variant, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    items: &[(V::Public, &[u8], V::Signature)],
 ) -> Result<(), Error> {
-    let mut hm = group::Signature::zero();
-    hm.map(msg);
-    if !equal(public, signature, &hm) {
+    if items.is_empty() {
+        return Ok(());
+    }
+    let mut publics = Vec::with_capacity(items.len());
+    let mut hms = Vec::with_capacity(items.len());
+    let mut signature = V::Signature::zero();
+    for (public, msg, sig) in items {
+        let r = group::Scalar::rand(rng);
+        let mut public = *public;
+        public.mul(&r);
+        publics.push(public);
+        let mut sig = *sig;
+        sig.mul(&r);
+        signature.add(&sig);
+        hms.push(hash_message::<V>(msg));
+    }
+    if !V::verify_multi(&publics, &hms, &signature) {
+        return Err(Error::InvalidSignature);
+    }
+    Ok(())
+}
+
+/// Aggregates multiple public keys into a single public key.
+///
+/// # Warning
+///
+/// An aggregate public key is only safe to use if the caller has verified that
+/// each public key was generated by someone that knows its private key (see
+/// `verify_proof_of_possession`). Otherwise, an adversary could pick a public key
+/// that cancels out all other public keys in the aggregate (a rogue-key attack) and
+/// forge an aggregate signature on their own.
+pub fn aggregate_public_keys<V: Variant>(publics: &[V::Public]) -> V::Public {
+    let mut public = V::Public::zero();
+    for p in publics {
+        public.add(p);
+    }
+    public
+}
+
+/// Aggregates multiple signatures into a single signature.
+///
+/// The signatures may be over the same message or over distinct messages.
+pub fn aggregate_signatures<V: Variant>(signatures: &[V::Signature]) -> V::Signature {
+    let mut signature = V::Signature::zero();
+    for s in signatures {
+        signature.add(s);
+    }
+    signature
+}
+
+/// Verifies an aggregate signature over a single message signed by all provided public keys.
+///
+/// Each public key must be accompanied by its proof of possession (see `sign_proof_of_possession`),
+/// which is verified before the public keys are aggregated to prevent rogue-key attacks (where an
+/// adversary picks a public key that cancels out all other public keys in the aggregate). Once
+/// verified, the aggregate check requires only 2 pairings, regardless of the number of public keys.
+pub fn verify_aggregate_same_message<V: Variant>(
+    publics: &[(V::Public, V::Signature)],
+    msg: &[u8],
+    signature: &V::Signature,
+) -> Result<(), Error> {
+    if publics.is_empty() {
+        return Err(Error::InvalidSignature);
+    }
+    let mut public = V::Public::zero();
+    for (p, proof) in publics {
+        verify_proof_of_possession::<V>(p, proof)?;
+        public.add(p);
+    }
+    verify::<V>(&public, msg, signature)
+}
+
+/// Verifies an aggregate signature over distinct messages, where `msgs[i]` was
+/// signed by `publics[i]`.
+///
+/// This is computed with a single multi-Miller loop (and final exponentiation) over
+/// all `n + 1` pairings.
+///
+/// Because each message must be unique, this function is not vulnerable to rogue-key
+/// attacks (if duplicate messages are provided, `Error::DuplicateMessage` is returned).
+pub fn verify_aggregate_distinct_messages<V: Variant>(
+    publics: &[V::Public],
+    msgs: &[&[u8]],
+    signature: &V::Signature,
+) -> Result<(), Error> {
+    if publics.is_empty() || publics.len() != msgs.len() {
+        return Err(Error::InvalidSignature);
+    }
+    let unique: HashSet<_> = msgs.iter().collect();
+    if unique.len() != msgs.len() {
+        return Err(Error::DuplicateMessage);
+    }
+    let hms = msgs
+        .iter()
+        .map(|msg| hash_message::<V>(msg))
+        .collect::<Vec<_>>();
+    if !V::verify_multi(publics, &hms, signature) {
         return Err(Error::InvalidSignature);
     }
     Ok(())
 }
 
 /// Signs the provided message with the key share.
-pub fn partial_sign(private: &Share, msg: &[u8]) -> Eval<group::Signature> {
-    let sig = sign(&private.private, msg);
+pub fn partial_sign<V: Variant>(private: &Share, msg: &[u8]) -> Eval<V::Signature> {
+    let sig = sign::<V>(&private.private, msg);
     Eval {
         value: sig,
         index: private.index,
@@ -54,13 +204,57 @@ pub fn partial_sign(private: &Share, msg: &[u8]) -> Eval<group::Signature> {
 }
 
 /// Verifies the partial signature against the public polynomial.
-pub fn partial_verify(
-    public: &poly::Public,
+pub fn partial_verify<V: Variant>(
+    public: &poly::Public<V>,
     msg: &[u8],
-    partial: &Eval<group::Signature>,
+    partial: &Eval<V::Signature>,
 ) -> Result<(), Error> {
     let public = public.evaluate(partial.index);
-    verify(&public.value, msg, &partial.value)
+    verify::<V>(&public.value, msg, &partial.value)
+}
+
+/// Verifies the partial signature against a precomputed table of public keys.
+///
+/// This is equivalent to `partial_verify` (over the polynomial the table was computed from) but
+/// does not evaluate the polynomial.
+pub fn partial_verify_with_table<V: Variant>(
+    table: &poly::PublicTable<V>,
+    msg: &[u8],
+    partial: &Eval<V::Signature>,
+) -> Result<(), Error> {
+    let public = table.get(partial.index).ok_or(Error::InvalidSignature)?;
+    verify::<V>(public, msg, &partial.value)
+}
+
+/// Verifies a batch of partial signatures over the same message against a precomputed table
+/// of public keys.
+///
+/// Each partial signature (and the public key of its index) is multiplied by a random scalar before
+/// being combined, which requires only 2 pairings (regardless of the number of partial signatures).
+///
+/// If the batch is invalid, this function does not identify which partial signature is invalid
+/// (use `partial_verify_with_table` on each to find it).
+pub fn partial_batch_verify<V: Variant, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    table: &poly::PublicTable<V>,
+    msg: &[u8],
+    partials: &[Eval<V::Signature>],
+) -> Result<(), Error> {
+    if partials.is_empty() {
+        return Ok(());
+    }
+    let mut publics = Vec::with_capacity(partials.len());
+    let mut signatures = Vec::with_capacity(partials.len());
+    let mut scalars = Vec::with_capacity(partials.len());
+    for partial in partials {
+        let public = table.get(partial.index).ok_or(Error::InvalidSignature)?;
+        publics.push(*public);
+        signatures.push(partial.value);
+        scalars.push(group::Scalar::rand(rng));
+    }
+    let public = V::Public::msm(&publics, &scalars);
+    let signature = V::Signature::msm(&signatures, &scalars);
+    verify::<V>(&public, msg, &signature)
 }
 
 /// Aggregates the partial signatures into a final signature.
@@ -69,59 +263,469 @@ pub fn partial_verify(
 ///
 /// Signatures recovered by this function are deterministic and are safe
 /// to use in a consensus-critical context.
-pub fn aggregate(
+pub fn aggregate<V: Variant>(
     threshold: u32,
-    partials: Vec<Eval<group::Signature>>,
-) -> Result<group::Signature, Error> {
+    partials: Vec<Eval<V::Signature>>,
+) -> Result<V::Signature, Error> {
     if threshold as usize > partials.len() {
         return Err(Error::NotEnoughPartialSignatures);
     }
-    poly::Signature::recover(threshold, partials)
+    poly::Signature::<V>::recover(threshold, partials)
+}
+
+/// Aggregates the partial signatures into a final signature using precomputed
+/// Lagrange `weights`.
+///
+/// When the same set of signers produces a threshold signature repeatedly, computing
+/// `weights` once avoids recomputing the Lagrange coefficients on each aggregation. A
+/// partial signature must be provided for each index in `weights`.
+pub fn aggregate_with_weights<V: Variant>(
+    weights: &poly::Weights,
+    partials: &[Eval<V::Signature>],
+) -> Result<V::Signature, Error> {
+    if weights.len() > partials.len() {
+        return Err(Error::NotEnoughPartialSignatures);
+    }
+    poly::Signature::<V>::recover_with_weights(weights, partials)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::bls12381::{dkg::ops::generate_shares, primitives::group::DST_G2};
+    use crate::bls12381::{
+        dkg::ops::generate_shares,
+        primitives::group::{MinPk, MinSig, DST_G1, DST_G2, DST_POP_G1, DST_POP_G2},
+    };
     use blst::BLST_ERROR;
     use rand::prelude::*;
 
-    /// Verify that a given signature is valid according to `blst`.
+    /// Verify that a given `MinPk` signature is valid according to `blst`.
     fn blst_verify(
-        public: &group::Public,
+        public: &group::G1,
         msg: &[u8],
-        signature: &group::Signature,
+        signature: &group::G2,
+        dst: &[u8],
     ) -> Result<(), BLST_ERROR> {
         let public = blst::min_pk::PublicKey::from_bytes(public.serialize().as_slice()).unwrap();
         let signature =
             blst::min_pk::Signature::from_bytes(signature.serialize().as_slice()).unwrap();
-        match signature.verify(true, msg, DST_G2, &[], &public, true) {
+        match signature.verify(true, msg, dst, &[], &public, true) {
             BLST_ERROR::BLST_SUCCESS => Ok(()),
             e => Err(e),
         }
     }
 
+    /// Verify that a given `MinSig` signature is valid according to `blst`.
+    fn blst_verify_min_sig(
+        public: &group::G2,
+        msg: &[u8],
+        signature: &group::G1,
+        dst: &[u8],
+    ) -> Result<(), BLST_ERROR> {
+        let public = blst::min_sig::PublicKey::from_bytes(public.serialize().as_slice()).unwrap();
+        let signature =
+            blst::min_sig::Signature::from_bytes(signature.serialize().as_slice()).unwrap();
+        match signature.verify(true, msg, dst, &[], &public, true) {
+            BLST_ERROR::BLST_SUCCESS => Ok(()),
+            e => Err(e),
+        }
+    }
+
+    fn single_compatibility<V: Variant>(
+        blst: impl Fn(&V::Public, &[u8], &V::Signature, &[u8]) -> Result<(), BLST_ERROR>,
+    ) {
+        let (private, public) = keypair::<V, _>(&mut thread_rng());
+        let msg = &[1, 9, 6, 9];
+        let sig = sign::<V>(&private, msg);
+        verify::<V>(&public, msg, &sig).expect("signature should be valid");
+        blst(&public, msg, &sig, V::MESSAGE).expect("signature should be valid");
+    }
+
     #[test]
     fn test_single_compatibility() {
-        let (private, public) = keypair(&mut thread_rng());
+        single_compatibility::<MinPk>(blst_verify);
+    }
+
+    #[test]
+    fn test_single_compatibility_min_sig() {
+        single_compatibility::<MinSig>(blst_verify_min_sig);
+    }
+
+    #[test]
+    fn test_variant_dsts() {
+        assert_eq!(MinPk::MESSAGE, DST_G2);
+        assert_eq!(MinPk::PROOF_OF_POSSESSION, DST_POP_G2);
+        assert_eq!(MinSig::MESSAGE, DST_G1);
+        assert_eq!(MinSig::PROOF_OF_POSSESSION, DST_POP_G1);
+    }
+
+    fn proof_of_possession<V: Variant>(
+        blst: impl Fn(&V::Public, &[u8], &V::Signature, &[u8]) -> Result<(), BLST_ERROR>,
+    ) {
+        let mut rng = thread_rng();
+        let (private, public) = keypair::<V, _>(&mut rng);
+        let pop = sign_proof_of_possession::<V>(&private);
+        verify_proof_of_possession::<V>(&public, &pop).expect("proof should be valid");
+
+        // Compatible with the PoP ciphersuite implemented by `blst`
+        blst(&public, &public.serialize(), &pop, V::PROOF_OF_POSSESSION)
+            .expect("proof should be valid");
+
+        // Proof for a different public key
+        let (_, other) = keypair::<V, _>(&mut rng);
+        assert!(matches!(
+            verify_proof_of_possession::<V>(&other, &pop),
+            Err(Error::InvalidSignature)
+        ));
+
+        // Signature over the serialized public key is not a proof of possession
+        let sig = sign::<V>(&private, &public.serialize());
+        assert!(matches!(
+            verify_proof_of_possession::<V>(&public, &sig),
+            Err(Error::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_proof_of_possession() {
+        proof_of_possession::<MinPk>(blst_verify);
+    }
+
+    #[test]
+    fn test_proof_of_possession_min_sig() {
+        proof_of_possession::<MinSig>(blst_verify_min_sig);
+    }
+
+    fn batch<V: Variant>() {
+        let mut rng = thread_rng();
+        let messages: Vec<Vec<u8>> = (0..10u8).map(|i| vec![i; 4]).collect();
+        let mut items: Vec<_> = messages
+            .iter()
+            .map(|msg| {
+                let (private, public) = keypair::<V, _>(&mut rng);
+                (public, &msg[..], sign::<V>(&private, msg))
+            })
+            .collect();
+        batch_verify::<V, _>(&mut rng, &items).expect("batch should be valid");
+
+        // Swapping two signatures leaves the sum unchanged but must be detected
+        let sig = items[2].2;
+        items[2].2 = items[5].2;
+        items[5].2 = sig;
+        assert!(matches!(
+            batch_verify::<V, _>(&mut rng, &items),
+            Err(Error::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        batch::<MinPk>();
+    }
+
+    #[test]
+    fn test_batch_verify_min_sig() {
+        batch::<MinSig>();
+    }
+
+    /// Verify that a given `MinPk` aggregate signature over the same message is valid according to `blst`.
+    fn blst_fast_aggregate_verify(
+        publics: &[group::G1],
+        msg: &[u8],
+        signature: &group::G2,
+    ) -> Result<(), BLST_ERROR> {
+        let publics = publics
+            .iter()
+            .map(|p| blst::min_pk::PublicKey::from_bytes(p.serialize().as_slice()).unwrap())
+            .collect::<Vec<_>>();
+        let publics = publics.iter().collect::<Vec<_>>();
+        let signature =
+            blst::min_pk::Signature::from_bytes(signature.serialize().as_slice()).unwrap();
+        match signature.fast_aggregate_verify(true, msg, DST_G2, &publics) {
+            BLST_ERROR::BLST_SUCCESS => Ok(()),
+            e => Err(e),
+        }
+    }
+
+    /// Verify that a given `MinSig` aggregate signature over the same message is valid according to `blst`.
+    fn blst_fast_aggregate_verify_min_sig(
+        publics: &[group::G2],
+        msg: &[u8],
+        signature: &group::G1,
+    ) -> Result<(), BLST_ERROR> {
+        let publics = publics
+            .iter()
+            .map(|p| blst::min_sig::PublicKey::from_bytes(p.serialize().as_slice()).unwrap())
+            .collect::<Vec<_>>();
+        let publics = publics.iter().collect::<Vec<_>>();
+        let signature =
+            blst::min_sig::Signature::from_bytes(signature.serialize().as_slice()).unwrap();
+        match signature.fast_aggregate_verify(true, msg, DST_G1, &publics) {
+            BLST_ERROR::BLST_SUCCESS => Ok(()),
+            e => Err(e),
+        }
+    }
+
+    /// Verify that a given `MinPk` aggregate signature over distinct messages is valid according to `blst`.
+    fn blst_aggregate_verify(
+        publics: &[group::G1],
+        msgs: &[&[u8]],
+        signature: &group::G2,
+    ) -> Result<(), BLST_ERROR> {
+        let publics = publics
+            .iter()
+            .map(|p| blst::min_pk::PublicKey::from_bytes(p.serialize().as_slice()).unwrap())
+            .collect::<Vec<_>>();
+        let publics = publics.iter().collect::<Vec<_>>();
+        let signature =
+            blst::min_pk::Signature::from_bytes(signature.serialize().as_slice()).unwrap();
+        match signature.aggregate_verify(true, msgs, DST_G2, &publics, true) {
+            BLST_ERROR::BLST_SUCCESS => Ok(()),
+            e => Err(e),
+        }
+    }
+
+    /// Verify that a given `MinSig` aggregate signature over distinct messages is valid according to `blst`.
+    fn blst_aggregate_verify_min_sig(
+        publics: &[group::G2],
+        msgs: &[&[u8]],
+        signature: &group::G1,
+    ) -> Result<(), BLST_ERROR> {
+        let publics = publics
+            .iter()
+            .map(|p| blst::min_sig::PublicKey::from_bytes(p.serialize().as_slice()).unwrap())
+            .collect::<Vec<_>>();
+        let publics = publics.iter().collect::<Vec<_>>();
+        let signature =
+            blst::min_sig::Signature::from_bytes(signature.serialize().as_slice()).unwrap();
+        match signature.aggregate_verify(true, msgs, DST_G1, &publics, true) {
+            BLST_ERROR::BLST_SUCCESS => Ok(()),
+            e => Err(e),
+        }
+    }
+
+    fn aggregate_same_message<V: Variant>(
+        blst: impl Fn(&[V::Public], &[u8], &V::Signature) -> Result<(), BLST_ERROR>,
+    ) {
+        let mut rng = thread_rng();
         let msg = &[1, 9, 6, 9];
-        let sig = sign(&private, msg);
-        verify(&public, msg, &sig).expect("signature should be valid");
-        blst_verify(&public, msg, &sig).expect("signature should be valid");
+        let (privates, publics): (Vec<_>, Vec<_>) =
+            (0..10).map(|_| keypair::<V, _>(&mut rng)).unzip();
+        let proofs = privates
+            .iter()
+            .zip(publics.iter())
+            .map(|(private, public)| (*public, sign_proof_of_possession::<V>(private)))
+            .collect::<Vec<_>>();
+        let signatures = privates
+            .iter()
+            .map(|private| sign::<V>(private, msg))
+            .collect::<Vec<_>>();
+        let signature = aggregate_signatures::<V>(&signatures);
+        verify_aggregate_same_message::<V>(&proofs, msg, &signature)
+            .expect("signature should be valid");
+        blst(&publics, msg, &signature).expect("signature should be valid");
+
+        // Aggregate public key should verify like a single public key
+        let public = aggregate_public_keys::<V>(&publics);
+        verify::<V>(&public, msg, &signature).expect("signature should be valid");
+
+        // Missing a signer
+        assert!(matches!(
+            verify_aggregate_same_message::<V>(&proofs[1..], msg, &signature),
+            Err(Error::InvalidSignature)
+        ));
+
+        // Rogue key (cancels out all other public keys) without a valid proof of possession
+        let (rogue_private, mut rogue) = keypair::<V, _>(&mut rng);
+        let mut negative_one = group::Scalar::zero();
+        negative_one.sub(&group::Scalar::one());
+        for public in &publics {
+            let mut negated = *public;
+            negated.mul(&negative_one);
+            rogue.add(&negated);
+        }
+        let forged = sign::<V>(&rogue_private, msg);
+        let mut rogue_proofs = proofs.clone();
+        rogue_proofs.push((rogue, sign_proof_of_possession::<V>(&rogue_private)));
+        assert!(matches!(
+            verify_aggregate_same_message::<V>(&rogue_proofs, msg, &forged),
+            Err(Error::InvalidSignature)
+        ));
     }
 
     #[test]
-    fn test_threshold_compatibility() {
+    fn test_aggregate_same_message() {
+        aggregate_same_message::<MinPk>(blst_fast_aggregate_verify);
+    }
+
+    #[test]
+    fn test_aggregate_same_message_min_sig() {
+        aggregate_same_message::<MinSig>(blst_fast_aggregate_verify_min_sig);
+    }
+
+    fn aggregate_distinct_messages<V: Variant>(
+        blst: impl Fn(&[V::Public], &[&[u8]], &V::Signature) -> Result<(), BLST_ERROR>,
+    ) {
+        let mut rng = thread_rng();
+        let msgs: Vec<Vec<u8>> = (0..10u8).map(|i| vec![i; 4]).collect();
+        let msgs: Vec<&[u8]> = msgs.iter().map(|m| m.as_slice()).collect();
+        let (privates, publics): (Vec<_>, Vec<_>) =
+            (0..10).map(|_| keypair::<V, _>(&mut rng)).unzip();
+        let signatures = privates
+            .iter()
+            .zip(msgs.iter())
+            .map(|(private, msg)| sign::<V>(private, msg))
+            .collect::<Vec<_>>();
+        let signature = aggregate_signatures::<V>(&signatures);
+        verify_aggregate_distinct_messages::<V>(&publics, &msgs, &signature)
+            .expect("signature should be valid");
+        blst(&publics, &msgs, &signature).expect("signature should be valid");
+
+        // Messages in the wrong order
+        let mut swapped = msgs.clone();
+        swapped.swap(0, 1);
+        assert!(matches!(
+            verify_aggregate_distinct_messages::<V>(&publics, &swapped, &signature),
+            Err(Error::InvalidSignature)
+        ));
+
+        // Duplicate messages
+        let mut duplicate = msgs.clone();
+        duplicate[1] = duplicate[0];
+        assert!(matches!(
+            verify_aggregate_distinct_messages::<V>(&publics, &duplicate, &signature),
+            Err(Error::DuplicateMessage)
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_distinct_messages() {
+        aggregate_distinct_messages::<MinPk>(blst_aggregate_verify);
+    }
+
+    #[test]
+    fn test_aggregate_distinct_messages_min_sig() {
+        aggregate_distinct_messages::<MinSig>(blst_aggregate_verify_min_sig);
+    }
+
+    fn threshold_compatibility<V: Variant>(
+        blst: impl Fn(&V::Public, &[u8], &V::Signature, &[u8]) -> Result<(), BLST_ERROR>,
+    ) {
         let (n, t) = (5, 4);
-        let (public, shares) = generate_shares(None, n, t);
+        let (public, shares) = generate_shares::<V>(None, n, t);
         let msg = &[1, 9, 6, 9];
-        let partials: Vec<_> = shares.iter().map(|s| partial_sign(s, msg)).collect();
+        let partials: Vec<_> = shares.iter().map(|s| partial_sign::<V>(s, msg)).collect();
         for p in &partials {
-            partial_verify(&public, msg, p).expect("signature should be valid");
+            partial_verify::<V>(&public, msg, p).expect("signature should be valid");
         }
-        let threshold_sig = aggregate(t, partials).unwrap();
-        let threshold_pub = poly::public(&public);
-        verify(&threshold_pub, msg, &threshold_sig).expect("signature should be valid");
-        blst_verify(&threshold_pub, msg, &threshold_sig).expect("signature should be valid");
+        let threshold_sig = aggregate::<V>(t, partials).unwrap();
+        let threshold_pub = poly::public::<V>(&public);
+        verify::<V>(&threshold_pub, msg, &threshold_sig).expect("signature should be valid");
+        blst(&threshold_pub, msg, &threshold_sig, V::MESSAGE).expect("signature should be valid");
+    }
+
+    #[test]
+    fn test_threshold_compatibility() {
+        threshold_compatibility::<MinPk>(blst_verify);
+    }
+
+    #[test]
+    fn test_aggregate_with_weights() {
+        let (n, t) = (7, 5);
+        let (public, shares) = generate_shares::<MinPk>(None, n, t);
+        let msg = &[1, 9, 6, 9];
+        let partials: Vec<_> = shares
+            .iter()
+            .map(|s| partial_sign::<MinPk>(s, msg))
+            .collect();
+
+        // Precompute weights for a fixed set of signers
+        let signers = [0u32, 2, 3, 5, 6];
+        let weights = poly::Weights::new(&signers).unwrap();
+        let signature = aggregate_with_weights::<MinPk>(&weights, &partials).unwrap();
+        let selected: Vec<_> = signers
+            .iter()
+            .map(|i| partials[*i as usize].clone())
+            .collect();
+        assert_eq!(signature, aggregate::<MinPk>(t, selected).unwrap());
+        verify::<MinPk>(&poly::public::<MinPk>(&public), msg, &signature)
+            .expect("signature should be valid");
+
+        // Missing a partial signature from one of the signers
+        let missing: Vec<_> = partials.iter().filter(|p| p.index != 3).cloned().collect();
+        assert!(matches!(
+            aggregate_with_weights::<MinPk>(&weights, &missing),
+            Err(Error::InvalidRecovery)
+        ));
+    }
+
+    fn partial_verify_table<V: Variant>() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (n, t) = (7, 5);
+        let (public, mut shares) = generate_shares::<V>(None, n, t);
+        let table = poly::PublicTable::<V>::new(&public, n);
+        assert_eq!(table.len(), n as usize);
+        for share in &shares {
+            assert_eq!(*table.get(share.index).unwrap(), share.public::<V>());
+        }
+        assert!(table.get(n).is_none());
+
+        // Verify all partial signatures at once
+        let msg = &[1, 9, 6, 9];
+        let partials: Vec<_> = shares.iter().map(|s| partial_sign::<V>(s, msg)).collect();
+        for p in &partials {
+            partial_verify_with_table::<V>(&table, msg, p).expect("signature should be valid");
+        }
+        partial_batch_verify::<V, _>(&mut rng, &table, msg, &partials)
+            .expect("signatures should be valid");
+        partial_batch_verify::<V, _>(&mut rng, &table, msg, &[]).expect("empty batch is valid");
+
+        // Wrong message
+        assert!(matches!(
+            partial_batch_verify::<V, _>(&mut rng, &table, &[1, 9, 6, 8], &partials),
+            Err(Error::InvalidSignature)
+        ));
+
+        // Corrupt a share
+        shares[3].private = group::Scalar::rand(&mut rng);
+        let mut corrupted = partials.clone();
+        corrupted[3] = partial_sign::<V>(&shares[3], msg);
+        assert!(matches!(
+            partial_batch_verify::<V, _>(&mut rng, &table, msg, &corrupted),
+            Err(Error::InvalidSignature)
+        ));
+        let invalid: Vec<_> = corrupted
+            .iter()
+            .filter(|p| partial_verify_with_table::<V>(&table, msg, p).is_err())
+            .map(|p| p.index)
+            .collect();
+        assert_eq!(invalid, vec![3]);
+
+        // Swapped indices (valid signatures attributed to the wrong signers)
+        let mut swapped = partials.clone();
+        swapped[0].index = 1;
+        swapped[1].index = 0;
+        assert!(partial_batch_verify::<V, _>(&mut rng, &table, msg, &swapped).is_err());
+
+        // Index outside of the table
+        let mut outside = partials.clone();
+        outside[0].index = n;
+        assert!(partial_batch_verify::<V, _>(&mut rng, &table, msg, &outside).is_err());
+        assert!(partial_verify_with_table::<V>(&table, msg, &outside[0]).is_err());
+    }
+
+    #[test]
+    fn test_partial_verify_table() {
+        partial_verify_table::<MinPk>();
+    }
+
+    #[test]
+    fn test_partial_verify_table_min_sig() {
+        partial_verify_table::<MinSig>();
+    }
+
+    #[test]
+    fn test_threshold_compatibility_min_sig() {
+        threshold_compatibility::<MinSig>(blst_verify_min_sig);
     }
 }
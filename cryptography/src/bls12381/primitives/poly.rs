@@ -7,7 +7,7 @@
 //! are performed over the correct field and that all elements are valid.
 
 use crate::bls12381::primitives::{
-    group::{self, Element, Scalar},
+    group::{self, Element, MinPk, Scalar, Variant},
     Error,
 };
 use rand::{rngs::OsRng, RngCore};
@@ -17,11 +17,13 @@ use std::collections::BTreeMap;
 pub type Private = Poly<group::Private>;
 
 /// Public polynomials represent commitments to secrets on a private polynomial.
-pub type Public = Poly<group::Public>;
+///
+/// Commitments are made in the public key group of the variant.
+pub type Public<V = MinPk> = Poly<<V as Variant>::Public>;
 
 /// Signature polynomials are used in threshold signing (where a signature
 /// is interpolated using at least `threshold` evaluations).
-pub type Signature = Poly<group::Signature>;
+pub type Signature<V = MinPk> = Poly<<V as Variant>::Signature>;
 
 /// A polynomial evaluation at a specific index.
 #[derive(Debug, Clone)]
@@ -86,6 +88,11 @@ impl<C> Poly<C> {
         &self.0[0]
     }
 
+    /// Returns the coefficients of the polynomial (starting with the constant term).
+    pub fn coefficients(&self) -> &[C] {
+        &self.0
+    }
+
     /// Returns the degree of the polynomial
     pub fn degree(&self) -> u32 {
         (self.0.len() - 1) as u32 // check size in deserialize, safe to cast
@@ -153,8 +160,12 @@ impl<C: Element> Poly<C> {
 
     /// Canonically serializes the polynomial.
     pub fn serialize(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-        for c in &self.0 {
+        // Normalize all coefficients at once (rather than once per coefficient)
+        let mut coeffs = self.0.clone();
+        C::batch_normalize(&mut coeffs);
+
+        let mut bytes = Vec::with_capacity(coeffs.len() * C::size());
+        for c in &coeffs {
             bytes.extend_from_slice(&c.serialize());
         }
         bytes
@@ -188,6 +199,11 @@ impl<C: Element> Poly<C> {
         xi.set_int(i + 1);
 
         // Use Horner's method to evaluate the polynomial
+        //
+        // Because `xi` is small, each step only requires a short scalar multiplication. This is
+        // faster than a multi-scalar multiplication over the coefficients (where the powers of `xi`
+        // are full-size scalars). To check many evaluations of the same polynomial at once, refer
+        // to `evaluate_linear_combination`.
         let res = self.0.iter().rev().fold(C::zero(), |mut sum, coeff| {
             sum.mul(&xi);
             sum.add(coeff);
@@ -199,67 +215,190 @@ impl<C: Element> Poly<C> {
         }
     }
 
+    /// Returns `weights[0] * p(indices[0]) + ... + weights[k-1] * p(indices[k-1])`.
+    ///
+    /// Rather than evaluating the polynomial at each index, this sums the weighted powers of each
+    /// index per coefficient and performs a single multi-scalar multiplication over the coefficients.
+    /// This is useful to check many evaluations of the same polynomial at once (by comparing against
+    /// a random linear combination of the evaluations).
+    ///
+    /// This panics if `indices` and `weights` are not the same length.
+    pub fn evaluate_linear_combination(&self, indices: &[u32], weights: &[Scalar]) -> C {
+        assert_eq!(indices.len(), weights.len(), "mismatched lengths");
+
+        // Compute the scalar for each coefficient
+        let mut scalars = vec![Scalar::zero(); self.0.len()];
+        for (i, weight) in indices.iter().zip(weights.iter()) {
+            let mut xi = Scalar::zero();
+            xi.set_int(i + 1);
+            let mut power = *weight;
+            for scalar in scalars.iter_mut() {
+                scalar.add(&power);
+                power.mul(&xi);
+            }
+        }
+        C::msm(&self.0, &scalars)
+    }
+
     /// Recover the polynomial's constant term given at least `t` polynomial evaluations.
+    ///
+    /// The first `t` evaluations (sorted by index) are used for recovery.
     pub fn recover(t: u32, mut evals: Vec<Eval<C>>) -> Result<C, Error> {
-        // Reference: https://github.com/celo-org/celo-threshold-bls-rs/blob/a714310be76620e10e8797d6637df64011926430/crates/threshold-bls/src/poly.rs#L131-L165
-
         // Ensure there are enough shares
         let t = t as usize;
         if evals.len() < t {
             return Err(Error::InvalidRecovery);
         }
 
-        // Convert the first `t` sorted shares into scalars
-        let mut err = None;
-        evals.sort_by(|a, b| a.index.cmp(&b.index));
-        let xs = evals
-            .into_iter()
-            .take(t)
-            .fold(BTreeMap::new(), |mut m, sh| {
-                let mut xi = Scalar::zero();
-                xi.set_int(sh.index + 1);
-                if m.insert(sh.index, (xi, sh.value)).is_some() {
-                    err = Some(Error::DuplicateEval);
-                }
-                m
-            });
-        if let Some(e) = err {
-            return Err(e);
+        // Compute weights for the first `t` sorted shares
+        evals.sort_by_key(|a| a.index);
+        evals.truncate(t);
+        let indices = evals.iter().map(|e| e.index).collect::<Vec<_>>();
+        let weights = Weights::new(&indices)?;
+        Self::recover_with_weights(&weights, &evals)
+    }
+
+    /// Recover the polynomial's constant term using precomputed Lagrange `weights`.
+    ///
+    /// An evaluation must be provided for each index in `weights` (evaluations at
+    /// any other index are ignored).
+    pub fn recover_with_weights(weights: &Weights, evals: &[Eval<C>]) -> Result<C, Error> {
+        // Select the evaluation for each weight
+        let mut selected = BTreeMap::new();
+        for eval in evals {
+            if !weights.0.contains_key(&eval.index) {
+                continue;
+            }
+            if selected.insert(eval.index, &eval.value).is_some() {
+                return Err(Error::DuplicateEval);
+            }
+        }
+        if selected.len() != weights.0.len() {
+            return Err(Error::InvalidRecovery);
+        }
+
+        // Multiply each evaluation by its weight and sum the result
+        let points = selected.into_values().cloned().collect::<Vec<_>>();
+        let scalars = weights.0.values().copied().collect::<Vec<_>>();
+        Ok(C::msm(&points, &scalars))
+    }
+}
+
+/// Lagrange coefficients (evaluated at `x = 0`) for a fixed set of indices.
+///
+/// Computing these coefficients requires `O(t^2)` scalar multiplications. If the
+/// constant term of many polynomials will be recovered from evaluations at the same
+/// indices (i.e. a threshold signature from the same signers in each round), the
+/// coefficients can be computed once and reused with `Poly::recover_with_weights`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Weights(BTreeMap<u32, Scalar>);
+
+impl Weights {
+    /// Computes the Lagrange coefficients for the provided indices.
+    pub fn new(indices: &[u32]) -> Result<Self, Error> {
+        // Reference: https://github.com/celo-org/celo-threshold-bls-rs/blob/a714310be76620e10e8797d6637df64011926430/crates/threshold-bls/src/poly.rs#L131-L165
+
+        // Convert indices into scalars
+        let mut xs = BTreeMap::new();
+        for index in indices {
+            let mut xi = Scalar::zero();
+            xi.set_int(index + 1);
+            if xs.insert(*index, xi).is_some() {
+                return Err(Error::DuplicateEval);
+            }
         }
 
-        // Iterate over all indices and for each multiply the lagrange basis
-        // with the value of the share
-        let mut acc = C::zero();
+        // Compute the numerator and denominator of each coefficient
+        let mut nums = Vec::with_capacity(xs.len());
+        let mut dens = Vec::with_capacity(xs.len());
         for (i, xi) in &xs {
-            let mut yi = xi.1.clone();
             let mut num = Scalar::one();
             let mut den = Scalar::one();
-
             for (j, xj) in &xs {
                 if i == j {
                     continue;
                 }
 
                 // xj - 0
-                num.mul(&xj.0);
+                num.mul(xj);
 
                 // 1 / (xj - xi)
-                let mut tmp = xj.0;
-                tmp.sub(&xi.0);
+                let mut tmp = *xj;
+                tmp.sub(xi);
                 den.mul(&tmp);
             }
+            nums.push(num);
+            dens.push(den);
+        }
 
-            let inv = den.inverse().ok_or(Error::NoInverse)?;
-            num.mul(&inv);
-            yi.mul(&num);
-            acc.add(&yi);
+        // Invert all denominators at once (using Montgomery's trick)
+        let mut prefix = Vec::with_capacity(dens.len());
+        let mut acc = Scalar::one();
+        for den in &dens {
+            prefix.push(acc);
+            acc.mul(den);
+        }
+        let mut inv = acc.inverse().ok_or(Error::NoInverse)?;
+        for i in (0..dens.len()).rev() {
+            let mut den_inv = prefix[i];
+            den_inv.mul(&inv);
+            inv.mul(&dens[i]);
+            nums[i].mul(&den_inv);
         }
-        Ok(acc)
+        Ok(Self(xs.into_keys().zip(nums).collect()))
+    }
+
+    /// Returns the indices the weights were computed for (in ascending order).
+    pub fn indices(&self) -> Vec<u32> {
+        self.0.keys().copied().collect()
+    }
+
+    /// Returns the number of weights.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether there are no weights.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Public keys of the shares at indices `0..n` of a public polynomial.
+///
+/// Verifying a partial signature against a public polynomial requires evaluating the polynomial at
+/// the signer's index (`O(t)` group operations). If many partial signatures will be verified against
+/// the same polynomial (i.e. a threshold signature in each round), the public key of each share can be
+/// computed once and reused with `ops::partial_verify_with_table` and `ops::partial_batch_verify`.
+#[derive(Debug, Clone)]
+pub struct PublicTable<V: Variant = MinPk>(Vec<V::Public>);
+
+impl<V: Variant> PublicTable<V> {
+    /// Evaluates the public polynomial at indices `0..n`.
+    pub fn new(public: &Public<V>, n: u32) -> Self {
+        let mut publics = (0..n).map(|i| public.evaluate(i).value).collect::<Vec<_>>();
+        V::Public::batch_normalize(&mut publics);
+        Self(publics)
+    }
+
+    /// Returns the public key of the share at `index` (if in the table).
+    pub fn get(&self, index: u32) -> Option<&V::Public> {
+        self.0.get(index as usize)
+    }
+
+    /// Returns the number of public keys in the table.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether the table is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 }
 
 /// Returns the public key of the polynomial (constant term).
-pub fn public(public: &Public) -> group::Public {
+pub fn public<V: Variant>(public: &Public<V>) -> V::Public {
     *public.constant()
 }
 
@@ -303,6 +442,77 @@ pub mod tests {
         Poly::recover(threshold, shares).unwrap_err();
     }
 
+    #[test]
+    fn recover_with_weights() {
+        let degree = 4;
+        let threshold = degree + 1;
+        let poly = Poly::<G2>::commit(new(degree));
+        let evals = (0..10).map(|i| poly.evaluate(i)).collect::<Vec<_>>();
+
+        // Weights can be reused across any evaluations at the same indices
+        let weights = Weights::new(&[7, 1, 3, 8, 5]).unwrap();
+        assert_eq!(weights.indices(), vec![1, 3, 5, 7, 8]);
+        assert_eq!(
+            Poly::recover_with_weights(&weights, &evals).unwrap(),
+            *poly.constant()
+        );
+        let selected = weights
+            .indices()
+            .iter()
+            .map(|i| evals[*i as usize].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Poly::recover(threshold, selected).unwrap(),
+            *poly.constant()
+        );
+
+        // Missing evaluation
+        assert!(matches!(
+            Poly::recover_with_weights(&weights, &evals[..8]),
+            Err(Error::InvalidRecovery)
+        ));
+
+        // Duplicate indices
+        assert!(matches!(
+            Weights::new(&[1, 2, 1]),
+            Err(Error::DuplicateEval)
+        ));
+    }
+
+    #[test]
+    fn evaluate_linear_combination() {
+        let poly = Poly::<G2>::commit(new(9));
+        let indices = [0, 3, 4, 17];
+        let weights = (0..indices.len())
+            .map(|_| Scalar::rand(&mut OsRng))
+            .collect::<Vec<_>>();
+        let mut expected = G2::zero();
+        for (i, w) in indices.iter().zip(weights.iter()) {
+            let mut eval = poly.evaluate(*i).value;
+            eval.mul(w);
+            expected.add(&eval);
+        }
+        assert_eq!(
+            poly.evaluate_linear_combination(&indices, &weights),
+            expected
+        );
+    }
+
+    #[test]
+    fn serialize_normalized() {
+        // Sum of commitments is not normalized
+        let mut poly = Poly::<G2>::commit(new(5));
+        poly.add(&Poly::<G2>::commit(new(5)));
+        let bytes = poly.serialize();
+        let expected = poly
+            .0
+            .iter()
+            .flat_map(|c| c.serialize())
+            .collect::<Vec<_>>();
+        assert_eq!(bytes, expected);
+        assert_eq!(Poly::<G2>::deserialize(&bytes, 6).unwrap(), poly);
+    }
+
     #[test]
     fn commit() {
         let secret = new(5);
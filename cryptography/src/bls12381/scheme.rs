@@ -1,11 +1,16 @@
 //! BLS12-381 implementation of the `Scheme` trait.
 
 use super::primitives::{
-    group::{self, Element, Scalar},
+    group::{self, Element, MinPk, Scalar},
     ops,
 };
-use crate::{utils::payload, PublicKey, Scheme, Signature};
-use rand::{rngs::OsRng, SeedableRng};
+use crate::{
+    keystore::Exportable,
+    utils::{legacy_payload, payload},
+    BatchItem, BatchScheme, PublicKey, Scheme, Signature,
+};
+use rand::{rngs::OsRng, CryptoRng, RngCore, SeedableRng};
+use zeroize::Zeroizing;
 
 /// BLS12-381 implementation of the `Scheme` trait.
 ///
@@ -22,7 +27,12 @@ pub struct Bls12381 {
 impl Bls12381 {
     /// Creates a new Bls12381 signer using randomness from the operating system.
     pub fn new() -> Self {
-        let (private, public) = ops::keypair(&mut OsRng);
+        Self::new_from(&mut OsRng)
+    }
+
+    /// Creates a new Bls12381 signer using the provided randomness.
+    pub fn new_from<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let (private, public) = ops::keypair::<MinPk, _>(rng);
         Self { private, public }
     }
 
@@ -33,6 +43,40 @@ impl Bls12381 {
         public.mul(&private);
         Some(Self { private, public })
     }
+
+    /// Returns a proof of possession of the signer's private key.
+    ///
+    /// This should be checked (using `ops::verify_proof_of_possession`) before
+    /// the signer's public key is used in any aggregate.
+    pub fn proof_of_possession(&self) -> group::Signature {
+        ops::sign_proof_of_possession::<MinPk>(&self.private)
+    }
+
+    /// Check that a signature is valid for the given message and public key using the
+    /// legacy (ambiguous) payload encoding.
+    ///
+    /// This should only be used to accept signatures produced before the payload encoding
+    /// was versioned (see [crate::utils] for the migration path).
+    pub fn verify_legacy(
+        namespace: &[u8],
+        message: &[u8],
+        public_key: &PublicKey,
+        signature: &Signature,
+    ) -> bool {
+        Self::verify_payload(&legacy_payload(namespace, message), public_key, signature)
+    }
+
+    fn verify_payload(payload: &[u8], public_key: &PublicKey, signature: &Signature) -> bool {
+        let public = match group::Public::deserialize(public_key.as_ref()) {
+            Some(public) => public,
+            None => return false,
+        };
+        let signature = match group::Signature::deserialize(signature.as_ref()) {
+            Some(signature) => signature,
+            None => return false,
+        };
+        ops::verify::<MinPk>(&public, payload, &signature).is_ok()
+    }
 }
 
 impl Default for Bls12381 {
@@ -48,7 +92,7 @@ impl Scheme for Bls12381 {
 
     fn sign(&mut self, namespace: &[u8], message: &[u8]) -> Signature {
         let payload = payload(namespace, message);
-        let signature = ops::sign(&self.private, &payload);
+        let signature = ops::sign::<MinPk>(&self.private, &payload);
         signature.serialize().into()
     }
 
@@ -62,22 +106,122 @@ impl Scheme for Bls12381 {
         public_key: &PublicKey,
         signature: &Signature,
     ) -> bool {
-        let public = match group::Public::deserialize(public_key.as_ref()) {
-            Some(public) => public,
-            None => return false,
-        };
-        let signature = match group::Signature::deserialize(signature.as_ref()) {
-            Some(signature) => signature,
-            None => return false,
-        };
-        let payload = payload(namespace, message);
-        ops::verify(&public, &payload, &signature).is_ok()
+        Self::verify_payload(&payload(namespace, message), public_key, signature)
+    }
+}
+
+impl BatchScheme for Bls12381 {
+    /// Verifies all signatures with a random linear combination over a single multi-pairing.
+    fn batch_verify<R: RngCore + CryptoRng>(rng: &mut R, items: &[BatchItem]) -> bool {
+        let mut payloads = Vec::with_capacity(items.len());
+        let mut parsed = Vec::with_capacity(items.len());
+        for (namespace, message, public_key, signature) in items {
+            let public = match group::Public::deserialize(public_key.as_ref()) {
+                Some(public) => public,
+                None => return false,
+            };
+            let signature = match group::Signature::deserialize(signature.as_ref()) {
+                Some(signature) => signature,
+                None => return false,
+            };
+            payloads.push(payload(namespace, message));
+            parsed.push((public, signature));
+        }
+        let batch = parsed
+            .into_iter()
+            .zip(payloads.iter())
+            .map(|((public, signature), payload)| (public, payload.as_slice(), signature))
+            .collect::<Vec<_>>();
+        ops::batch_verify::<MinPk, _>(rng, &batch).is_ok()
+    }
+}
+
+impl Exportable for Bls12381 {
+    const NAME: &'static str = "bls12381";
+
+    fn private_key(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.private.serialize())
+    }
+
+    fn from_private_key(private_key: &[u8]) -> Option<Self> {
+        Self::from(private_key.try_into().ok()?)
+    }
+
+    fn new_from<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Self::new_from(rng)
     }
 }
 
 /// Creates a new BLS12-381 signer with a secret key derived from the provided seed.
 pub fn insecure_signer(seed: u16) -> Bls12381 {
     let mut rng = rand::rngs::StdRng::seed_from_u64(seed as u64);
-    let (private, public) = ops::keypair(&mut rng);
+    let (private, public) = ops::keypair::<MinPk, _>(&mut rng);
     Bls12381 { private, public }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_batch_verify() {
+        let namespace = b"test";
+        let messages: Vec<Vec<u8>> = (0..5u8).map(|i| vec![i; 32]).collect();
+        let mut signers: Vec<_> = (0..5).map(insecure_signer).collect();
+        let publics: Vec<_> = signers.iter().map(|s| s.me()).collect();
+        let mut signatures: Vec<_> = signers
+            .iter_mut()
+            .zip(messages.iter())
+            .map(|(s, m)| s.sign(namespace, m))
+            .collect();
+        let batch: Vec<BatchItem> = (0..5)
+            .map(|i| {
+                (
+                    &namespace[..],
+                    &messages[i][..],
+                    &publics[i],
+                    &signatures[i],
+                )
+            })
+            .collect();
+        assert!(Bls12381::batch_verify(&mut thread_rng(), &batch));
+
+        // Replace a signature with one over a different message
+        signatures[1] = signers[1].sign(namespace, b"bad");
+        let batch: Vec<BatchItem> = (0..5)
+            .map(|i| {
+                (
+                    &namespace[..],
+                    &messages[i][..],
+                    &publics[i],
+                    &signatures[i],
+                )
+            })
+            .collect();
+        assert!(!Bls12381::batch_verify(&mut thread_rng(), &batch));
+        assert_eq!(Bls12381::batch_invalid(&mut thread_rng(), &batch), vec![1]);
+    }
+
+    #[test]
+    fn test_namespace_ambiguity() {
+        let mut signer = insecure_signer(0);
+        let public = signer.me();
+        let signature = signer.sign(b"ab", b"c");
+        assert!(Bls12381::verify(b"ab", b"c", &public, &signature));
+        assert!(!Bls12381::verify(b"a", b"bc", &public, &signature));
+        assert!(!Bls12381::verify_legacy(b"ab", b"c", &public, &signature));
+    }
+
+    #[test]
+    fn test_verify_legacy() {
+        let signer = insecure_signer(0);
+        let public = signer.me();
+        let signature: Signature = ops::sign::<MinPk>(&signer.private, b"abc")
+            .serialize()
+            .into();
+        assert!(Bls12381::verify_legacy(b"ab", b"c", &public, &signature));
+        assert!(Bls12381::verify_legacy(b"a", b"bc", &public, &signature));
+        assert!(!Bls12381::verify(b"ab", b"c", &public, &signature));
+    }
+}
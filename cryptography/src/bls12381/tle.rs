@@ -0,0 +1,410 @@
+//! Timelock encryption to a future round of a threshold signature.
+//!
+//! A message is encrypted to a round number (and the group public key of a DKG) such that it can
+//! only be decrypted once the threshold signature over that round (`ops::aggregate` over partial
+//! signatures of `round.to_be_bytes()`) is published. Until at least `threshold` contributors sign
+//! the round, no one (including the sender) can decrypt the message.
+//!
+//! # Construction
+//!
+//! Messages are encrypted with Boneh-Franklin identity-based encryption (`FullIdent`, made CCA-secure
+//! with the Fujisaki-Okamoto transform) where the identity is the round and the private key of the
+//! identity is the threshold signature over it. For a group public key `P = g^s` and round `i`
+//! (hashed to the signature group as `Q = H(i)`), the sender:
+//!
+//! 1. Samples a random `σ` and derives `r = H3(σ || M)`.
+//! 2. Computes `U = g^r` and `V = σ ⊕ H2(e(P^r, Q))`.
+//! 3. Encrypts `M` with ChaCha20-Poly1305 under the key `H4(σ)` to produce `W`.
+//!
+//! Given the threshold signature `S = Q^s`, anyone can recover `σ = V ⊕ H2(e(U, S))` (as
+//! `e(g^r, Q^s) = e(g^s, Q)^r`), decrypt `W`, and check that `U = g^{H3(σ || M)}`.
+//!
+//! `U` is in the public key group and `S` is in the signature group, so this works with either
+//! [Variant].
+//!
+//! # Example
+//!
+//! ```rust
+//! use commonware_cryptography::bls12381::{
+//!     dkg::ops::generate_shares,
+//!     primitives::{group::MinPk, ops::{partial_sign, aggregate}, poly::public},
+//!     tle::{encrypt, decrypt},
+//! };
+//! use rand::rngs::OsRng;
+//!
+//! // Generate a group public key (and shares)
+//! let (n, t) = (5, 3);
+//! let (commitment, shares) = generate_shares::<MinPk>(None, n, t);
+//! let group = public::<MinPk>(&commitment);
+//!
+//! // Encrypt a message to round 10
+//! let round = 10u64;
+//! let ciphertext = encrypt::<MinPk, _>(&mut OsRng, &group, round, b"hello world");
+//!
+//! // Once round 10 is signed, decrypt the message
+//! let partials: Vec<_> = shares
+//!     .iter()
+//!     .map(|s| partial_sign::<MinPk>(s, &round.to_be_bytes()))
+//!     .collect();
+//! let signature = aggregate::<MinPk>(t, partials).unwrap();
+//! assert_eq!(decrypt::<MinPk>(&signature, &ciphertext).unwrap(), b"hello world");
+//! ```
+
+use crate::bls12381::primitives::{
+    group::{Element, MinPk, Point, Scalar, Variant},
+    Error,
+};
+use chacha20poly1305::{
+    aead::{Aead, Payload},
+    ChaCha20Poly1305, KeyInit,
+};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+/// Domain separation tag used to derive the mask of `σ` from the pairing (`H2`).
+const DST_MASK: &[u8] = b"COMMONWARE_BLS12381_TLE_MASK_";
+
+/// Domain separation tag used to derive the encryption randomness (`H3`).
+const DST_RANDOMNESS: &[u8] = b"COMMONWARE_BLS12381_TLE_RANDOMNESS_";
+
+/// Domain separation tag used to derive the message key from `σ` (`H4`).
+const DST_KEY: &[u8] = b"COMMONWARE_BLS12381_TLE_KEY_";
+
+/// Length of `σ`.
+const SIGMA_LENGTH: usize = 32;
+
+/// Length of the authentication tag appended to the encrypted message.
+const TAG_LENGTH: usize = 16;
+
+/// Message encrypted to a round.
+#[derive(Clone, Debug)]
+pub struct Ciphertext<V: Variant = MinPk> {
+    /// Round the message is encrypted to.
+    pub round: u64,
+
+    u: V::Public,
+    v: [u8; SIGMA_LENGTH],
+    w: Vec<u8>,
+}
+
+impl<V: Variant> Ciphertext<V> {
+    /// Canonically serializes the ciphertext.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + V::Public::size() + SIGMA_LENGTH + 4 + self.w.len());
+        bytes.extend_from_slice(&self.round.to_be_bytes());
+        bytes.extend_from_slice(&self.u.serialize());
+        bytes.extend_from_slice(&self.v);
+        bytes.extend_from_slice(&(self.w.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&self.w);
+        bytes
+    }
+
+    /// Deserializes a canonically encoded ciphertext.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        let u_length = V::Public::size();
+        let header = 8 + u_length + SIGMA_LENGTH + 4;
+        if bytes.len() < header {
+            return None;
+        }
+        let round = u64::from_be_bytes(bytes[..8].try_into().unwrap());
+        let u = V::Public::deserialize(&bytes[8..8 + u_length])?;
+        let v = bytes[8 + u_length..8 + u_length + SIGMA_LENGTH]
+            .try_into()
+            .unwrap();
+        let w_length = u32::from_be_bytes(bytes[header - 4..header].try_into().unwrap()) as usize;
+        if w_length < TAG_LENGTH || bytes.len() - header != w_length {
+            return None;
+        }
+        Some(Self {
+            round,
+            u,
+            v,
+            w: bytes[header..].to_vec(),
+        })
+    }
+}
+
+/// Hashes a round to the signature group (the same point signed by `ops::partial_sign`
+/// over `round.to_be_bytes()`).
+fn hash_round<V: Variant>(round: u64) -> V::Signature {
+    let mut hm = V::Signature::zero();
+    hm.map(V::MESSAGE, &round.to_be_bytes());
+    hm
+}
+
+/// Derives the encryption randomness from `σ` and the message (`H3`).
+fn randomness(sigma: &[u8; SIGMA_LENGTH], message: &[u8]) -> Scalar {
+    let mut input = Zeroizing::new(Vec::with_capacity(SIGMA_LENGTH + message.len()));
+    input.extend_from_slice(sigma);
+    input.extend_from_slice(message);
+    Scalar::map(DST_RANDOMNESS, &input)
+}
+
+/// Masks (or unmasks) `σ` with the pairing shared by the sender and the holder of the round
+/// signature (`H2`).
+fn mask<V: Variant>(
+    u: &V::Public,
+    signature: &V::Signature,
+    sigma: &[u8; SIGMA_LENGTH],
+) -> [u8; SIGMA_LENGTH] {
+    let mut hasher = Sha256::new();
+    hasher.update(DST_MASK);
+    hasher.update(V::pairing(u, signature).serialize());
+    let mask: [u8; SIGMA_LENGTH] = hasher.finalize().into();
+    let mut masked = [0u8; SIGMA_LENGTH];
+    for (i, byte) in masked.iter_mut().enumerate() {
+        *byte = sigma[i] ^ mask[i];
+    }
+    masked
+}
+
+/// Derives the message key from `σ` (`H4`).
+fn key(sigma: &[u8; SIGMA_LENGTH]) -> Zeroizing<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    hasher.update(DST_KEY);
+    hasher.update(sigma);
+    Zeroizing::new(hasher.finalize().into())
+}
+
+/// Returns the additional data authenticated alongside the message.
+fn aad<V: Variant>(round: u64, u: &V::Public, v: &[u8; SIGMA_LENGTH]) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(8 + V::Public::size() + SIGMA_LENGTH);
+    aad.extend_from_slice(&round.to_be_bytes());
+    aad.extend_from_slice(&u.serialize());
+    aad.extend_from_slice(v);
+    aad
+}
+
+/// Encrypts `message` to `round` such that it can only be decrypted with the threshold signature
+/// (over `round.to_be_bytes()`) of the group with public key `public`.
+pub fn encrypt<V: Variant, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    public: &V::Public,
+    round: u64,
+    message: &[u8],
+) -> Ciphertext<V> {
+    // Sample σ and derive the encryption randomness
+    let mut sigma = Zeroizing::new([0u8; SIGMA_LENGTH]);
+    rng.fill_bytes(sigma.as_mut());
+    let r = randomness(&sigma, message);
+
+    // Compute U = g^r and V = σ ⊕ H2(e(P^r, H(round)))
+    let mut u = V::Public::one();
+    u.mul(&r);
+    let mut shared = *public;
+    shared.mul(&r);
+    let v = mask::<V>(&shared, &hash_round::<V>(round), &sigma);
+
+    // Encrypt the message with a key derived from σ (the key is only used once, so the
+    // nonce can be fixed)
+    let cipher = ChaCha20Poly1305::new(key(&sigma).as_ref().into());
+    let w = cipher
+        .encrypt(
+            &Default::default(),
+            Payload {
+                msg: message,
+                aad: &aad::<V>(round, &u, &v),
+            },
+        )
+        .expect("encryption should not fail");
+    Ciphertext { round, u, v, w }
+}
+
+/// Decrypts a ciphertext with the threshold signature over its round.
+///
+/// Returns an error if the signature is not the threshold signature over the round (of the group
+/// the message was encrypted to) or the ciphertext was modified.
+pub fn decrypt<V: Variant>(
+    signature: &V::Signature,
+    ciphertext: &Ciphertext<V>,
+) -> Result<Vec<u8>, Error> {
+    // Recover σ
+    let sigma = Zeroizing::new(mask::<V>(&ciphertext.u, signature, &ciphertext.v));
+
+    // Decrypt the message
+    let cipher = ChaCha20Poly1305::new(key(&sigma).as_ref().into());
+    let message = cipher
+        .decrypt(
+            &Default::default(),
+            Payload {
+                msg: &ciphertext.w,
+                aad: &aad::<V>(ciphertext.round, &ciphertext.u, &ciphertext.v),
+            },
+        )
+        .map_err(|_| Error::DecryptionFailed)?;
+
+    // Ensure the ciphertext was honestly generated
+    let mut u = V::Public::one();
+    u.mul(&randomness(&sigma, &message));
+    if u != ciphertext.u {
+        return Err(Error::DecryptionFailed);
+    }
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bls12381::dkg::{
+        ops::generate_shares,
+        simulation::{run, Config},
+    };
+    use crate::bls12381::primitives::{
+        group::{MinSig, Share},
+        ops::{aggregate, partial_sign, sign},
+        poly::{self, public},
+    };
+    use rand::thread_rng;
+    use std::collections::BTreeMap;
+
+    /// Runs an honest DKG (with the simulation harness) and returns the group public polynomial
+    /// and the share of each contributor.
+    fn run_dkg<V: Variant>(n: u32, t: u32) -> (poly::Public<V>, Vec<Share>) {
+        let outcome = run::<V>(&Config {
+            n,
+            t,
+            seed: 0,
+            faults: BTreeMap::new(),
+            concurrency: 1,
+        });
+        let output = outcome.output.unwrap();
+        let shares = outcome
+            .shares
+            .values()
+            .map(|result| {
+                assert_eq!(result.public, output.public);
+                result.share().unwrap().clone()
+            })
+            .collect();
+        (output.public, shares)
+    }
+
+    /// Generates the threshold signature over `round` from the first `t` shares.
+    fn sign_round<V: Variant>(shares: &[Share], t: u32, round: u64) -> V::Signature {
+        let partials = shares
+            .iter()
+            .take(t as usize)
+            .map(|s| partial_sign::<V>(s, &round.to_be_bytes()))
+            .collect::<Vec<_>>();
+        aggregate::<V>(t, partials).unwrap()
+    }
+
+    fn run_encrypt_decrypt<V: Variant>() {
+        let mut rng = thread_rng();
+        let (n, t) = (5, 3);
+        let (commitment, shares) = run_dkg::<V>(n, t);
+        let group = public::<V>(&commitment);
+
+        // Encrypt messages to a future round
+        let round = 42;
+        let message = b"hello from the past";
+        let ciphertext = encrypt::<V, _>(&mut rng, &group, round, message);
+        let empty = encrypt::<V, _>(&mut rng, &group, round, &[]);
+
+        // Signatures over other rounds cannot decrypt the message
+        let early = sign_round::<V>(&shares, t, round - 1);
+        assert!(matches!(
+            decrypt::<V>(&early, &ciphertext),
+            Err(Error::DecryptionFailed)
+        ));
+
+        // Once the round is signed, the message can be decrypted
+        let signature = sign_round::<V>(&shares, t, round);
+        assert_eq!(decrypt::<V>(&signature, &ciphertext).unwrap(), message);
+        assert!(decrypt::<V>(&signature, &empty).unwrap().is_empty());
+
+        // Any set of `t` shares yields the same signature
+        let signature = sign_round::<V>(&shares[(n - t) as usize..], t, round);
+        assert_eq!(decrypt::<V>(&signature, &ciphertext).unwrap(), message);
+    }
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        run_encrypt_decrypt::<MinPk>();
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_min_sig() {
+        run_encrypt_decrypt::<MinSig>();
+    }
+
+    #[test]
+    fn test_decrypt_wrong_group() {
+        let mut rng = thread_rng();
+        let (n, t) = (5, 3);
+        let (commitment, _) = run_dkg::<MinPk>(n, t);
+        let group = public::<MinPk>(&commitment);
+        let ciphertext = encrypt::<MinPk, _>(&mut rng, &group, 1, b"secret");
+
+        // A signature over the round from another group cannot decrypt the message
+        let (_, shares) = generate_shares::<MinPk>(None, n, t);
+        let signature = sign_round::<MinPk>(&shares, t, 1);
+        assert!(decrypt::<MinPk>(&signature, &ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_tampered() {
+        let mut rng = thread_rng();
+        let (n, t) = (5, 3);
+        let (commitment, shares) = generate_shares::<MinPk>(None, n, t);
+        let group = public::<MinPk>(&commitment);
+        let ciphertext = encrypt::<MinPk, _>(&mut rng, &group, 7, b"secret");
+        let signature = sign_round::<MinPk>(&shares, t, 7);
+
+        // Modify the round
+        let mut tampered = ciphertext.clone();
+        tampered.round = 8;
+        assert!(decrypt::<MinPk>(&signature, &tampered).is_err());
+
+        // Modify U
+        let mut tampered = ciphertext.clone();
+        tampered.u.add(&<MinPk as Variant>::Public::one());
+        assert!(decrypt::<MinPk>(&signature, &tampered).is_err());
+
+        // Modify V
+        let mut tampered = ciphertext.clone();
+        tampered.v[0] ^= 1;
+        assert!(decrypt::<MinPk>(&signature, &tampered).is_err());
+
+        // Modify W
+        let mut tampered = ciphertext.clone();
+        tampered.w[0] ^= 1;
+        assert!(decrypt::<MinPk>(&signature, &tampered).is_err());
+
+        // Not a threshold signature over the round
+        let (private, _) = crate::bls12381::primitives::ops::keypair::<MinPk, _>(&mut rng);
+        let forged = sign::<MinPk>(&private, &7u64.to_be_bytes());
+        assert!(decrypt::<MinPk>(&forged, &ciphertext).is_err());
+        assert_eq!(
+            decrypt::<MinPk>(&signature, &ciphertext).unwrap(),
+            b"secret"
+        );
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut rng = thread_rng();
+        let (commitment, _) = generate_shares::<MinPk>(None, 5, 3);
+        let group = public::<MinPk>(&commitment);
+        let ciphertext = encrypt::<MinPk, _>(&mut rng, &group, 3, b"secret");
+        let bytes = ciphertext.serialize();
+        assert_eq!(
+            Ciphertext::<MinPk>::deserialize(&bytes)
+                .unwrap()
+                .serialize(),
+            bytes
+        );
+
+        // Truncated and extended encodings are rejected
+        assert!(Ciphertext::<MinPk>::deserialize(&bytes[..bytes.len() - 1]).is_none());
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert!(Ciphertext::<MinPk>::deserialize(&extended).is_none());
+
+        // Invalid points are rejected
+        let mut invalid = bytes.clone();
+        invalid[8..8 + 48].fill(0xff);
+        assert!(Ciphertext::<MinPk>::deserialize(&invalid).is_none());
+    }
+}
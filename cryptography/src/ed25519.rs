@@ -23,10 +23,15 @@
 //! assert!(Ed25519::verify(namespace, msg, &signer.me(), &signature));
 //! ```
 
-use crate::{utils::payload, PublicKey, Scheme, Signature};
+use crate::{
+    keystore::Exportable,
+    utils::{legacy_payload, payload},
+    BatchItem, BatchScheme, PublicKey, Scheme, Signature,
+};
 use ed25519_consensus;
-use rand::rngs::OsRng;
+use rand::{rngs::OsRng, CryptoRng, RngCore};
 use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
 
 const SECRET_KEY_LENGTH: usize = 32;
 const PUBLIC_KEY_LENGTH: usize = 32;
@@ -42,7 +47,12 @@ pub struct Ed25519 {
 impl Ed25519 {
     /// Creates a new Ed25519 signer using randomness from the operating system.
     pub fn new() -> Self {
-        let signer = ed25519_consensus::SigningKey::new(OsRng);
+        Self::new_from(&mut OsRng)
+    }
+
+    /// Creates a new Ed25519 signer using the provided randomness.
+    pub fn new_from<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let signer = ed25519_consensus::SigningKey::new(rng);
         let verifier = signer.verification_key();
         Self {
             signer,
@@ -59,6 +69,37 @@ impl Ed25519 {
             verifier: verifier.to_bytes().to_vec().into(),
         }
     }
+
+    /// Check that a signature is valid for the given message and public key using the
+    /// legacy (ambiguous) payload encoding.
+    ///
+    /// This should only be used to accept signatures produced before the payload encoding
+    /// was versioned (see [crate::utils] for the migration path).
+    pub fn verify_legacy(
+        namespace: &[u8],
+        message: &[u8],
+        public_key: &PublicKey,
+        signature: &Signature,
+    ) -> bool {
+        Self::verify_payload(&legacy_payload(namespace, message), public_key, signature)
+    }
+
+    fn verify_payload(payload: &[u8], public_key: &PublicKey, signature: &Signature) -> bool {
+        let public_key: [u8; PUBLIC_KEY_LENGTH] = match public_key.as_ref().try_into() {
+            Ok(key) => key,
+            Err(_) => return false,
+        };
+        let public_key = match ed25519_consensus::VerificationKey::try_from(public_key) {
+            Ok(key) => key,
+            Err(_) => return false,
+        };
+        let signature: [u8; SIGNATURE_LENGTH] = match signature.as_ref().try_into() {
+            Ok(sig) => sig,
+            Err(_) => return false,
+        };
+        let signature = ed25519_consensus::Signature::from(signature);
+        public_key.verify(&signature, payload).is_ok()
+    }
 }
 
 impl Default for Ed25519 {
@@ -91,21 +132,50 @@ impl Scheme for Ed25519 {
         public_key: &PublicKey,
         signature: &Signature,
     ) -> bool {
-        let public_key: [u8; PUBLIC_KEY_LENGTH] = match public_key.as_ref().try_into() {
-            Ok(key) => key,
-            Err(_) => return false,
-        };
-        let public_key = match ed25519_consensus::VerificationKey::try_from(public_key) {
-            Ok(key) => key,
-            Err(_) => return false,
-        };
-        let signature: [u8; SIGNATURE_LENGTH] = match signature.as_ref().try_into() {
-            Ok(sig) => sig,
-            Err(_) => return false,
-        };
-        let signature = ed25519_consensus::Signature::from(signature);
-        let payload = payload(namespace, message);
-        public_key.verify(&signature, &payload).is_ok()
+        Self::verify_payload(&payload(namespace, message), public_key, signature)
+    }
+}
+
+impl BatchScheme for Ed25519 {
+    /// Verifies all signatures with a single multi-scalar multiplication.
+    ///
+    /// `ed25519-consensus` applies the same validation rules to batch verification as it
+    /// does to single verification, so a batch is valid if and only if every item would
+    /// be accepted by `verify`.
+    fn batch_verify<R: RngCore + CryptoRng>(rng: &mut R, items: &[BatchItem]) -> bool {
+        let mut verifier = ed25519_consensus::batch::Verifier::new();
+        for (namespace, message, public_key, signature) in items {
+            let public_key: [u8; PUBLIC_KEY_LENGTH] = match public_key.as_ref().try_into() {
+                Ok(key) => key,
+                Err(_) => return false,
+            };
+            let public_key = ed25519_consensus::VerificationKeyBytes::from(public_key);
+            let signature: [u8; SIGNATURE_LENGTH] = match signature.as_ref().try_into() {
+                Ok(sig) => sig,
+                Err(_) => return false,
+            };
+            let signature = ed25519_consensus::Signature::from(signature);
+            let payload = payload(namespace, message);
+            verifier.queue((public_key, signature, &payload));
+        }
+        verifier.verify(rng).is_ok()
+    }
+}
+
+impl Exportable for Ed25519 {
+    const NAME: &'static str = "ed25519";
+
+    fn private_key(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.signer.to_bytes().to_vec())
+    }
+
+    fn from_private_key(private_key: &[u8]) -> Option<Self> {
+        let private_key: [u8; SECRET_KEY_LENGTH] = private_key.try_into().ok()?;
+        Some(Self::from(private_key))
+    }
+
+    fn new_from<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Self::new_from(rng)
     }
 }
 
@@ -120,3 +190,88 @@ pub fn insecure_signer(seed: u16) -> Ed25519 {
     let secret_key: [u8; SECRET_KEY_LENGTH] = Sha256::digest(seed.to_be_bytes()).into();
     Ed25519::from(secret_key)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_batch_verify() {
+        let namespace = b"test";
+        let messages: Vec<Vec<u8>> = (0..10u8).map(|i| vec![i; 32]).collect();
+        let mut signers: Vec<_> = (0..10).map(insecure_signer).collect();
+        let publics: Vec<_> = signers.iter().map(|s| s.me()).collect();
+        let mut signatures: Vec<_> = signers
+            .iter_mut()
+            .zip(messages.iter())
+            .map(|(s, m)| s.sign(namespace, m))
+            .collect();
+        let batch: Vec<BatchItem> = (0..10)
+            .map(|i| {
+                (
+                    &namespace[..],
+                    &messages[i][..],
+                    &publics[i],
+                    &signatures[i],
+                )
+            })
+            .collect();
+        assert!(Ed25519::batch_verify(&mut thread_rng(), &batch));
+        assert!(Ed25519::batch_invalid(&mut thread_rng(), &batch).is_empty());
+
+        // Swap two signatures
+        signatures.swap(3, 7);
+        let batch: Vec<BatchItem> = (0..10)
+            .map(|i| {
+                (
+                    &namespace[..],
+                    &messages[i][..],
+                    &publics[i],
+                    &signatures[i],
+                )
+            })
+            .collect();
+        assert!(!Ed25519::batch_verify(&mut thread_rng(), &batch));
+        assert_eq!(
+            Ed25519::batch_invalid(&mut thread_rng(), &batch),
+            vec![3, 7]
+        );
+    }
+
+    #[test]
+    fn test_batch_verify_malformed() {
+        let namespace = b"test";
+        let message = b"hello";
+        let mut signer = insecure_signer(0);
+        let public = signer.me();
+        let signature = signer.sign(namespace, message);
+        let truncated = Signature::from(signature[..SIGNATURE_LENGTH - 1].to_vec());
+        let batch: Vec<BatchItem> = vec![
+            (&namespace[..], &message[..], &public, &signature),
+            (&namespace[..], &message[..], &public, &truncated),
+        ];
+        assert!(!Ed25519::batch_verify(&mut thread_rng(), &batch));
+        assert_eq!(Ed25519::batch_invalid(&mut thread_rng(), &batch), vec![1]);
+    }
+
+    #[test]
+    fn test_namespace_ambiguity() {
+        let mut signer = insecure_signer(0);
+        let public = signer.me();
+        let signature = signer.sign(b"ab", b"c");
+        assert!(Ed25519::verify(b"ab", b"c", &public, &signature));
+        assert!(!Ed25519::verify(b"a", b"bc", &public, &signature));
+        assert!(!Ed25519::verify_legacy(b"ab", b"c", &public, &signature));
+    }
+
+    #[test]
+    fn test_verify_legacy() {
+        let signer = insecure_signer(0);
+        let public = signer.me();
+        let signature: Signature = signer.signer.sign(b"abc").to_bytes().to_vec().into();
+        assert!(Ed25519::verify_legacy(b"ab", b"c", &public, &signature));
+        assert!(Ed25519::verify_legacy(b"a", b"bc", &public, &signature));
+        assert!(!Ed25519::verify(b"ab", b"c", &public, &signature));
+    }
+}
@@ -0,0 +1,178 @@
+//! Orchestrator of a FROST signing round.
+//!
+//! # Phases
+//!
+//! The arbiter first collects a [Commitment] from each contributor that is willing to sign. After
+//! the Phase 0 timeout, every contributor that submitted a commitment becomes a signer (there must be
+//! at least `threshold` of them) and the arbiter distributes the commitments of all signers to all
+//! signers (`P1::commitments`). The arbiter then collects a [Partial] signature from each signer,
+//! verifies it, and aggregates all partial signatures into a signature over the message.
+//!
+//! FROST is not robust: if any signer does not submit a valid partial signature, the round fails.
+//! The signer is disqualified (and returned) so that it can be excluded from a future round.
+
+use super::{
+    contributor::{pairs, Commitment, Partial},
+    primitives::{self, Public},
+    Error,
+};
+use crate::{utils::payload, Signature};
+use curve25519_dalek::{edwards::EdwardsPoint, scalar::Scalar};
+use std::collections::{BTreeMap, HashSet};
+
+/// Collect commitments from contributors.
+pub struct P0 {
+    public: Public,
+    payload: Vec<u8>,
+
+    commitments: BTreeMap<u32, Commitment>,
+    disqualified: HashSet<u32>,
+}
+
+impl P0 {
+    /// Create a new arbiter for a round signing `message` (in `namespace`) with the secret
+    /// committed to by `public`.
+    pub fn new(public: Public, namespace: &[u8], message: &[u8]) -> Self {
+        Self {
+            public,
+            payload: payload(namespace, message),
+            commitments: BTreeMap::new(),
+            disqualified: HashSet::new(),
+        }
+    }
+
+    /// Required number of signers to produce a signature.
+    pub fn required(&self) -> u32 {
+        self.public.required()
+    }
+
+    /// Disqualify a contributor from the round.
+    pub fn disqualify(&mut self, contributor: u32) {
+        self.commitments.remove(&contributor);
+        self.disqualified.insert(contributor);
+    }
+
+    /// Verify and track a commitment from a contributor.
+    pub fn commitment(&mut self, contributor: u32, commitment: Commitment) -> Result<(), Error> {
+        if self.disqualified.contains(&contributor) {
+            return Err(Error::ContributorDisqualified);
+        }
+        if self.commitments.contains_key(&contributor) {
+            return Err(Error::DuplicateCommitment);
+        }
+        self.commitments.insert(contributor, commitment);
+        Ok(())
+    }
+
+    /// If there are at least `required` commitments, select all contributors that submitted
+    /// a commitment as signers and proceed to collecting partial signatures.
+    ///
+    /// Return the disqualified contributors.
+    pub fn finalize(self) -> (Option<P1>, HashSet<u32>) {
+        if self.commitments.len() < self.required() as usize {
+            return (None, self.disqualified);
+        }
+
+        // Compute the challenge
+        let group = *self.public.constant();
+        let pairs = pairs(&self.commitments);
+        let binding_factors = primitives::binding_factors(&group, &pairs, &self.payload);
+        let group_commitment = primitives::group_commitment(&pairs, &binding_factors);
+        let challenge = primitives::challenge(&group_commitment, &group, &self.payload);
+        (
+            Some(P1 {
+                public: self.public,
+                commitments: self.commitments,
+                binding_factors,
+                group_commitment,
+                challenge,
+                partials: BTreeMap::new(),
+                disqualified: self.disqualified.clone(),
+            }),
+            self.disqualified,
+        )
+    }
+}
+
+/// Collect partial signatures from signers.
+pub struct P1 {
+    public: Public,
+    commitments: BTreeMap<u32, Commitment>,
+    binding_factors: BTreeMap<u32, Scalar>,
+    group_commitment: EdwardsPoint,
+    challenge: Scalar,
+
+    partials: BTreeMap<u32, Scalar>,
+    disqualified: HashSet<u32>,
+}
+
+impl P1 {
+    /// Returns the commitments of all signers (to distribute to all signers).
+    pub fn commitments(&self) -> BTreeMap<u32, Commitment> {
+        self.commitments.clone()
+    }
+
+    /// Disqualify a signer from the round.
+    pub fn disqualify(&mut self, signer: u32) {
+        self.disqualified.insert(signer);
+    }
+
+    /// Verify and track a partial signature from a signer.
+    ///
+    /// If the partial signature is invalid, the signer is disqualified.
+    pub fn partial(&mut self, partial: Partial) -> Result<(), Error> {
+        let signer = partial.index;
+        if self.disqualified.contains(&signer) {
+            return Err(Error::ContributorDisqualified);
+        }
+        let commitment = match self.commitments.get(&signer) {
+            Some(commitment) => commitment,
+            None => return Err(Error::UnexpectedPartial),
+        };
+        if self.partials.contains_key(&signer) {
+            return Err(Error::DuplicatePartial);
+        }
+
+        // Verify the partial signature
+        let indices = self.commitments.keys().copied().collect::<Vec<_>>();
+        let lambda = primitives::lagrange(signer, &indices).expect("signer must be selected");
+        let public = primitives::evaluate_public(&self.public, signer);
+        let expected = commitment.hiding
+            + commitment.binding * self.binding_factors[&signer]
+            + public * (self.challenge * lambda);
+        if EdwardsPoint::mul_base(&partial.value) != expected {
+            self.disqualified.insert(signer);
+            return Err(Error::InvalidPartial);
+        }
+        self.partials.insert(signer, partial.value);
+        Ok(())
+    }
+
+    /// If all signers submitted a valid partial signature, aggregate them into a
+    /// signature (that can be verified with `Ed25519::verify`).
+    ///
+    /// Any signer that did not submit a valid partial signature is disqualified.
+    ///
+    /// Return the disqualified contributors.
+    pub fn finalize(mut self) -> (Result<Signature, Error>, HashSet<u32>) {
+        for signer in self.commitments.keys() {
+            if !self.partials.contains_key(signer) {
+                self.disqualified.insert(*signer);
+            }
+        }
+        if self
+            .commitments
+            .keys()
+            .any(|signer| self.disqualified.contains(signer))
+        {
+            return (Err(Error::MissingPartial), self.disqualified);
+        }
+
+        // Aggregate partial signatures
+        let z = self.partials.values().sum::<Scalar>();
+        let mut signature = Vec::with_capacity(64);
+        signature.extend_from_slice(&primitives::serialize_point(&self.group_commitment));
+        signature.extend_from_slice(&primitives::serialize_scalar(&z));
+        (Ok(signature.into()), self.disqualified)
+    }
+}
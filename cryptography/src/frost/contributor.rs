@@ -0,0 +1,187 @@
+//! Participants in a FROST signing round.
+//!
+//! # Phases
+//!
+//! A contributor first commits to a pair of single-use nonces (`P0::finalize`) and sends the
+//! resulting [Commitment] to the arbiter. Once the arbiter has selected the signers of a round, it
+//! distributes the commitments of all signers (and the message to sign) and the contributor
+//! produces its [Partial] signature (`P1::sign`).
+//!
+//! Nonces are consumed when signing (so a [P1] can only ever produce a single partial signature).
+//! Reusing nonces across two messages would reveal the contributor's share.
+
+use super::{
+    primitives::{self, Public, Share, POINT_LENGTH, SCALAR_LENGTH},
+    Error,
+};
+use crate::utils::payload;
+use curve25519_dalek::{edwards::EdwardsPoint, scalar::Scalar};
+use rand::{CryptoRng, RngCore};
+use std::collections::BTreeMap;
+use zeroize::Zeroize;
+
+/// Commitments to the nonces of a contributor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Commitment {
+    pub hiding: EdwardsPoint,
+    pub binding: EdwardsPoint,
+}
+
+impl Commitment {
+    /// Canonically serializes the commitment.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 * POINT_LENGTH);
+        bytes.extend_from_slice(&primitives::serialize_point(&self.hiding));
+        bytes.extend_from_slice(&primitives::serialize_point(&self.binding));
+        bytes
+    }
+
+    /// Deserializes a canonically encoded commitment.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 2 * POINT_LENGTH {
+            return None;
+        }
+        let hiding = primitives::deserialize_point(&bytes[..POINT_LENGTH])?;
+        let binding = primitives::deserialize_point(&bytes[POINT_LENGTH..])?;
+        Some(Self { hiding, binding })
+    }
+}
+
+/// Partial signature generated by a contributor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Partial {
+    pub index: u32,
+    pub value: Scalar,
+}
+
+impl Partial {
+    /// Canonically serializes the partial signature.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(4 + SCALAR_LENGTH);
+        bytes.extend_from_slice(&self.index.to_be_bytes());
+        bytes.extend_from_slice(&primitives::serialize_scalar(&self.value));
+        bytes
+    }
+
+    /// Deserializes a canonically encoded partial signature.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 4 + SCALAR_LENGTH {
+            return None;
+        }
+        let index = u32::from_be_bytes(bytes[..4].try_into().unwrap());
+        let value = primitives::deserialize_scalar(&bytes[4..])?;
+        Some(Self { index, value })
+    }
+}
+
+/// Converts a set of commitments into the `(hiding, binding)` pairs used by primitives.
+pub(super) fn pairs(
+    commitments: &BTreeMap<u32, Commitment>,
+) -> BTreeMap<u32, (EdwardsPoint, EdwardsPoint)> {
+    commitments
+        .iter()
+        .map(|(index, commitment)| (*index, (commitment.hiding, commitment.binding)))
+        .collect()
+}
+
+/// Generate nonces and a commitment to them.
+pub struct P0 {
+    public: Public,
+    share: Share,
+}
+
+impl P0 {
+    /// Create a new contributor with a share of the secret committed to by `public`.
+    pub fn new(public: Public, share: Share) -> Self {
+        Self { public, share }
+    }
+
+    /// Generate single-use nonces and return the commitment to send to the arbiter.
+    pub fn finalize<R: RngCore + CryptoRng>(self, rng: &mut R) -> (P1, Commitment) {
+        let hiding = primitives::nonce(rng, &self.share.private);
+        let binding = primitives::nonce(rng, &self.share.private);
+        let commitment = Commitment {
+            hiding: EdwardsPoint::mul_base(&hiding),
+            binding: EdwardsPoint::mul_base(&binding),
+        };
+        (
+            P1 {
+                public: self.public.clone(),
+                share: self.share.clone(),
+                hiding,
+                binding,
+                commitment,
+            },
+            commitment,
+        )
+    }
+}
+
+impl Drop for P0 {
+    fn drop(&mut self) {
+        self.share.zeroize();
+    }
+}
+
+/// Generate a partial signature over the message selected by the arbiter.
+pub struct P1 {
+    public: Public,
+    share: Share,
+    hiding: Scalar,
+    binding: Scalar,
+    commitment: Commitment,
+}
+
+impl P1 {
+    /// Returns the commitment to the contributor's nonces.
+    pub fn commitment(&self) -> &Commitment {
+        &self.commitment
+    }
+
+    /// Sign `message` (in `namespace`) with the signers selected by the arbiter.
+    ///
+    /// The contributor must be one of the signers (with the commitment it generated)
+    /// and there must be at least `threshold` signers.
+    pub fn sign(
+        self,
+        namespace: &[u8],
+        message: &[u8],
+        commitments: &BTreeMap<u32, Commitment>,
+    ) -> Result<Partial, Error> {
+        // Ensure the signers are valid
+        if commitments.len() < self.public.required() as usize {
+            return Err(Error::InsufficientCommitments);
+        }
+        if commitments.get(&self.share.index) != Some(&self.commitment) {
+            return Err(Error::MissingCommitment);
+        }
+
+        // Compute the challenge
+        let payload = payload(namespace, message);
+        let group = self.public.constant();
+        let pairs = pairs(commitments);
+        let binding_factors = primitives::binding_factors(group, &pairs, &payload);
+        let group_commitment = primitives::group_commitment(&pairs, &binding_factors);
+        let challenge = primitives::challenge(&group_commitment, group, &payload);
+
+        // Compute the partial signature
+        let indices = commitments.keys().copied().collect::<Vec<_>>();
+        let lambda =
+            primitives::lagrange(self.share.index, &indices).expect("contributor must be a signer");
+        let value = self.hiding
+            + self.binding * binding_factors[&self.share.index]
+            + lambda * self.share.private * challenge;
+        Ok(Partial {
+            index: self.share.index,
+            value,
+        })
+    }
+}
+
+impl Drop for P1 {
+    fn drop(&mut self) {
+        self.share.zeroize();
+        self.hiding.zeroize();
+        self.binding.zeroize();
+    }
+}
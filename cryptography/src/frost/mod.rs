@@ -0,0 +1,350 @@
+//! Threshold Ed25519 signatures with FROST (Flexible Round-Optimized Schnorr Threshold Signatures).
+//!
+//! This module implements the two-round FROST signing protocol (with the `FROST(Ed25519, SHA-512)`
+//! ciphersuite of RFC 9591). Any `threshold` of `n` holders of a share of a secret can produce a signature
+//! that is indistinguishable from (and verifies as) a standard Ed25519 signature over the group public key
+//! (i.e. with `Ed25519::verify`). This makes it possible to produce threshold signatures for external
+//! verifiers that do not support BLS12-381.
+//!
+//! Polynomials are stored in the same container used by the BLS12-381 DKG ([Poly](crate::bls12381::primitives::poly::Poly))
+//! and follow the same conventions: the share at index `i` is the evaluation of the private polynomial at
+//! `x = i + 1` and `public` is a commitment to each coefficient of the private polynomial. Because the
+//! operations on [Poly](crate::bls12381::primitives::poly::Poly) are defined over the BLS12-381 scalar field,
+//! commitment, evaluation, and interpolation over the Ed25519 scalar field and group are implemented in
+//! [primitives].
+//!
+//! # Protocol
+//!
+//! Like the BLS12-381 DKG, the protocol has two types of participants: the arbiter and contributors. The
+//! arbiter orchestrates a signing round (and can be implemented as a standalone process or by some consensus
+//! protocol) and contributors are the holders of shares.
+//!
+//! ## [Phase 0] Step 0: Commit to Nonces
+//!
+//! Each contributor that is willing to sign generates a pair of single-use nonces and sends a commitment
+//! to them to the arbiter. After the Phase 0 timeout, every contributor that submitted a commitment is
+//! selected as a signer. If there are not at least `threshold` signers, the arbiter will abort the round.
+//!
+//! ## [Phase 1] Step 1: Distribute Commitments
+//!
+//! The arbiter distributes the commitments of all signers (and the message to sign) to all signers.
+//!
+//! ## [Phase 1] Step 2: Sign
+//!
+//! Each signer uses its nonces (which are then discarded) and its share to produce a partial signature
+//! and sends it to the arbiter.
+//!
+//! ## [Phase 1] Step 3: Aggregate
+//!
+//! The arbiter verifies each partial signature (disqualifying any signer that submits an invalid partial
+//! signature) and, if all signers submitted a valid partial signature, aggregates them into a signature.
+//! If any signer did not, the round fails and should be retried without the disqualified signers.
+//!
+//! # Namespaces
+//!
+//! The signed message is the `payload` of the namespace and message (just like `Ed25519::sign`), so the
+//! signature can be verified with `Ed25519::verify` (using the same namespace).
+//!
+//! # Example
+//!
+//! ```rust
+//! use commonware_cryptography::{
+//!     ed25519::Ed25519,
+//!     frost::{arbiter, contributor, ops::generate_shares, primitives::public_key},
+//!     Scheme,
+//! };
+//! use rand::rngs::OsRng;
+//!
+//! // Generate shares of a new secret (with a trusted dealer)
+//! let (n, t) = (5, 3);
+//! let (public, shares) = generate_shares(&mut OsRng, n, t);
+//!
+//! // Commit to nonces
+//! let mut arbiter = arbiter::P0::new(public.clone(), b"namespace", b"message");
+//! let mut signers = Vec::new();
+//! for share in shares.into_iter().take(t as usize) {
+//!     let index = share.index;
+//!     let (signer, commitment) = contributor::P0::new(public.clone(), share).finalize(&mut OsRng);
+//!     arbiter.commitment(index, commitment).unwrap();
+//!     signers.push(signer);
+//! }
+//! let (arbiter, _) = arbiter.finalize();
+//! let mut arbiter = arbiter.unwrap();
+//!
+//! // Sign
+//! let commitments = arbiter.commitments();
+//! for signer in signers {
+//!     let partial = signer.sign(b"namespace", b"message", &commitments).unwrap();
+//!     arbiter.partial(partial).unwrap();
+//! }
+//!
+//! // Aggregate
+//! let (signature, _) = arbiter.finalize();
+//! let signature = signature.unwrap();
+//! assert!(Ed25519::verify(b"namespace", b"message", &public_key(&public), &signature));
+//! ```
+
+pub mod arbiter;
+pub mod contributor;
+pub mod ops;
+pub mod primitives;
+
+#[derive(Debug)]
+pub enum Error {
+    InsufficientCommitments,
+    DuplicateCommitment,
+    MissingCommitment,
+    ContributorDisqualified,
+    UnexpectedPartial,
+    DuplicatePartial,
+    InvalidPartial,
+    MissingPartial,
+    ShareWrongCommitment,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::InsufficientCommitments => write!(f, "insufficient commitments"),
+            Error::DuplicateCommitment => write!(f, "duplicate commitment"),
+            Error::MissingCommitment => write!(f, "missing commitment"),
+            Error::ContributorDisqualified => write!(f, "contributor disqualified"),
+            Error::UnexpectedPartial => write!(f, "unexpected partial"),
+            Error::DuplicatePartial => write!(f, "duplicate partial"),
+            Error::InvalidPartial => write!(f, "invalid partial"),
+            Error::MissingPartial => write!(f, "missing partial"),
+            Error::ShareWrongCommitment => write!(f, "share wrong commitment"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ed25519::Ed25519, Scheme, Signature};
+    use contributor::{Commitment, Partial};
+    use primitives::{public_key, Public, Share};
+    use rand::thread_rng;
+    use std::collections::{BTreeMap, HashSet};
+
+    const NAMESPACE: &[u8] = b"test";
+
+    /// Runs a signing round with the provided shares (where each signer is honest).
+    fn run_round(
+        public: &Public,
+        shares: &[Share],
+        message: &[u8],
+    ) -> (Result<Signature, Error>, HashSet<u32>) {
+        let mut rng = thread_rng();
+        let mut arbiter = arbiter::P0::new(public.clone(), NAMESPACE, message);
+        let mut signers = Vec::new();
+        for share in shares {
+            let (signer, commitment) =
+                contributor::P0::new(public.clone(), share.clone()).finalize(&mut rng);
+            arbiter.commitment(share.index, commitment).unwrap();
+            signers.push(signer);
+        }
+        let (arbiter, disqualified) = arbiter.finalize();
+        let mut arbiter = match arbiter {
+            Some(arbiter) => arbiter,
+            None => return (Err(Error::InsufficientCommitments), disqualified),
+        };
+        let commitments = arbiter.commitments();
+        for signer in signers {
+            let partial = signer.sign(NAMESPACE, message, &commitments).unwrap();
+            arbiter.partial(partial).unwrap();
+        }
+        arbiter.finalize()
+    }
+
+    #[test]
+    fn test_frost() {
+        let mut rng = thread_rng();
+        for (n, t) in [(1, 1), (3, 2), (5, 3), (7, 7), (10, 7)] {
+            let (public, shares) = ops::generate_shares(&mut rng, n, t);
+            for share in &shares {
+                ops::verify_share(&public, share).unwrap();
+            }
+            let group = public_key(&public);
+            assert!(Ed25519::validate(&group));
+
+            // Sign with exactly `t` signers
+            let (signature, disqualified) = run_round(&public, &shares[..t as usize], b"message");
+            let signature = signature.unwrap();
+            assert!(disqualified.is_empty());
+            assert!(Ed25519::verify(NAMESPACE, b"message", &group, &signature));
+            assert!(!Ed25519::verify(NAMESPACE, b"other", &group, &signature));
+            assert!(!Ed25519::verify(b"other", b"message", &group, &signature));
+
+            // Sign with all signers
+            let (signature, _) = run_round(&public, &shares, b"message");
+            assert!(Ed25519::verify(
+                NAMESPACE,
+                b"message",
+                &group,
+                &signature.unwrap()
+            ));
+        }
+    }
+
+    #[test]
+    fn test_frost_different_signers() {
+        let mut rng = thread_rng();
+        let (public, shares) = ops::generate_shares(&mut rng, 5, 3);
+        let group = public_key(&public);
+        for signers in [[0, 1, 2], [2, 3, 4], [0, 2, 4]] {
+            let shares = signers
+                .iter()
+                .map(|i| shares[*i].clone())
+                .collect::<Vec<_>>();
+            let (signature, _) = run_round(&public, &shares, b"message");
+            assert!(Ed25519::verify(
+                NAMESPACE,
+                b"message",
+                &group,
+                &signature.unwrap()
+            ));
+        }
+    }
+
+    #[test]
+    fn test_frost_insufficient_signers() {
+        let mut rng = thread_rng();
+        let (public, shares) = ops::generate_shares(&mut rng, 5, 3);
+        let (signature, _) = run_round(&public, &shares[..2], b"message");
+        assert!(matches!(signature, Err(Error::InsufficientCommitments)));
+    }
+
+    #[test]
+    fn test_frost_invalid_partial() {
+        let mut rng = thread_rng();
+        let (public, shares) = ops::generate_shares(&mut rng, 5, 3);
+        let mut arbiter = arbiter::P0::new(public.clone(), NAMESPACE, b"message");
+        let mut signers = Vec::new();
+        for share in &shares[..4] {
+            let (signer, commitment) =
+                contributor::P0::new(public.clone(), share.clone()).finalize(&mut rng);
+            arbiter.commitment(share.index, commitment).unwrap();
+            signers.push(signer);
+        }
+        assert!(matches!(
+            arbiter.commitment(0, *signers[1].commitment()),
+            Err(Error::DuplicateCommitment)
+        ));
+        let (arbiter, _) = arbiter.finalize();
+        let mut arbiter = arbiter.unwrap();
+        let commitments = arbiter.commitments();
+        let mut partials = signers
+            .into_iter()
+            .map(|signer| signer.sign(NAMESPACE, b"message", &commitments).unwrap())
+            .collect::<Vec<_>>();
+
+        // Submit an invalid partial signature
+        let tampered = Partial {
+            index: 1,
+            value: partials[1].value + curve25519_dalek::Scalar::ONE,
+        };
+        assert!(matches!(
+            arbiter.partial(tampered),
+            Err(Error::InvalidPartial)
+        ));
+        assert!(matches!(
+            arbiter.partial(partials.remove(1)),
+            Err(Error::ContributorDisqualified)
+        ));
+
+        // Submit the remaining partial signatures
+        arbiter.partial(partials[0].clone()).unwrap();
+        assert!(matches!(
+            arbiter.partial(partials[0].clone()),
+            Err(Error::DuplicatePartial)
+        ));
+        for partial in partials.into_iter().skip(1) {
+            arbiter.partial(partial).unwrap();
+        }
+        let unexpected = Partial {
+            index: 4,
+            value: curve25519_dalek::Scalar::ONE,
+        };
+        assert!(matches!(
+            arbiter.partial(unexpected),
+            Err(Error::UnexpectedPartial)
+        ));
+
+        // Ensure the round fails (and the signer is disqualified)
+        let (signature, disqualified) = arbiter.finalize();
+        assert!(matches!(signature, Err(Error::MissingPartial)));
+        assert_eq!(disqualified, HashSet::from([1]));
+    }
+
+    #[test]
+    fn test_frost_missing_commitment() {
+        let mut rng = thread_rng();
+        let (public, shares) = ops::generate_shares(&mut rng, 5, 3);
+        let mut commitments = BTreeMap::new();
+        let mut signers = Vec::new();
+        for share in &shares[..3] {
+            let (signer, commitment) =
+                contributor::P0::new(public.clone(), share.clone()).finalize(&mut rng);
+            commitments.insert(share.index, commitment);
+            signers.push(signer);
+        }
+
+        // Replace the commitment of a signer
+        let (_, other) = contributor::P0::new(public.clone(), shares[0].clone()).finalize(&mut rng);
+        let mut replaced = commitments.clone();
+        replaced.insert(0, other);
+        let signer = signers.remove(0);
+        assert!(matches!(
+            signer.sign(NAMESPACE, b"message", &replaced),
+            Err(Error::MissingCommitment)
+        ));
+
+        // Omit a signer
+        commitments.remove(&1);
+        commitments.insert(3, other);
+        let signer = signers.remove(0);
+        assert!(matches!(
+            signer.sign(NAMESPACE, b"message", &commitments),
+            Err(Error::MissingCommitment)
+        ));
+
+        // Too few signers
+        commitments.remove(&3);
+        let signer = signers.remove(0);
+        assert!(matches!(
+            signer.sign(NAMESPACE, b"message", &commitments),
+            Err(Error::InsufficientCommitments)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_share() {
+        let mut rng = thread_rng();
+        let (public, mut shares) = ops::generate_shares(&mut rng, 5, 3);
+        shares[0].index = 1;
+        assert!(matches!(
+            ops::verify_share(&public, &shares[0]),
+            Err(Error::ShareWrongCommitment)
+        ));
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut rng = thread_rng();
+        let (public, shares) = ops::generate_shares(&mut rng, 3, 2);
+        let (signer, commitment) =
+            contributor::P0::new(public.clone(), shares[0].clone()).finalize(&mut rng);
+        let (_, other) = contributor::P0::new(public.clone(), shares[1].clone()).finalize(&mut rng);
+        assert_eq!(
+            Commitment::deserialize(&commitment.serialize()).unwrap(),
+            commitment
+        );
+        let commitments = BTreeMap::from([(0, commitment), (1, other)]);
+        let partial = signer.sign(NAMESPACE, b"message", &commitments).unwrap();
+        assert_eq!(Partial::deserialize(&partial.serialize()).unwrap(), partial);
+        assert!(Partial::deserialize(&partial.serialize()[1..]).is_none());
+    }
+}
@@ -0,0 +1,29 @@
+//! Stateless operations useful in FROST.
+
+use super::{
+    primitives::{self, Public, Share},
+    Error,
+};
+use rand::{CryptoRng, RngCore};
+
+/// Generates shares (and a commitment to the private polynomial) of a new random secret
+/// with a trusted dealer.
+pub fn generate_shares<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    n: u32,
+    t: u32,
+) -> (Public, Vec<Share>) {
+    assert!(t > 0 && t <= n, "invalid threshold");
+    let private = primitives::new_from(t - 1, rng);
+    let commitment = primitives::commit(&private);
+    let shares = (0..n).map(|i| primitives::evaluate(&private, i)).collect();
+    (commitment, shares)
+}
+
+/// Verifies that a given share is consistent with the public polynomial.
+pub fn verify_share(public: &Public, share: &Share) -> Result<(), Error> {
+    if primitives::evaluate_public(public, share.index) != share.public() {
+        return Err(Error::ShareWrongCommitment);
+    }
+    Ok(())
+}
@@ -0,0 +1,327 @@
+//! Operations over the Ed25519 group (and its scalar field) used by FROST.
+//!
+//! Polynomials are stored in [Poly] (from the BLS12-381 primitives) with coefficients in the Ed25519 scalar
+//! field (for private polynomials) and the Ed25519 group (for public polynomials). [Poly] only provides
+//! commitment, evaluation, and recovery over the BLS12-381 scalar field, so the equivalent operations over
+//! the Ed25519 scalar field are implemented here (`commit`, `evaluate`, `evaluate_public`, and `lagrange`).
+//! Like the BLS12-381 primitives, the share at index `i` is the evaluation of the polynomial at `x = i + 1`.
+//!
+//! All hashes are defined by the `FROST(Ed25519, SHA-512)` ciphersuite of RFC 9591.
+
+use crate::bls12381::primitives::poly::Poly;
+use crate::PublicKey;
+use curve25519_dalek::{
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    scalar::Scalar,
+    traits::{Identity, IsIdentity, VartimeMultiscalarMul},
+};
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+use std::collections::BTreeMap;
+use zeroize::Zeroize;
+
+/// Private polynomials are used to generate secret shares.
+pub type Private = Poly<Scalar>;
+
+/// Public polynomials represent commitments to secrets on a private polynomial.
+pub type Public = Poly<EdwardsPoint>;
+
+/// Length of a serialized scalar.
+pub const SCALAR_LENGTH: usize = 32;
+
+/// Length of a serialized point.
+pub const POINT_LENGTH: usize = 32;
+
+/// Length of a serialized share.
+pub const SHARE_LENGTH: usize = 4 + SCALAR_LENGTH;
+
+/// Context string of the `FROST(Ed25519, SHA-512)` ciphersuite.
+const CONTEXT: &[u8] = b"FROST-ED25519-SHA512-v1";
+
+/// Private key share in a threshold signing scheme.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Share {
+    /// Index of the share.
+    pub index: u32,
+    /// Private key share.
+    pub private: Scalar,
+}
+
+impl Share {
+    /// Returns the public key corresponding to the share.
+    pub fn public(&self) -> EdwardsPoint {
+        EdwardsPoint::mul_base(&self.private)
+    }
+
+    /// Canonically serializes the share.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(SHARE_LENGTH);
+        bytes.extend_from_slice(&self.index.to_be_bytes());
+        bytes.extend_from_slice(self.private.as_bytes());
+        bytes
+    }
+
+    /// Deserializes a canonically encoded share.
+    pub fn deserialize(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != SHARE_LENGTH {
+            return None;
+        }
+        let index = u32::from_be_bytes(bytes[..4].try_into().unwrap());
+        let private = deserialize_scalar(&bytes[4..])?;
+        Some(Self { index, private })
+    }
+}
+
+impl Zeroize for Share {
+    fn zeroize(&mut self) {
+        self.index.zeroize();
+        self.private.zeroize();
+    }
+}
+
+/// Returns a new private polynomial of the given degree where each coefficient is
+/// sampled at random from the provided RNG.
+///
+/// In the context of secret sharing, the threshold is the degree + 1.
+pub fn new_from<R: RngCore + CryptoRng>(degree: u32, rng: &mut R) -> Private {
+    let coeffs = (0..=degree).map(|_| Scalar::random(rng)).collect();
+    Poly::from(coeffs)
+}
+
+/// Commits to each coefficient of a private polynomial.
+pub fn commit(private: &Private) -> Public {
+    let coeffs = private
+        .coefficients()
+        .iter()
+        .map(EdwardsPoint::mul_base)
+        .collect();
+    Poly::from(coeffs)
+}
+
+/// Returns the identifier (the scalar `x` at which the polynomial is evaluated) of an index.
+pub(super) fn identifier(index: u32) -> Scalar {
+    Scalar::from(index as u64 + 1)
+}
+
+/// Evaluates a private polynomial at the specified index.
+pub fn evaluate(private: &Private, index: u32) -> Share {
+    let x = identifier(index);
+    let private = private
+        .coefficients()
+        .iter()
+        .rev()
+        .fold(Scalar::ZERO, |sum, coeff| sum * x + coeff);
+    Share { index, private }
+}
+
+/// Evaluates a public polynomial at the specified index (returning the public key of the
+/// share at that index).
+pub fn evaluate_public(public: &Public, index: u32) -> EdwardsPoint {
+    let x = identifier(index);
+    let mut powers = Vec::with_capacity(public.required() as usize);
+    let mut power = Scalar::ONE;
+    for _ in 0..public.required() {
+        powers.push(power);
+        power *= x;
+    }
+    EdwardsPoint::vartime_multiscalar_mul(&powers, public.coefficients())
+}
+
+/// Returns the group public key of a public polynomial (in the encoding used by `Ed25519`).
+pub fn public_key(public: &Public) -> PublicKey {
+    PublicKey::copy_from_slice(&serialize_point(public.constant()))
+}
+
+/// Returns the Lagrange coefficient (evaluated at `x = 0`) of `index` over the
+/// provided set of `indices`.
+///
+/// Returns `None` if `index` is not in `indices`.
+pub fn lagrange(index: u32, indices: &[u32]) -> Option<Scalar> {
+    if !indices.contains(&index) {
+        return None;
+    }
+    let xi = identifier(index);
+    let mut numerator = Scalar::ONE;
+    let mut denominator = Scalar::ONE;
+    for j in indices {
+        if *j == index {
+            continue;
+        }
+        let xj = identifier(*j);
+        numerator *= xj;
+        denominator *= xj - xi;
+    }
+    Some(numerator * denominator.invert())
+}
+
+/// Canonically serializes a scalar.
+pub fn serialize_scalar(scalar: &Scalar) -> [u8; SCALAR_LENGTH] {
+    scalar.to_bytes()
+}
+
+/// Deserializes a canonically encoded scalar.
+pub fn deserialize_scalar(bytes: &[u8]) -> Option<Scalar> {
+    let bytes: [u8; SCALAR_LENGTH] = bytes.try_into().ok()?;
+    Scalar::from_canonical_bytes(bytes).into()
+}
+
+/// Canonically serializes a point.
+pub fn serialize_point(point: &EdwardsPoint) -> [u8; POINT_LENGTH] {
+    point.compress().to_bytes()
+}
+
+/// Deserializes a canonically encoded point.
+///
+/// The point must not be the identity and must be in the prime-order subgroup.
+pub fn deserialize_point(bytes: &[u8]) -> Option<EdwardsPoint> {
+    let compressed = CompressedEdwardsY::from_slice(bytes).ok()?;
+    let point = compressed.decompress()?;
+    if point.compress() != compressed || point.is_identity() || !point.is_torsion_free() {
+        return None;
+    }
+    Some(point)
+}
+
+/// Canonically serializes a public polynomial.
+pub fn serialize_public(public: &Public) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(public.required() as usize * POINT_LENGTH);
+    for coeff in public.coefficients() {
+        bytes.extend_from_slice(&serialize_point(coeff));
+    }
+    bytes
+}
+
+/// Deserializes a canonically encoded public polynomial (with `expected` coefficients).
+pub fn deserialize_public(bytes: &[u8], expected: u32) -> Option<Public> {
+    if expected == 0 || bytes.len() != (expected as usize).checked_mul(POINT_LENGTH)? {
+        return None;
+    }
+    let coeffs = bytes
+        .chunks_exact(POINT_LENGTH)
+        .map(deserialize_point)
+        .collect::<Option<Vec<_>>>()?;
+    Some(Poly::from(coeffs))
+}
+
+/// Computes `SHA-512(CONTEXT || label || input...)`.
+fn hash(label: &[u8], inputs: &[&[u8]]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+    hasher.update(CONTEXT);
+    hasher.update(label);
+    for input in inputs {
+        hasher.update(input);
+    }
+    hasher.finalize().into()
+}
+
+/// Generates a nonce from fresh randomness and a secret (`H3` of RFC 9591).
+pub(super) fn nonce<R: RngCore + CryptoRng>(rng: &mut R, secret: &Scalar) -> Scalar {
+    let mut random = [0u8; 32];
+    rng.fill_bytes(&mut random);
+    let nonce = Scalar::from_bytes_mod_order_wide(&hash(b"nonce", &[&random, secret.as_bytes()]));
+    random.zeroize();
+    nonce
+}
+
+/// Computes the binding factor of each signer (`H1`, `H4`, and `H5` of RFC 9591).
+///
+/// `commitments` maps each signer to its `(hiding, binding)` nonce commitments.
+pub(super) fn binding_factors(
+    group: &EdwardsPoint,
+    commitments: &BTreeMap<u32, (EdwardsPoint, EdwardsPoint)>,
+    message: &[u8],
+) -> BTreeMap<u32, Scalar> {
+    // Hash the message and commitment list
+    let message = hash(b"msg", &[message]);
+    let mut encoded = Vec::with_capacity(commitments.len() * (SCALAR_LENGTH + 2 * POINT_LENGTH));
+    for (index, (hiding, binding)) in commitments {
+        encoded.extend_from_slice(&serialize_scalar(&identifier(*index)));
+        encoded.extend_from_slice(&serialize_point(hiding));
+        encoded.extend_from_slice(&serialize_point(binding));
+    }
+    let encoded = hash(b"com", &[&encoded]);
+
+    // Derive a binding factor for each signer
+    let group = serialize_point(group);
+    commitments
+        .keys()
+        .map(|index| {
+            let id = serialize_scalar(&identifier(*index));
+            let rho = hash(b"rho", &[&group, &message, &encoded, &id]);
+            (*index, Scalar::from_bytes_mod_order_wide(&rho))
+        })
+        .collect()
+}
+
+/// Computes the group commitment (`R`) from each signer's nonce commitments and binding factor.
+pub(super) fn group_commitment(
+    commitments: &BTreeMap<u32, (EdwardsPoint, EdwardsPoint)>,
+    binding_factors: &BTreeMap<u32, Scalar>,
+) -> EdwardsPoint {
+    commitments.iter().fold(
+        EdwardsPoint::identity(),
+        |sum, (index, (hiding, binding))| sum + hiding + binding * binding_factors[index],
+    )
+}
+
+/// Computes the Ed25519 challenge (`H2` of RFC 9591, which is not domain separated so that the
+/// resulting signature is a standard Ed25519 signature).
+pub(super) fn challenge(
+    group_commitment: &EdwardsPoint,
+    group: &EdwardsPoint,
+    message: &[u8],
+) -> Scalar {
+    let mut hasher = Sha512::new();
+    hasher.update(serialize_point(group_commitment));
+    hasher.update(serialize_point(group));
+    hasher.update(message);
+    Scalar::from_bytes_mod_order_wide(&hasher.finalize().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_evaluate() {
+        let mut rng = thread_rng();
+        let private = new_from(3, &mut rng);
+        let public = commit(&private);
+        for i in 0..10 {
+            let share = evaluate(&private, i);
+            assert_eq!(share.public(), evaluate_public(&public, i));
+        }
+    }
+
+    #[test]
+    fn test_lagrange_recovery() {
+        let mut rng = thread_rng();
+        let private = new_from(2, &mut rng);
+        let indices = [1, 4, 7];
+        let recovered = indices
+            .iter()
+            .map(|i| evaluate(&private, *i).private * lagrange(*i, &indices).unwrap())
+            .sum::<Scalar>();
+        assert_eq!(recovered, *private.constant());
+        assert!(lagrange(0, &indices).is_none());
+    }
+
+    #[test]
+    fn test_serialization() {
+        let mut rng = thread_rng();
+        let private = new_from(2, &mut rng);
+        let public = commit(&private);
+        let bytes = serialize_public(&public);
+        assert_eq!(deserialize_public(&bytes, 3).unwrap(), public);
+        assert!(deserialize_public(&bytes, 2).is_none());
+        assert!(deserialize_public(&bytes[..bytes.len() - 1], 3).is_none());
+
+        let share = evaluate(&private, 3);
+        assert!(Share::deserialize(&share.serialize()).unwrap() == share);
+
+        // Identity and non-canonical scalars are rejected
+        assert!(deserialize_point(&serialize_point(&EdwardsPoint::identity())).is_none());
+        assert!(deserialize_scalar(&[0xff; SCALAR_LENGTH]).is_none());
+    }
+}
@@ -0,0 +1,552 @@
+//! Generate, export, and import passphrase-encrypted identity keys.
+//!
+//! A key file records the scheme of the key it contains (so that a key can't be loaded
+//! as the wrong type), the public key (so the identity in a key file can be determined without
+//! the passphrase), and the private key (encrypted). The private key is encrypted with
+//! ChaCha20-Poly1305 (using everything that precedes it as associated data) under a key derived
+//! from the passphrase with Argon2id.
+//!
+//! # Format
+//!
+//! All integers are big-endian.
+//!
+//! ```text
+//! magic ("CWKEYFIL") || version (u8)
+//! scheme (u8 length || name) || public key (u32 length || bytes)
+//! memory (u32) || iterations (u32) || parallelism (u32) || salt (16 bytes) || nonce (12 bytes)
+//! encrypted private key (u32 length || bytes)
+//! ```
+//!
+//! # Example
+//! ```rust
+//! use commonware_cryptography::{ed25519::Ed25519, keystore, Scheme};
+//! use rand::rngs::OsRng;
+//!
+//! // Generate a new key file
+//! let (signer, file) = keystore::generate::<Ed25519, _>(&mut OsRng, b"passphrase", keystore::Params::default()).unwrap();
+//!
+//! // Determine the scheme of the key file
+//! assert_eq!(keystore::scheme(&file).unwrap(), "ed25519");
+//!
+//! // Import the signer from the key file
+//! let imported = keystore::import::<Ed25519>(&file, b"passphrase").unwrap();
+//! assert_eq!(imported.me(), signer.me());
+//! ```
+
+use crate::{PublicKey, Scheme};
+use argon2::{Algorithm, Argon2, Version};
+use chacha20poly1305::{
+    aead::{Aead, Payload},
+    ChaCha20Poly1305, KeyInit,
+};
+use rand::{CryptoRng, RngCore};
+use zeroize::Zeroizing;
+
+/// Identifies a key file.
+const MAGIC: &[u8] = b"CWKEYFIL";
+
+/// Version of the key file format.
+const VERSION: u8 = 1;
+
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+/// Length of the authentication tag appended to encrypted data.
+pub(crate) const TAG_LENGTH: usize = 16;
+
+/// Maximum memory cost (in KiB) accepted when deriving a key (1 GiB).
+pub const MAX_MEMORY: u32 = 1024 * 1024;
+
+/// Maximum number of passes over memory accepted when deriving a key.
+pub const MAX_ITERATIONS: u32 = 16;
+
+/// Maximum degree of parallelism accepted when deriving a key.
+pub const MAX_PARALLELISM: u32 = 16;
+
+/// Errors that can occur when interacting with a key file.
+#[derive(Debug)]
+pub enum Error {
+    InvalidFile,
+    UnsupportedVersion,
+    InvalidParams,
+    SchemeMismatch,
+    DecryptionFailed,
+    InvalidKey,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::InvalidFile => write!(f, "invalid file"),
+            Error::UnsupportedVersion => write!(f, "unsupported version"),
+            Error::InvalidParams => write!(f, "invalid params"),
+            Error::SchemeMismatch => write!(f, "scheme mismatch"),
+            Error::DecryptionFailed => write!(f, "decryption failed"),
+            Error::InvalidKey => write!(f, "invalid key"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Schemes whose private keys can be exported to (and imported from) a key file.
+pub trait Exportable: Scheme {
+    /// Name of the scheme (recorded in key files).
+    const NAME: &'static str;
+
+    /// Returns the private key of the signer.
+    fn private_key(&self) -> Zeroizing<Vec<u8>>;
+
+    /// Creates a signer from a private key (returned by `private_key`).
+    fn from_private_key(private_key: &[u8]) -> Option<Self>;
+
+    /// Creates a new signer using the provided randomness.
+    fn new_from<R: RngCore + CryptoRng>(rng: &mut R) -> Self;
+}
+
+/// Parameters used to derive an encryption key from a passphrase (with Argon2id).
+///
+/// Parameters above `MAX_MEMORY`, `MAX_ITERATIONS`, or `MAX_PARALLELISM` are rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Params {
+    /// Memory cost (in KiB).
+    pub memory: u32,
+    /// Number of passes over memory.
+    pub iterations: u32,
+    /// Degree of parallelism.
+    pub parallelism: u32,
+}
+
+impl Default for Params {
+    /// Recommended parameters for Argon2id (19 MiB of memory and 2 iterations).
+    fn default() -> Self {
+        Self {
+            memory: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+impl Params {
+    /// Derives an encryption key from a passphrase.
+    ///
+    /// Parameters above `MAX_MEMORY`, `MAX_ITERATIONS`, or `MAX_PARALLELISM` are rejected (before
+    /// any work is performed), so a malicious file can't force an excessive amount of work.
+    fn derive(&self, passphrase: &[u8], salt: &[u8]) -> Option<Zeroizing<[u8; KEY_LENGTH]>> {
+        if self.memory > MAX_MEMORY
+            || self.iterations > MAX_ITERATIONS
+            || self.parallelism > MAX_PARALLELISM
+        {
+            return None;
+        }
+        let params = argon2::Params::new(
+            self.memory,
+            self.iterations,
+            self.parallelism,
+            Some(KEY_LENGTH),
+        )
+        .ok()?;
+        let mut key = Zeroizing::new([0u8; KEY_LENGTH]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase, salt, key.as_mut())
+            .ok()?;
+        Some(key)
+    }
+}
+
+/// Parameters, salt, and nonce used to encrypt the secret contents of a file (recorded in
+/// plaintext after the rest of its header).
+pub(crate) struct Envelope {
+    params: Params,
+    salt: [u8; SALT_LENGTH],
+    nonce: [u8; NONCE_LENGTH],
+}
+
+impl Envelope {
+    /// Creates an envelope with a random salt and nonce.
+    pub(crate) fn new<R: RngCore + CryptoRng>(rng: &mut R, params: Params) -> Self {
+        let mut salt = [0u8; SALT_LENGTH];
+        rng.fill_bytes(&mut salt);
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rng.fill_bytes(&mut nonce);
+        Self {
+            params,
+            salt,
+            nonce,
+        }
+    }
+
+    pub(crate) fn write(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.params.memory.to_be_bytes());
+        bytes.extend_from_slice(&self.params.iterations.to_be_bytes());
+        bytes.extend_from_slice(&self.params.parallelism.to_be_bytes());
+        bytes.extend_from_slice(&self.salt);
+        bytes.extend_from_slice(&self.nonce);
+    }
+
+    pub(crate) fn read<E>(reader: &mut Reader<'_, E>) -> Result<Self, E> {
+        let params = Params {
+            memory: reader.u32()?,
+            iterations: reader.u32()?,
+            parallelism: reader.u32()?,
+        };
+        let salt = reader.take(SALT_LENGTH)?.try_into().unwrap();
+        let nonce = reader.take(NONCE_LENGTH)?.try_into().unwrap();
+        Ok(Self {
+            params,
+            salt,
+            nonce,
+        })
+    }
+
+    /// Encrypts `plaintext` (and authenticates `aad`) under a key derived from `passphrase`.
+    pub(crate) fn seal(
+        &self,
+        passphrase: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let key = self
+            .params
+            .derive(passphrase, &self.salt)
+            .ok_or(Error::InvalidParams)?;
+        let cipher = ChaCha20Poly1305::new(key.as_ref().into());
+        Ok(cipher
+            .encrypt(
+                &self.nonce.into(),
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .expect("unable to encrypt"))
+    }
+
+    /// Decrypts `ciphertext` (and verifies `aad`) under a key derived from `passphrase`.
+    pub(crate) fn open(
+        &self,
+        passphrase: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, Error> {
+        let key = self
+            .params
+            .derive(passphrase, &self.salt)
+            .ok_or(Error::InvalidParams)?;
+        let cipher = ChaCha20Poly1305::new(key.as_ref().into());
+        let plaintext = cipher
+            .decrypt(
+                &self.nonce.into(),
+                Payload {
+                    msg: ciphertext,
+                    aad,
+                },
+            )
+            .map_err(|_| Error::DecryptionFailed)?;
+        Ok(Zeroizing::new(plaintext))
+    }
+}
+
+/// Reads the fields of a file (returning `error()` if the file is too short).
+pub(crate) struct Reader<'a, E> {
+    bytes: &'a [u8],
+    offset: usize,
+    error: fn() -> E,
+}
+
+impl<'a, E> Reader<'a, E> {
+    pub(crate) fn new(bytes: &'a [u8], error: fn() -> E) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            error,
+        }
+    }
+
+    /// Returns all bytes read so far.
+    pub(crate) fn read(&self) -> &'a [u8] {
+        &self.bytes[..self.offset]
+    }
+
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8], E> {
+        let end = self.offset.checked_add(len).ok_or_else(self.error)?;
+        if end > self.bytes.len() {
+            return Err((self.error)());
+        }
+        let taken = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(taken)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, E> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, E> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, E> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Ensures all bytes have been read.
+    pub(crate) fn finish(self) -> Result<(), E> {
+        if self.offset != self.bytes.len() {
+            return Err((self.error)());
+        }
+        Ok(())
+    }
+}
+
+/// Generates a new signer (using `rng`) and exports it to a key file.
+pub fn generate<C: Exportable, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    passphrase: &[u8],
+    params: Params,
+) -> Result<(C, Vec<u8>), Error> {
+    let signer = C::new_from(rng);
+    let file = export(rng, &signer, passphrase, params)?;
+    Ok((signer, file))
+}
+
+/// Exports a signer to a key file encrypted under `passphrase`.
+pub fn export<C: Exportable, R: RngCore + CryptoRng>(
+    rng: &mut R,
+    signer: &C,
+    passphrase: &[u8],
+    params: Params,
+) -> Result<Vec<u8>, Error> {
+    // Encode plaintext header
+    let mut file = Vec::new();
+    file.extend_from_slice(MAGIC);
+    file.push(VERSION);
+    const { assert!(C::NAME.len() <= u8::MAX as usize, "scheme name is too long") };
+    file.push(C::NAME.len() as u8);
+    file.extend_from_slice(C::NAME.as_bytes());
+    let public_key = signer.me();
+    file.extend_from_slice(&(public_key.len() as u32).to_be_bytes());
+    file.extend_from_slice(&public_key);
+    let envelope = Envelope::new(rng, params);
+    envelope.write(&mut file);
+
+    // Encrypt private key
+    let ciphertext = envelope.seal(passphrase, &file, &signer.private_key())?;
+    file.extend_from_slice(&(ciphertext.len() as u32).to_be_bytes());
+    file.extend_from_slice(&ciphertext);
+    Ok(file)
+}
+
+/// Plaintext header of a key file.
+struct Header<'a> {
+    scheme: &'a str,
+    public_key: &'a [u8],
+    envelope: Envelope,
+    aad: &'a [u8],
+    ciphertext: &'a [u8],
+}
+
+fn parse(file: &[u8]) -> Result<Header<'_>, Error> {
+    let mut reader = Reader::new(file, || Error::InvalidFile);
+    if reader.take(MAGIC.len())? != MAGIC {
+        return Err(Error::InvalidFile);
+    }
+    if reader.u8()? != VERSION {
+        return Err(Error::UnsupportedVersion);
+    }
+    let len = reader.u8()? as usize;
+    let scheme = std::str::from_utf8(reader.take(len)?).map_err(|_| Error::InvalidFile)?;
+    let len = reader.u32()? as usize;
+    let public_key = reader.take(len)?;
+    let envelope = Envelope::read(&mut reader)?;
+    let aad = reader.read();
+    let len = reader.u32()? as usize;
+    let ciphertext = reader.take(len)?;
+    reader.finish()?;
+    Ok(Header {
+        scheme,
+        public_key,
+        envelope,
+        aad,
+        ciphertext,
+    })
+}
+
+/// Returns the name of the scheme recorded in a key file.
+pub fn scheme(file: &[u8]) -> Result<String, Error> {
+    Ok(parse(file)?.scheme.to_string())
+}
+
+/// Returns the public key recorded in a key file.
+pub fn public_key(file: &[u8]) -> Result<PublicKey, Error> {
+    Ok(PublicKey::copy_from_slice(parse(file)?.public_key))
+}
+
+/// Imports a signer from a key file encrypted under `passphrase`.
+pub fn import<C: Exportable>(file: &[u8], passphrase: &[u8]) -> Result<C, Error> {
+    let header = parse(file)?;
+    if header.scheme != C::NAME {
+        return Err(Error::SchemeMismatch);
+    }
+
+    // Decrypt private key
+    let private_key = header
+        .envelope
+        .open(passphrase, header.aad, header.ciphertext)?;
+
+    // Ensure the private key matches the recorded public key
+    let signer = C::from_private_key(&private_key).ok_or(Error::InvalidKey)?;
+    if signer.me().as_ref() != header.public_key {
+        return Err(Error::InvalidKey);
+    }
+    Ok(signer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bls12381::scheme::Bls12381, ed25519::Ed25519, secp256k1::Secp256k1, secp256r1::Secp256r1,
+    };
+    use rand::{rngs::StdRng, thread_rng, SeedableRng};
+
+    /// Cheap parameters (to keep tests fast).
+    const PARAMS: Params = Params {
+        memory: 64,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn export_and_import<C: Exportable>() {
+        let mut rng = thread_rng();
+        let (signer, file) = generate::<C, _>(&mut rng, b"passphrase", PARAMS).unwrap();
+        assert_eq!(scheme(&file).unwrap(), C::NAME);
+        assert_eq!(public_key(&file).unwrap(), signer.me());
+
+        // Import and sign
+        let mut imported = import::<C>(&file, b"passphrase").unwrap();
+        assert_eq!(imported.me(), signer.me());
+        let signature = imported.sign(b"namespace", b"message");
+        assert!(C::verify(
+            b"namespace",
+            b"message",
+            &signer.me(),
+            &signature
+        ));
+
+        // Wrong passphrase
+        assert!(matches!(
+            import::<C>(&file, b"wrong"),
+            Err(Error::DecryptionFailed)
+        ));
+
+        // Tampered public key
+        let mut tampered = file.clone();
+        let offset = MAGIC.len() + 2 + C::NAME.len() + 4;
+        tampered[offset] ^= 1;
+        assert!(matches!(
+            import::<C>(&tampered, b"passphrase"),
+            Err(Error::DecryptionFailed)
+        ));
+
+        // Truncated
+        assert!(matches!(
+            import::<C>(&file[..file.len() - 1], b"passphrase"),
+            Err(Error::InvalidFile)
+        ));
+
+        // Unknown version
+        let mut tampered = file.clone();
+        tampered[MAGIC.len()] += 1;
+        assert!(matches!(
+            import::<C>(&tampered, b"passphrase"),
+            Err(Error::UnsupportedVersion)
+        ));
+
+        // Signer is generated from the provided randomness
+        let (a, _) =
+            generate::<C, _>(&mut StdRng::seed_from_u64(0), b"passphrase", PARAMS).unwrap();
+        let (b, _) =
+            generate::<C, _>(&mut StdRng::seed_from_u64(0), b"passphrase", PARAMS).unwrap();
+        assert_eq!(a.me(), b.me());
+    }
+
+    #[test]
+    fn test_export_and_import_ed25519() {
+        export_and_import::<Ed25519>();
+    }
+
+    #[test]
+    fn test_export_and_import_bls12381() {
+        export_and_import::<Bls12381>();
+    }
+
+    #[test]
+    fn test_export_and_import_secp256r1() {
+        export_and_import::<Secp256r1>();
+    }
+
+    #[test]
+    fn test_export_and_import_secp256k1() {
+        export_and_import::<Secp256k1>();
+    }
+
+    #[test]
+    fn test_scheme_mismatch() {
+        let mut rng = thread_rng();
+        let (_, file) = generate::<Ed25519, _>(&mut rng, b"passphrase", PARAMS).unwrap();
+        assert!(matches!(
+            import::<Bls12381>(&file, b"passphrase"),
+            Err(Error::SchemeMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let mut rng = thread_rng();
+        let params = Params {
+            memory: 0,
+            iterations: 0,
+            parallelism: 0,
+        };
+        assert!(matches!(
+            generate::<Ed25519, _>(&mut rng, b"passphrase", params),
+            Err(Error::InvalidParams)
+        ));
+    }
+
+    #[test]
+    fn test_excessive_params() {
+        let mut rng = thread_rng();
+        for params in [
+            Params {
+                memory: MAX_MEMORY + 1,
+                ..PARAMS
+            },
+            Params {
+                iterations: MAX_ITERATIONS + 1,
+                ..PARAMS
+            },
+            Params {
+                parallelism: MAX_PARALLELISM + 1,
+                ..PARAMS
+            },
+        ] {
+            assert!(matches!(
+                generate::<Ed25519, _>(&mut rng, b"passphrase", params),
+                Err(Error::InvalidParams)
+            ));
+        }
+
+        // Key files with excessive parameters are rejected before deriving a key
+        let (signer, mut file) = generate::<Ed25519, _>(&mut rng, b"passphrase", PARAMS).unwrap();
+        let offset = MAGIC.len() + 2 + Ed25519::NAME.len() + 4 + signer.me().len();
+        file[offset..offset + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(
+            import::<Ed25519>(&file, b"passphrase"),
+            Err(Error::InvalidParams)
+        ));
+    }
+}
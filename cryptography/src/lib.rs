@@ -1,9 +1,14 @@
 //! Generate keys, sign arbitrary messages, and deterministically verify untrusted signatures.
 
 use bytes::Bytes;
+use rand::{CryptoRng, RngCore};
 
 pub mod bls12381;
 pub mod ed25519;
+pub mod frost;
+pub mod keystore;
+pub mod secp256k1;
+pub mod secp256r1;
 pub mod utils;
 
 /// Byte array representing an arbitrary public key.
@@ -44,3 +49,46 @@ pub trait Scheme: Send + Sync + Clone + 'static {
         signature: &Signature,
     ) -> bool;
 }
+
+/// Signature to verify as part of a batch: `(namespace, message, public_key, signature)`.
+pub type BatchItem<'a> = (&'a [u8], &'a [u8], &'a PublicKey, &'a Signature);
+
+/// Interface for schemes that can verify many signatures at once.
+///
+/// Verifying a batch is typically much cheaper than verifying each signature
+/// individually but only reports whether all signatures are valid. If a batch
+/// fails, `batch_invalid` can be used to identify which items are invalid.
+pub trait BatchScheme: Scheme {
+    /// Check that all signatures in the batch are valid.
+    ///
+    /// The provided randomness is used to sample the coefficients of a random linear
+    /// combination over the batch (preventing an adversary from crafting invalid signatures
+    /// that cancel each other out). It must not be predictable by the signers.
+    ///
+    /// The default implementation verifies each signature individually.
+    fn batch_verify<R: RngCore + CryptoRng>(rng: &mut R, items: &[BatchItem]) -> bool {
+        let _ = rng;
+        items
+            .iter()
+            .all(|(namespace, message, public_key, signature)| {
+                Self::verify(namespace, message, public_key, signature)
+            })
+    }
+
+    /// Return the indices of all invalid signatures in the batch (sorted in ascending order).
+    ///
+    /// If the batch is valid, no signature is verified individually.
+    fn batch_invalid<R: RngCore + CryptoRng>(rng: &mut R, items: &[BatchItem]) -> Vec<usize> {
+        if Self::batch_verify(rng, items) {
+            return Vec::new();
+        }
+        items
+            .iter()
+            .enumerate()
+            .filter(|(_, (namespace, message, public_key, signature))| {
+                !Self::verify(namespace, message, public_key, signature)
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
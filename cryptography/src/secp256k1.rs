@@ -0,0 +1,527 @@
+//! Secp256k1 implementation of the Scheme trait (using BIP-340 Schnorr signatures).
+//!
+//! This implementation uses the `k256` crate to produce Schnorr signatures that can be verified
+//! by Bitcoin Taproot scripts (as specified in BIP-340). Public keys are encoded as 32-byte
+//! x-only keys and signatures are encoded as `r || s` (64 bytes).
+//!
+//! # Namespaces
+//!
+//! Instead of prefixing the message with the namespace, the namespace is used as the tag of a
+//! BIP-340 tagged hash (`SHA256(SHA256(namespace) || SHA256(namespace) || message)`) and the
+//! resulting 32-byte digest is signed. This provides unambiguous domain separation (the namespace
+//! is hashed independently of the message) and allows any verifier (like a Taproot script) to
+//! compute the signed digest with [tagged_hash].
+//!
+//! # Example
+//! ```rust
+//! use commonware_cryptography::{secp256k1::Secp256k1, Scheme};
+//!
+//! // Generate a new private key
+//! let mut signer = Secp256k1::new();
+//!
+//! // Create a message to sign
+//! let namespace = b"demo";
+//! let msg = b"hello, world!";
+//!
+//! // Sign the message
+//! let signature = signer.sign(namespace, msg);
+//!
+//! // Verify the signature
+//! assert!(Secp256k1::verify(namespace, msg, &signer.me(), &signature));
+//! ```
+
+use crate::{keystore::Exportable, BatchScheme, PublicKey, Scheme, Signature};
+use k256::schnorr::{self, SigningKey, VerifyingKey};
+use rand::{
+    rngs::{OsRng, StdRng},
+    CryptoRng, RngCore, SeedableRng,
+};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+const SECRET_KEY_LENGTH: usize = 32;
+const PUBLIC_KEY_LENGTH: usize = 32;
+const SIGNATURE_LENGTH: usize = 64;
+const DIGEST_LENGTH: usize = 32;
+
+/// Secp256k1 Signer.
+#[derive(Clone)]
+pub struct Secp256k1 {
+    signer: SigningKey,
+    verifier: PublicKey,
+
+    /// Source of auxiliary randomness for signing (if `None`, the operating system is used).
+    aux: Option<StdRng>,
+}
+
+impl Secp256k1 {
+    /// Creates a new Secp256k1 signer using randomness from the operating system.
+    pub fn new() -> Self {
+        Self::new_from(&mut OsRng)
+    }
+
+    /// Creates a new Secp256k1 signer using the provided randomness.
+    pub fn new_from<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Self::from_signing_key(SigningKey::random(rng))
+    }
+
+    /// Creates a new Secp256k1 signer from a secret key.
+    ///
+    /// Returns `None` if the secret key is zero or not less than the order of the curve.
+    pub fn from(signer: [u8; SECRET_KEY_LENGTH]) -> Option<Self> {
+        let signer = SigningKey::from_bytes(&signer).ok()?;
+        Some(Self::from_signing_key(signer))
+    }
+
+    fn from_signing_key(signer: SigningKey) -> Self {
+        let verifier = signer.verifying_key().to_bytes().to_vec().into();
+        Self {
+            signer,
+            verifier,
+            aux: None,
+        }
+    }
+
+    /// Signs a raw message (rather than the tagged hash of a namespace and message).
+    fn sign_raw(&self, message: &[u8], aux_rand: &[u8; 32]) -> Signature {
+        let signature = self
+            .signer
+            .sign_raw(message, aux_rand)
+            .expect("unable to sign message");
+        signature.to_bytes().to_vec().into()
+    }
+}
+
+impl Default for Secp256k1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Computes the BIP-340 tagged hash of `message` (using `tag` as the tag).
+///
+/// This is the digest that is signed (and verified) for a given namespace (used as the tag) and
+/// message.
+pub fn tagged_hash(tag: &[u8], message: &[u8]) -> [u8; DIGEST_LENGTH] {
+    let tag = Sha256::digest(tag);
+    let mut hasher = Sha256::new();
+    hasher.update(tag);
+    hasher.update(tag);
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+/// Verifies a signature over a raw message.
+fn verify_raw(public_key: &[u8], message: &[u8], signature: &[u8]) -> bool {
+    if public_key.len() != PUBLIC_KEY_LENGTH || signature.len() != SIGNATURE_LENGTH {
+        return false;
+    }
+    let public_key = match VerifyingKey::from_bytes(public_key) {
+        Ok(key) => key,
+        Err(_) => return false,
+    };
+    let signature = match schnorr::Signature::try_from(signature) {
+        Ok(sig) => sig,
+        Err(_) => return false,
+    };
+    public_key.verify_raw(message, &signature).is_ok()
+}
+
+impl Scheme for Secp256k1 {
+    fn me(&self) -> PublicKey {
+        self.verifier.clone()
+    }
+
+    fn sign(&mut self, namespace: &[u8], message: &[u8]) -> Signature {
+        let mut aux_rand = [0u8; 32];
+        match &mut self.aux {
+            Some(aux) => aux.fill_bytes(&mut aux_rand),
+            None => OsRng.fill_bytes(&mut aux_rand),
+        }
+        self.sign_raw(&tagged_hash(namespace, message), &aux_rand)
+    }
+
+    fn validate(public_key: &PublicKey) -> bool {
+        public_key.len() == PUBLIC_KEY_LENGTH && VerifyingKey::from_bytes(public_key).is_ok()
+    }
+
+    fn verify(
+        namespace: &[u8],
+        message: &[u8],
+        public_key: &PublicKey,
+        signature: &Signature,
+    ) -> bool {
+        verify_raw(public_key, &tagged_hash(namespace, message), signature)
+    }
+}
+
+impl BatchScheme for Secp256k1 {}
+
+impl Exportable for Secp256k1 {
+    const NAME: &'static str = "secp256k1";
+
+    fn private_key(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.signer.to_bytes().to_vec())
+    }
+
+    fn from_private_key(private_key: &[u8]) -> Option<Self> {
+        Self::from(private_key.try_into().ok()?)
+    }
+
+    fn new_from<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Self::new_from(rng)
+    }
+}
+
+/// Creates a new Secp256k1 signer with a secret key derived from the provided
+/// seed.
+///
+/// The auxiliary randomness used when signing is also derived from the seed, so
+/// the same sequence of signatures is produced on every run.
+///
+/// # Warning
+///
+/// This function is intended for testing and demonstration purposes only.
+/// It should never be used in production.
+pub fn insecure_signer(seed: u16) -> Secp256k1 {
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    let mut signer = Secp256k1::from_signing_key(SigningKey::random(&mut rng));
+    signer.aux = Some(rng);
+    signer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test vector from BIP-340.
+    struct Vector {
+        index: usize,
+        /// Empty if the vector only tests verification.
+        secret_key: &'static str,
+        public_key: &'static str,
+        aux_rand: &'static str,
+        message: &'static str,
+        signature: &'static str,
+        valid: bool,
+    }
+
+    /// Test vectors from <https://github.com/bitcoin/bips/blob/master/bip-0340/test-vectors.csv>.
+    const BIP340_VECTORS: &[Vector] = &[
+        Vector {
+            index: 0,
+            secret_key: "0000000000000000000000000000000000000000000000000000000000000003",
+            public_key: "F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9",
+            aux_rand: "0000000000000000000000000000000000000000000000000000000000000000",
+            message: "0000000000000000000000000000000000000000000000000000000000000000",
+            signature: "E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA821525F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0",
+            valid: true,
+        },
+        Vector {
+            index: 1,
+            secret_key: "B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF",
+            public_key: "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            aux_rand: "0000000000000000000000000000000000000000000000000000000000000001",
+            message: "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            signature: "6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A",
+            valid: true,
+        },
+        Vector {
+            index: 2,
+            secret_key: "C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9",
+            public_key: "DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8",
+            aux_rand: "C87AA53824B4D7AE2EB035A2B5BBBCCC080E76CDC6D1692C4B0B62D798E6D906",
+            message: "7E2D58D8B3BCDF1ABADEC7829054F90DDA9805AAB56C77333024B9D0A508B75C",
+            signature: "5831AAEED7B44BB74E5EAB94BA9D4294C49BCF2A60728D8B4C200F50DD313C1BAB745879A5AD954A72C45A91C3A51D3C7ADEA98D82F8481E0E1E03674A6F3FB7",
+            valid: true,
+        },
+        Vector {
+            index: 3,
+            secret_key: "0B432B2677937381AEF05BB02A66ECD012773062CF3FA2549E44F58ED2401710",
+            public_key: "25D1DFF95105F5253C4022F628A996AD3A0D95FBF21D468A1B33F8C160D8F517",
+            aux_rand: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            message: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF",
+            signature: "7EB0509757E246F19449885651611CB965ECC1A187DD51B64FDA1EDC9637D5EC97582B9CB13DB3933705B32BA982AF5AF25FD78881EBB32771FC5922EFC66EA3",
+            valid: true,
+        },
+        Vector {
+            index: 4,
+            secret_key: "",
+            public_key: "D69C3509BB99E412E68B0FE8544E72837DFA30746D8BE2AA65975F29D22DC7B9",
+            aux_rand: "",
+            message: "4DF3C3F68FCC83B27E9D42C90431A72499F17875C81A599B566C9889B9696703",
+            signature: "00000000000000000000003B78CE563F89A0ED9414F5AA28AD0D96D6795F9C6376AFB1548AF603B3EB45C9F8207DEE1060CB71C04E80F593060B07D28308D7F4",
+            valid: true,
+        },
+        Vector {
+            index: 5,
+            secret_key: "",
+            public_key: "EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34",
+            aux_rand: "",
+            message: "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            signature: "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+            valid: false,
+        },
+        Vector {
+            index: 6,
+            secret_key: "",
+            public_key: "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            aux_rand: "",
+            message: "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            signature: "FFF97BD5755EEEA420453A14355235D382F6472F8568A18B2F057A14602975563CC27944640AC607CD107AE10923D9EF7A73C643E166BE5EBEAFA34B1AC553E2",
+            valid: false,
+        },
+        Vector {
+            index: 7,
+            secret_key: "",
+            public_key: "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            aux_rand: "",
+            message: "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            signature: "1FA62E331EDBC21C394792D2AB1100A7B432B013DF3F6FF4F99FCB33E0E1515F28890B3EDB6E7189B630448B515CE4F8622A954CFE545735AAEA5134FCCDB2BD",
+            valid: false,
+        },
+        Vector {
+            index: 8,
+            secret_key: "",
+            public_key: "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            aux_rand: "",
+            message: "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            signature: "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769961764B3AA9B2FFCB6EF947B6887A226E8D7C93E00C5ED0C1834FF0D0C2E6DA6",
+            valid: false,
+        },
+        Vector {
+            index: 9,
+            secret_key: "",
+            public_key: "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            aux_rand: "",
+            message: "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            signature: "0000000000000000000000000000000000000000000000000000000000000000123DDA8328AF9C23A94C1FEECFD123BA4FB73476F0D594DCB65C6425BD186051",
+            valid: false,
+        },
+        Vector {
+            index: 10,
+            secret_key: "",
+            public_key: "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            aux_rand: "",
+            message: "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            signature: "00000000000000000000000000000000000000000000000000000000000000017615FBAF5AE28864013C099742DEADB4DBA87F11AC6754F93780D5A1837CF197",
+            valid: false,
+        },
+        Vector {
+            index: 11,
+            secret_key: "",
+            public_key: "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            aux_rand: "",
+            message: "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            signature: "4A298DACAE57395A15D0795DDBFD1DCB564DA82B0F269BC70A74F8220429BA1D69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+            valid: false,
+        },
+        Vector {
+            index: 12,
+            secret_key: "",
+            public_key: "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            aux_rand: "",
+            message: "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            signature: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+            valid: false,
+        },
+        Vector {
+            index: 13,
+            secret_key: "",
+            public_key: "DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659",
+            aux_rand: "",
+            message: "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            signature: "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141",
+            valid: false,
+        },
+        Vector {
+            index: 14,
+            secret_key: "",
+            public_key: "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30",
+            aux_rand: "",
+            message: "243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89",
+            signature: "6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B",
+            valid: false,
+        },
+        Vector {
+            index: 15,
+            secret_key: "0340034003400340034003400340034003400340034003400340034003400340",
+            public_key: "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+            aux_rand: "0000000000000000000000000000000000000000000000000000000000000000",
+            message: "",
+            signature: "71535DB165ECD9FBBC046E5FFAEA61186BB6AD436732FCCC25291A55895464CF6069CE26BF03466228F19A3A62DB8A649F2D560FAC652827D1AF0574E427AB63",
+            valid: true,
+        },
+        Vector {
+            index: 16,
+            secret_key: "0340034003400340034003400340034003400340034003400340034003400340",
+            public_key: "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+            aux_rand: "0000000000000000000000000000000000000000000000000000000000000000",
+            message: "11",
+            signature: "08A20A0AFEF64124649232E0693C583AB1B9934AE63B4C3511F3AE1134C6A303EA3173BFEA6683BD101FA5AA5DBC1996FE7CACFC5A577D33EC14564CEC2BACBF",
+            valid: true,
+        },
+        Vector {
+            index: 17,
+            secret_key: "0340034003400340034003400340034003400340034003400340034003400340",
+            public_key: "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+            aux_rand: "0000000000000000000000000000000000000000000000000000000000000000",
+            message: "0102030405060708090A0B0C0D0E0F1011",
+            signature: "5130F39A4059B43BC7CAC09A19ECE52B5D8699D1A71E3C52DA9AFDB6B50AC370C4A482B77BF960F8681540E25B6771ECE1E5A37FD80E5A51897C5566A97EA5A5",
+            valid: true,
+        },
+        Vector {
+            index: 18,
+            secret_key: "0340034003400340034003400340034003400340034003400340034003400340",
+            public_key: "778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117",
+            aux_rand: "0000000000000000000000000000000000000000000000000000000000000000",
+            message: "99999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999",
+            signature: "403B12B0D8555A344175EA7EC746566303321E5DBFA8BE6F091635163ECA79A8585ED3E3170807E7C03B720FC54C7B23897FCBA0E9D0B4A06894CFD249F22367",
+            valid: true,
+        },
+    ];
+
+    #[test]
+    fn test_bip340_vectors() {
+        for vector in BIP340_VECTORS {
+            let public_key = hex::decode(vector.public_key).unwrap();
+            let message = hex::decode(vector.message).unwrap();
+            let signature = hex::decode(vector.signature).unwrap();
+            if !vector.secret_key.is_empty() {
+                let secret_key = hex::decode(vector.secret_key).unwrap();
+                let signer = Secp256k1::from(secret_key.try_into().unwrap()).unwrap();
+                assert_eq!(signer.me(), public_key, "index {}", vector.index);
+                let aux_rand = hex::decode(vector.aux_rand).unwrap();
+                assert_eq!(
+                    signer.sign_raw(&message, &aux_rand.try_into().unwrap()),
+                    signature,
+                    "index {}",
+                    vector.index
+                );
+            }
+            assert_eq!(
+                verify_raw(&public_key, &message, &signature),
+                vector.valid,
+                "index {}",
+                vector.index
+            );
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let mut signer = insecure_signer(0);
+        let public = signer.me();
+        assert!(Secp256k1::validate(&public));
+        let signature = signer.sign(b"namespace", b"message");
+        assert!(Secp256k1::verify(
+            b"namespace",
+            b"message",
+            &public,
+            &signature
+        ));
+
+        // The signed digest is the tagged hash of the message
+        let digest = tagged_hash(b"namespace", b"message");
+        assert!(verify_raw(&public, &digest, &signature));
+
+        // Wrong namespace, message, or signer
+        assert!(!Secp256k1::verify(
+            b"other", b"message", &public, &signature
+        ));
+        assert!(!Secp256k1::verify(
+            b"namespace",
+            b"other",
+            &public,
+            &signature
+        ));
+        assert!(!Secp256k1::verify(
+            b"namespace",
+            b"message",
+            &insecure_signer(1).me(),
+            &signature
+        ));
+
+        // Ambiguous split of namespace and message
+        let signature = signer.sign(b"ab", b"c");
+        assert!(!Secp256k1::verify(b"a", b"bc", &public, &signature));
+    }
+
+    #[test]
+    fn test_insecure_signer_deterministic() {
+        let mut a = insecure_signer(0);
+        let mut b = insecure_signer(0);
+        for message in [&b"first"[..], b"second"] {
+            let signature = a.sign(b"namespace", message);
+            assert_eq!(signature, b.sign(b"namespace", message));
+            assert!(Secp256k1::verify(
+                b"namespace",
+                message,
+                &a.me(),
+                &signature
+            ));
+        }
+
+        // Auxiliary randomness is not reused across signatures
+        assert_ne!(
+            a.sign(b"namespace", b"third"),
+            a.sign(b"namespace", b"third")
+        );
+    }
+
+    #[test]
+    fn test_tagged_hash() {
+        // Tagged hash of the BIP-340 challenge tag (with an empty message)
+        let tag = Sha256::digest(b"BIP0340/challenge");
+        let expected = Sha256::new().chain_update(tag).chain_update(tag).finalize();
+        assert_eq!(tagged_hash(b"BIP0340/challenge", b""), expected.as_slice());
+    }
+
+    #[test]
+    fn test_validate() {
+        let public = insecure_signer(0).me();
+        assert!(Secp256k1::validate(&public));
+
+        // Not an x-coordinate on the curve (vector 5)
+        let invalid =
+            hex::decode("EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34")
+                .unwrap();
+        assert!(!Secp256k1::validate(&PublicKey::from(invalid)));
+
+        // Exceeds the field size (vector 14)
+        let invalid =
+            hex::decode("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30")
+                .unwrap();
+        assert!(!Secp256k1::validate(&PublicKey::from(invalid)));
+
+        // Compressed (rather than x-only) encoding
+        let mut compressed = vec![0x02];
+        compressed.extend_from_slice(&public);
+        assert!(!Secp256k1::validate(&PublicKey::from(compressed)));
+
+        // Truncated
+        assert!(!Secp256k1::validate(&public.slice(..PUBLIC_KEY_LENGTH - 1)));
+    }
+
+    #[test]
+    fn test_verify_malformed() {
+        let mut signer = insecure_signer(0);
+        let public = signer.me();
+        let signature = signer.sign(b"namespace", b"message");
+        let truncated = signature.slice(..SIGNATURE_LENGTH - 1);
+        assert!(!Secp256k1::verify(
+            b"namespace",
+            b"message",
+            &public,
+            &truncated
+        ));
+        let mut extended = signature.to_vec();
+        extended.push(0);
+        assert!(!Secp256k1::verify(
+            b"namespace",
+            b"message",
+            &public,
+            &Signature::from(extended)
+        ));
+    }
+}
@@ -0,0 +1,299 @@
+//! Secp256r1 (P-256) implementation of the Scheme trait.
+//!
+//! This implementation uses the `p256` crate to produce ECDSA signatures over SHA-256. Signatures
+//! are generated deterministically (as specified in RFC 6979) and are always normalized to "low-S"
+//! form. Because any valid ECDSA signature `(r, s)` can be converted into another valid signature
+//! `(r, -s)` for the same message, signatures with a "high-S" value are rejected during
+//! verification (which is necessary for stability in a consensus context).
+//!
+//! Public keys are encoded in compressed SEC1 form (33 bytes) and signatures are encoded as
+//! `r || s` (64 bytes).
+//!
+//! # Example
+//! ```rust
+//! use commonware_cryptography::{secp256r1::Secp256r1, Scheme};
+//!
+//! // Generate a new private key
+//! let mut signer = Secp256r1::new();
+//!
+//! // Create a message to sign
+//! let namespace = b"demo";
+//! let msg = b"hello, world!";
+//!
+//! // Sign the message
+//! let signature = signer.sign(namespace, msg);
+//!
+//! // Verify the signature
+//! assert!(Secp256r1::verify(namespace, msg, &signer.me(), &signature));
+//! ```
+
+use crate::{keystore::Exportable, utils::payload, BatchScheme, PublicKey, Scheme, Signature};
+use p256::ecdsa::{
+    self,
+    signature::{Signer, Verifier},
+    SigningKey, VerifyingKey,
+};
+use rand::{rngs::OsRng, CryptoRng, RngCore, SeedableRng};
+use zeroize::Zeroizing;
+
+const SECRET_KEY_LENGTH: usize = 32;
+const PUBLIC_KEY_LENGTH: usize = 33;
+const SIGNATURE_LENGTH: usize = 64;
+
+/// Secp256r1 Signer.
+#[derive(Clone)]
+pub struct Secp256r1 {
+    signer: SigningKey,
+    verifier: PublicKey,
+}
+
+impl Secp256r1 {
+    /// Creates a new Secp256r1 signer using randomness from the operating system.
+    pub fn new() -> Self {
+        Self::new_from(&mut OsRng)
+    }
+
+    /// Creates a new Secp256r1 signer using the provided randomness.
+    pub fn new_from<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Self::from_signing_key(SigningKey::random(rng))
+    }
+
+    /// Creates a new Secp256r1 signer from a secret key.
+    ///
+    /// Returns `None` if the secret key is zero or not less than the order of the curve.
+    pub fn from(signer: [u8; SECRET_KEY_LENGTH]) -> Option<Self> {
+        let signer = SigningKey::from_bytes(&signer.into()).ok()?;
+        Some(Self::from_signing_key(signer))
+    }
+
+    fn from_signing_key(signer: SigningKey) -> Self {
+        let verifier = signer
+            .verifying_key()
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec()
+            .into();
+        Self { signer, verifier }
+    }
+
+    /// Parses a compressed SEC1 public key.
+    fn parse_public_key(public_key: &PublicKey) -> Option<VerifyingKey> {
+        if public_key.len() != PUBLIC_KEY_LENGTH {
+            return None;
+        }
+        VerifyingKey::from_sec1_bytes(public_key.as_ref()).ok()
+    }
+}
+
+impl Default for Secp256r1 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scheme for Secp256r1 {
+    fn me(&self) -> PublicKey {
+        self.verifier.clone()
+    }
+
+    fn sign(&mut self, namespace: &[u8], message: &[u8]) -> Signature {
+        let payload = payload(namespace, message);
+        let signature: ecdsa::Signature = self.signer.sign(&payload);
+        let signature = signature.normalize_s().unwrap_or(signature);
+        signature.to_bytes().to_vec().into()
+    }
+
+    fn validate(public_key: &PublicKey) -> bool {
+        Self::parse_public_key(public_key).is_some()
+    }
+
+    fn verify(
+        namespace: &[u8],
+        message: &[u8],
+        public_key: &PublicKey,
+        signature: &Signature,
+    ) -> bool {
+        let public_key = match Self::parse_public_key(public_key) {
+            Some(key) => key,
+            None => return false,
+        };
+        if signature.len() != SIGNATURE_LENGTH {
+            return false;
+        }
+        let signature = match ecdsa::Signature::from_slice(signature.as_ref()) {
+            Ok(sig) => sig,
+            Err(_) => return false,
+        };
+
+        // Reject malleable (high-S) signatures
+        if signature.normalize_s().is_some() {
+            return false;
+        }
+        let payload = payload(namespace, message);
+        public_key.verify(&payload, &signature).is_ok()
+    }
+}
+
+impl BatchScheme for Secp256r1 {}
+
+impl Exportable for Secp256r1 {
+    const NAME: &'static str = "secp256r1";
+
+    fn private_key(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.signer.to_bytes().to_vec())
+    }
+
+    fn from_private_key(private_key: &[u8]) -> Option<Self> {
+        Self::from(private_key.try_into().ok()?)
+    }
+
+    fn new_from<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        Self::new_from(rng)
+    }
+}
+
+/// Creates a new Secp256r1 signer with a secret key derived from the provided
+/// seed.
+///
+/// # Warning
+///
+/// This function is intended for testing and demonstration purposes only.
+/// It should never be used in production.
+pub fn insecure_signer(seed: u16) -> Secp256r1 {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed as u64);
+    Secp256r1::from_signing_key(SigningKey::random(&mut rng))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::NonZeroScalar;
+
+    /// Test vector from RFC 6979 (A.2.5, with SHA-256 and message "sample").
+    #[test]
+    fn test_rfc6979_vector() {
+        let secret =
+            hex::decode("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721")
+                .unwrap();
+        let signer = SigningKey::from_bytes(secret.as_slice().into()).unwrap();
+        let signature: ecdsa::Signature = signer.sign(b"sample");
+        assert_eq!(
+            signature.to_bytes().to_vec(),
+            hex::decode(concat!(
+                "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716",
+                "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8"
+            ))
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let mut signer = insecure_signer(0);
+        let public = signer.me();
+        assert_eq!(public.len(), PUBLIC_KEY_LENGTH);
+        assert!(Secp256r1::validate(&public));
+        let signature = signer.sign(b"namespace", b"message");
+        assert_eq!(signature.len(), SIGNATURE_LENGTH);
+        assert!(Secp256r1::verify(
+            b"namespace",
+            b"message",
+            &public,
+            &signature
+        ));
+
+        // Signing is deterministic
+        assert_eq!(signer.sign(b"namespace", b"message"), signature);
+
+        // Wrong namespace, message, or signer
+        assert!(!Secp256r1::verify(
+            b"other", b"message", &public, &signature
+        ));
+        assert!(!Secp256r1::verify(
+            b"namespace",
+            b"other",
+            &public,
+            &signature
+        ));
+        let other = insecure_signer(1).me();
+        assert!(!Secp256r1::verify(
+            b"namespace",
+            b"message",
+            &other,
+            &signature
+        ));
+    }
+
+    #[test]
+    fn test_reject_high_s() {
+        for i in 0..16 {
+            let mut signer = insecure_signer(i);
+            let public = signer.me();
+            let message = i.to_be_bytes();
+            let signature = signer.sign(b"namespace", &message);
+            let parsed = ecdsa::Signature::from_slice(&signature).unwrap();
+            assert!(parsed.normalize_s().is_none());
+            assert!(Secp256r1::verify(
+                b"namespace",
+                &message,
+                &public,
+                &signature
+            ));
+
+            // Negate s (which is still a valid ECDSA signature)
+            let (r, s) = parsed.split_scalars();
+            let s = NonZeroScalar::new(-*s).unwrap();
+            let malleated = ecdsa::Signature::from_scalars(r, s).unwrap();
+            assert!(public_verifies(&public, &message, &malleated));
+            let malleated = Signature::from(malleated.to_bytes().to_vec());
+            assert!(!Secp256r1::verify(
+                b"namespace",
+                &message,
+                &public,
+                &malleated
+            ));
+        }
+    }
+
+    fn public_verifies(public: &PublicKey, message: &[u8], signature: &ecdsa::Signature) -> bool {
+        let key = Secp256r1::parse_public_key(public).unwrap();
+        key.verify(&payload(b"namespace", message), signature)
+            .is_ok()
+    }
+
+    #[test]
+    fn test_validate() {
+        let signer = insecure_signer(0);
+        let public = signer.me();
+
+        // Uncompressed encoding
+        let uncompressed = PublicKey::from(
+            signer
+                .signer
+                .verifying_key()
+                .to_encoded_point(false)
+                .as_bytes()
+                .to_vec(),
+        );
+        assert!(!Secp256r1::validate(&uncompressed));
+
+        // Invalid prefix
+        let mut invalid = public.to_vec();
+        invalid[0] = 0x04;
+        assert!(!Secp256r1::validate(&PublicKey::from(invalid)));
+
+        // Truncated
+        assert!(!Secp256r1::validate(&public.slice(..PUBLIC_KEY_LENGTH - 1)));
+    }
+
+    #[test]
+    fn test_from_invalid_secret() {
+        assert!(Secp256r1::from([0u8; SECRET_KEY_LENGTH]).is_none());
+        assert!(Secp256r1::from([0xff; SECRET_KEY_LENGTH]).is_none());
+        let order = hex::decode("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551")
+            .unwrap();
+        assert!(Secp256r1::from(order.try_into().unwrap()).is_none());
+        let max = -p256::Scalar::ONE;
+        assert!(Secp256r1::from(max.to_bytes().into()).is_some());
+    }
+}
@@ -1,9 +1,95 @@
 //! Utility functions for cryptographic primitives.
+//!
+//! # Payload Encoding
+//!
+//! Before a message is signed, it is combined with a namespace (to prevent a signature
+//! meant for one context from being replayed in another). The namespace is length-prefixed so
+//! that no two `(namespace, message)` pairs share the same payload:
+//!
+//! ```text
+//! version (u8) || namespace length (u32, big-endian) || namespace || message
+//! ```
+//!
+//! The current version is [PAYLOAD_VERSION].
+//!
+//! # Migration
+//!
+//! Signatures were previously produced over `namespace || message` (see [legacy_payload]).
+//! Because this encoding is ambiguous (a signature over namespace `ab` and message `c` is also
+//! valid for namespace `a` and message `bc`), it is no longer used by `Scheme::sign` or
+//! `Scheme::verify`. Applications that must continue to accept signatures produced with the legacy
+//! encoding (i.e. those persisted before upgrading) can verify them with `verify_legacy` (on
+//! `Ed25519` and `Bls12381`) until they are re-signed.
 
-/// Concatenates the namespace and message into a single payload for signing.
+/// Version of the payload encoding.
+pub const PAYLOAD_VERSION: u8 = 1;
+
+/// Encodes the namespace and message into a single payload for signing.
+///
+/// # Panics
+///
+/// Panics if the namespace is longer than `u32::MAX` bytes.
 pub fn payload(namespace: &[u8], message: &[u8]) -> Vec<u8> {
+    let len = u32::try_from(namespace.len()).expect("namespace too long");
+    let mut payload = Vec::with_capacity(1 + 4 + namespace.len() + message.len());
+    payload.push(PAYLOAD_VERSION);
+    payload.extend_from_slice(&len.to_be_bytes());
+    payload.extend_from_slice(namespace);
+    payload.extend_from_slice(message);
+    payload
+}
+
+/// Concatenates the namespace and message into a single payload for signing.
+///
+/// # Warning
+///
+/// This encoding is ambiguous and is only provided to verify signatures produced before
+/// [PAYLOAD_VERSION] was introduced. It should never be used to produce new signatures.
+pub fn legacy_payload(namespace: &[u8], message: &[u8]) -> Vec<u8> {
     let mut payload = Vec::with_capacity(namespace.len() + message.len());
     payload.extend_from_slice(namespace);
     payload.extend_from_slice(message);
     payload
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_vectors() {
+        assert_eq!(payload(b"", b""), [0x01, 0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(
+            payload(b"ab", b"c"),
+            [0x01, 0x00, 0x00, 0x00, 0x02, b'a', b'b', b'c']
+        );
+        assert_eq!(
+            payload(b"a", b"bc"),
+            [0x01, 0x00, 0x00, 0x00, 0x01, b'a', b'b', b'c']
+        );
+        assert_eq!(legacy_payload(b"ab", b"c"), *b"abc");
+        assert_eq!(legacy_payload(b"a", b"bc"), *b"abc");
+    }
+
+    #[test]
+    fn test_payload_unambiguous() {
+        let splits = [
+            (&b""[..], &b"abc"[..]),
+            (b"a", b"bc"),
+            (b"ab", b"c"),
+            (b"abc", b""),
+        ];
+        for (i, (namespace_a, message_a)) in splits.iter().enumerate() {
+            for (namespace_b, message_b) in splits.iter().skip(i + 1) {
+                assert_eq!(
+                    legacy_payload(namespace_a, message_a),
+                    legacy_payload(namespace_b, message_b)
+                );
+                assert_ne!(
+                    payload(namespace_a, message_a),
+                    payload(namespace_b, message_b)
+                );
+            }
+        }
+    }
+}
@@ -6,7 +6,7 @@ use commonware_cryptography::{
     bls12381::{
         dkg::arbiter::P0,
         primitives::{
-            group::{self, Element},
+            group::{self, Element, MinPk},
             poly,
         },
     },
@@ -68,7 +68,7 @@ impl Arbiter {
         let mut group = None;
         if let Some(previous) = &previous {
             group = Some(previous.serialize());
-            let public = poly::public(previous).serialize();
+            let public = poly::public::<MinPk>(previous).serialize();
             info!(round, public = hex::encode(public), "starting reshare");
         } else {
             info!(round, "starting key generation");
@@ -87,7 +87,7 @@ impl Arbiter {
             .await;
 
         // Collect commitments
-        let mut p0 = P0::new(
+        let mut p0 = P0::<MinPk>::new(
             self.t,
             previous,
             self.players.clone(),
@@ -122,7 +122,7 @@ impl Arbiter {
                                 continue;
                             }
                         };
-                        let commitment = match poly::Public::deserialize(&msg.commitment, self.t) {
+                        let commitment = match poly::Public::<MinPk>::deserialize(&msg.commitment, self.t) {
                             Some(commitment) => commitment,
                             None => {
                                 p0.disqualify(sender);
@@ -395,9 +395,10 @@ impl Arbiter {
                             p2.disqualify(sender);
                             continue;
                         }
+                        let index = share.index;
                         match p2.reveal(sender.clone(), share) {
                             Ok(()) => {
-                                signatures.insert((dealer, share.index), msg.signature);
+                                signatures.insert((dealer, index), msg.signature);
                             }
                             Err(_) => {
                                 p2.disqualify(sender);
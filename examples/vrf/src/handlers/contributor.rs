@@ -9,7 +9,7 @@ use commonware_cryptography::{
             contributor::{Output, P0, P1},
         },
         primitives::{
-            group::{self, Private},
+            group::{self, MinPk, Private},
             poly,
         },
     },
@@ -104,7 +104,7 @@ impl<C: Scheme> Contributor<C> {
                         }
                     };
                     if let Some(group) = msg.group {
-                        let result = poly::Public::deserialize(&group, self.t);
+                        let result = poly::Public::<MinPk>::deserialize(&group, self.t);
                         if result.is_none() {
                             warn!("received invalid group polynomial");
                             continue;
@@ -131,15 +131,13 @@ impl<C: Scheme> Contributor<C> {
                 );
                 should_deal = false;
             }
-            (Some(previous), Some(public)) => {
-                if previous.public != *public {
-                    warn!(
-                        expected = public_hex(&previous.public),
-                        found = public_hex(public),
-                        "group polynomial does not match expected"
-                    );
-                    should_deal = false;
-                }
+            (Some(previous), Some(public)) if previous.public != *public => {
+                warn!(
+                    expected = public_hex(&previous.public),
+                    found = public_hex(public),
+                    "group polynomial does not match expected"
+                );
+                should_deal = false;
             }
             (None, Some(public)) => {
                 warn!(
@@ -161,7 +159,7 @@ impl<C: Scheme> Contributor<C> {
         let (mut p1, shares) = if should_deal {
             let previous = public
                 .as_ref()
-                .map(|public| (public.clone(), previous.unwrap().share));
+                .map(|public| (public.clone(), previous.unwrap().share().unwrap().clone()));
             let p0 = P0::new(
                 me.clone(),
                 self.t,
@@ -251,14 +249,16 @@ impl<C: Scheme> Contributor<C> {
                                 return (round, None);
                             }
                         };
-                        let commitment =
-                            match poly::Public::deserialize(&commitment.commitment, self.t) {
-                                Some(commitment) => commitment,
-                                None => {
-                                    warn!("received invalid commitment from player");
-                                    return (round, None);
-                                }
-                            };
+                        let commitment = match poly::Public::<MinPk>::deserialize(
+                            &commitment.commitment,
+                            self.t,
+                        ) {
+                            Some(commitment) => commitment,
+                            None => {
+                                warn!("received invalid commitment from player");
+                                return (round, None);
+                            }
+                        };
 
                         // Verify commitment is on public
                         if let Err(e) = p1.commitment(dealer.clone(), commitment) {
@@ -300,7 +300,7 @@ impl<C: Scheme> Contributor<C> {
         if should_deal {
             let shares = shares.clone().unwrap();
             for (idx, player) in self.contributors.iter().enumerate() {
-                let share = shares[idx];
+                let share = shares[idx].clone();
                 if idx == me_idx as usize {
                     if let Err(e) = p2.share(me.clone(), share) {
                         warn!(round, error = ?e, "failed to add our share");
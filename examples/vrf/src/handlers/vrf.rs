@@ -3,15 +3,16 @@ use commonware_cryptography::{
     bls12381::{
         dkg::contributor::Output,
         primitives::{
-            group::{self, Element},
+            group::{self, Element, MinPk},
             ops,
-            poly::Eval,
+            poly::{self, Eval, PublicTable},
         },
     },
     PublicKey,
 };
 use commonware_p2p::{Receiver, Sender};
 use prost::Message;
+use rand::rngs::OsRng;
 use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 use tokio::{select, sync::mpsc};
@@ -52,13 +53,14 @@ impl Vrf {
     async fn run_round(
         &self,
         output: &Output,
+        table: &PublicTable,
         round: u64,
         sender: &Sender,
         receiver: &mut Receiver,
     ) -> Option<group::Signature> {
         // Construct payload
         let payload = round.to_be_bytes();
-        let signature = ops::partial_sign(&output.share, &payload);
+        let signature = ops::partial_sign::<MinPk>(output.share().unwrap(), &payload);
 
         // Construct partial signature
         let mut partials = vec![signature.clone()];
@@ -81,6 +83,7 @@ impl Vrf {
         let start = tokio::time::Instant::now();
         let t_signature = start + self.timeout;
         let mut received = HashSet::new();
+        let mut received_partials = Vec::new();
         loop {
             select! {
                 biased;
@@ -123,14 +126,36 @@ impl Vrf {
                                 continue;
                             }
                         };
-                        match ops::partial_verify(&output.public, &payload, &signature) {
-                            Ok(_) => {
-                                partials.push(signature);
-                                debug!(round, dealer, "received partial signature");
-                            }
-                            Err(_) => {
-                                warn!(round, dealer, "received invalid partial signature");
-                            }
+                        if signature.index != *dealer {
+                            warn!(
+                                round,
+                                dealer,
+                                signature.index, "received signature with wrong index"
+                            );
+                            continue;
+                        }
+                        received_partials.push(signature);
+                        debug!(round, dealer, "received partial signature");
+                    }
+                }
+            }
+        }
+
+        // Verify all received partial signatures at once (only checking each
+        // partial signature if the batch is invalid)
+        match ops::partial_batch_verify::<MinPk, _>(&mut OsRng, table, &payload, &received_partials)
+        {
+            Ok(_) => partials.extend(received_partials),
+            Err(_) => {
+                for signature in received_partials {
+                    match ops::partial_verify_with_table::<MinPk>(table, &payload, &signature) {
+                        Ok(_) => partials.push(signature),
+                        Err(_) => {
+                            warn!(
+                                round,
+                                dealer = signature.index,
+                                "received invalid partial signature"
+                            );
                         }
                     }
                 }
@@ -138,7 +163,7 @@ impl Vrf {
         }
 
         // Aggregate partial signatures
-        match ops::aggregate(self.threshold, partials) {
+        match ops::aggregate::<MinPk>(self.threshold, partials) {
             Ok(signature) => Some(signature),
             Err(_) => {
                 warn!(round, "failed to aggregate partial signatures");
@@ -148,6 +173,8 @@ impl Vrf {
     }
 
     pub async fn run(mut self, sender: Sender, mut receiver: Receiver) {
+        // Public keys of each contributor (recomputed only when the group polynomial changes)
+        let mut cached: Option<(poly::Public, PublicTable)> = None;
         loop {
             let (round, output) = match self.requests.recv().await {
                 Some(request) => request,
@@ -156,7 +183,17 @@ impl Vrf {
                 }
             };
 
-            match self.run_round(&output, round, &sender, &mut receiver).await {
+            let table = match &cached {
+                Some((public, table)) if *public == output.public => table,
+                _ => {
+                    let table = PublicTable::new(&output.public, self.contributors.len() as u32);
+                    &cached.insert((output.public.clone(), table)).1
+                }
+            };
+            match self
+                .run_round(&output, table, round, &sender, &mut receiver)
+                .await
+            {
                 Some(signature) => {
                     let signature = signature.serialize();
                     info!(
@@ -6,39 +6,40 @@ use crate::{
     actors::{spawner, tracker},
     connection::{self, Stream},
     metrics,
+    runtime::{DirectRateLimiter, Runtime},
+    transport::Transport,
 };
 use commonware_cryptography::{PublicKey, Scheme};
-use governor::{DefaultDirectRateLimiter, Jitter, Quota, RateLimiter};
+use governor::{Quota, RateLimiter};
 use prometheus_client::metrics::counter::Counter;
 use prometheus_client::metrics::family::Family;
 use prometheus_client::registry::Registry;
+use rand::Rng;
+use std::sync::{Arc, Mutex};
 use std::{net::SocketAddr, time::Duration};
-use std::{
-    ops::Add,
-    sync::{Arc, Mutex},
-};
-use tokio::net::TcpStream;
-use tokio::time::{self, Instant};
 use tracing::debug;
 
-pub struct Config<C: Scheme> {
+pub struct Config<C: Scheme, T: Transport> {
     pub registry: Arc<Mutex<Registry>>,
+    pub transport: T,
     pub connection: connection::Config<C>,
     pub dial_frequency: Duration,
     pub dial_rate: Quota,
 }
 
-pub struct Actor<C: Scheme> {
+pub struct Actor<R: Runtime, C: Scheme, T: Transport> {
+    runtime: R,
+    transport: T,
     connection: connection::Config<C>,
     dial_frequency: Duration,
 
-    dial_limiter: DefaultDirectRateLimiter,
+    dial_limiter: DirectRateLimiter<R>,
 
     dial_attempts: Family<metrics::Peer, Counter>,
 }
 
-impl<C: Scheme> Actor<C> {
-    pub fn new(cfg: Config<C>) -> Self {
+impl<R: Runtime, C: Scheme, T: Transport> Actor<R, C, T> {
+    pub fn new(runtime: R, cfg: Config<C, T>) -> Self {
         let dial_attempts = Family::<metrics::Peer, Counter>::default();
         {
             let mut registry = cfg.registry.lock().unwrap();
@@ -49,14 +50,20 @@ impl<C: Scheme> Actor<C> {
             );
         }
         Self {
+            dial_limiter: RateLimiter::direct_with_clock(cfg.dial_rate, &runtime),
+            runtime,
+            transport: cfg.transport,
             connection: cfg.connection,
             dial_frequency: cfg.dial_frequency,
-            dial_limiter: RateLimiter::direct(cfg.dial_rate),
             dial_attempts,
         }
     }
 
-    async fn dial_peers(&self, tracker: &tracker::Mailbox, supervisor: &spawner::Mailbox<C>) {
+    async fn dial_peers(
+        &self,
+        tracker: &tracker::Mailbox,
+        supervisor: &spawner::Mailbox<R, C, T::Stream>,
+    ) {
         for (peer, address, reservation) in tracker.dialable().await {
             // Check if we have hit rate limit for dialing and if so, skip (we don't
             // want to block the loop)
@@ -75,7 +82,9 @@ impl<C: Scheme> Actor<C> {
             self.dial_attempts
                 .get_or_create(&metrics::Peer::new(&peer))
                 .inc();
-            tokio::spawn(Self::dial(
+            self.runtime.spawn(Self::dial(
+                self.runtime.clone(),
+                self.transport.clone(),
                 self.connection.clone(),
                 peer.clone(),
                 address,
@@ -86,14 +95,16 @@ impl<C: Scheme> Actor<C> {
     }
 
     async fn dial(
+        runtime: R,
+        transport: T,
         config: connection::Config<C>,
         peer: PublicKey,
         address: SocketAddr,
         reservation: tracker::Reservation,
-        supervisor: spawner::Mailbox<C>,
+        supervisor: spawner::Mailbox<R, C, T::Stream>,
     ) {
         // Attempt to dial peer
-        let connection = match TcpStream::connect(address).await {
+        let connection = match transport.dial(address).await {
             Ok(stream) => stream,
             Err(e) => {
                 debug!(peer=hex::encode(peer), error = ?e, "failed to dial peer");
@@ -106,15 +117,8 @@ impl<C: Scheme> Actor<C> {
             "dialed peer"
         );
 
-        // Set TCP_NODELAY
-        if let Some(nodelay) = config.tcp_nodelay {
-            if let Err(e) = connection.set_nodelay(nodelay) {
-                debug!(peer = hex::encode(&peer), error = ?e, "failed to set TCP_NODELAY")
-            }
-        }
-
         // Upgrade connection
-        let stream = match Stream::upgrade_dialer(config, connection, peer.clone()).await {
+        let stream = match Stream::upgrade_dialer(runtime, config, connection, peer.clone()).await {
             Ok(stream) => stream,
             Err(e) => {
                 debug!(peer=hex::encode(&peer), error = ?e, "failed to upgrade connection");
@@ -127,17 +131,21 @@ impl<C: Scheme> Actor<C> {
         supervisor.spawn(peer, stream, reservation).await;
     }
 
-    pub async fn run(self, tracker: tracker::Mailbox, supervisor: spawner::Mailbox<C>) {
-        let mut next_update = Instant::now();
+    pub async fn run(
+        mut self,
+        tracker: tracker::Mailbox,
+        supervisor: spawner::Mailbox<R, C, T::Stream>,
+    ) {
+        let mut next_update = self.runtime.current();
         loop {
-            time::sleep_until(next_update).await;
+            self.runtime.sleep_until(next_update).await;
 
             // Attempt to dial peers we know about
             self.dial_peers(&tracker, &supervisor).await;
 
             // Ensure we reset the timer with a new jitter
-            let jitter = Jitter::up_to(self.dial_frequency);
-            next_update = Instant::now().add(jitter + self.dial_frequency);
+            let jitter = self.dial_frequency.mul_f64(self.runtime.gen::<f64>());
+            next_update = self.runtime.current() + jitter + self.dial_frequency;
         }
     }
 }
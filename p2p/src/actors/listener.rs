@@ -2,51 +2,70 @@
 
 use crate::{
     actors::{spawner, tracker},
-    connection::{self, IncomingHandshake, Stream},
+    connection::{self, IncomingHandshake, Replays, Stream},
+    runtime::{self, DirectRateLimiter, Runtime},
+    transport::{Listener, Transport},
 };
 use commonware_cryptography::Scheme;
-use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
-use std::net::{Ipv4Addr, SocketAddr};
-use tokio::net::{TcpListener, TcpStream};
+use governor::{Quota, RateLimiter};
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    sync::{Arc, Mutex},
+};
 use tracing::debug;
 
 /// Configuration for the listener actor.
-pub struct Config<C: Scheme> {
+pub struct Config<C: Scheme, T: Transport> {
     pub port: u16,
+    pub transport: T,
     pub connection: connection::Config<C>,
     pub allowed_incoming_connectioned_rate: Quota,
 }
 
-pub struct Actor<C: Scheme> {
+pub struct Actor<R: Runtime, C: Scheme, T: Transport> {
+    runtime: R,
     port: u16,
+    transport: T,
     connection: connection::Config<C>,
+    replays: Arc<Mutex<Replays>>,
 
-    rate_limiter: DefaultDirectRateLimiter,
+    rate_limiter: DirectRateLimiter<R>,
 }
 
-impl<C: Scheme> Actor<C> {
-    pub fn new(cfg: Config<C>) -> Self {
+impl<R: Runtime, C: Scheme, T: Transport> Actor<R, C, T> {
+    pub fn new(runtime: R, cfg: Config<C, T>) -> Self {
         Self {
+            rate_limiter: RateLimiter::direct_with_clock(
+                cfg.allowed_incoming_connectioned_rate,
+                &runtime,
+            ),
+            runtime,
             port: cfg.port,
+            transport: cfg.transport,
             connection: cfg.connection,
-            rate_limiter: RateLimiter::direct(cfg.allowed_incoming_connectioned_rate),
+            replays: Arc::new(Mutex::new(Replays::default())),
         }
     }
 
     async fn handshake(
+        runtime: R,
         connection: connection::Config<C>,
-        stream: TcpStream,
+        replays: Arc<Mutex<Replays>>,
+        stream: T::Stream,
         tracker: tracker::Mailbox,
-        supervisor: spawner::Mailbox<C>,
+        supervisor: spawner::Mailbox<R, C, T::Stream>,
     ) {
         // Wait for the peer to send us their public key
         //
         // PartialHandshake limits how long we will wait for the peer to send us their public key
         // to ensure an adversary can't force us to hold many pending connections open.
         let handshake = match IncomingHandshake::verify(
+            &runtime,
             &connection.crypto,
             connection.max_frame_length,
             connection.handshake_timeout,
+            connection.synchrony_bound,
+            &replays,
             stream,
         )
         .await
@@ -74,7 +93,7 @@ impl<C: Scheme> Actor<C> {
         };
 
         // Perform handshake
-        let stream = match Stream::upgrade_listener(connection, handshake).await {
+        let stream = match Stream::upgrade_listener(runtime, connection, handshake).await {
             Ok(connection) => connection,
             Err(e) => {
                 debug!(error = ?e, peer=hex::encode(&peer), "failed to upgrade connection");
@@ -87,16 +106,20 @@ impl<C: Scheme> Actor<C> {
         supervisor.spawn(peer, stream, reservation).await;
     }
 
-    pub async fn run(self, tracker: tracker::Mailbox, supervisor: spawner::Mailbox<C>) {
+    pub async fn run(
+        self,
+        tracker: tracker::Mailbox,
+        supervisor: spawner::Mailbox<R, C, T::Stream>,
+    ) {
         // Configure the listener on the specified port
         let address = SocketAddr::new(std::net::IpAddr::V4(Ipv4Addr::UNSPECIFIED), self.port);
-        let listener = TcpListener::bind(address).await.unwrap();
+        let mut listener = self.transport.bind(address).await.unwrap();
         debug!(port = self.port, "listening for incoming connections");
 
         // Loop over incoming connections as fast as our rate limiter allows
         loop {
             // Ensure we don't attempt to perform too many handshakes at once
-            self.rate_limiter.until_ready().await;
+            runtime::until_ready(&self.runtime, &self.rate_limiter).await;
 
             // Accept a new connection
             let (stream, address) = match listener.accept().await {
@@ -108,16 +131,11 @@ impl<C: Scheme> Actor<C> {
             };
             debug!(ip = ?address.ip(), port = ?address.port(), "accepted incoming connection");
 
-            // Set TCP_NODELAY
-            if let Some(nodelay) = self.connection.tcp_nodelay {
-                if let Err(e) = stream.set_nodelay(nodelay) {
-                    debug!(ip = ?address.ip(), port = ?address.port(), error = ?e, "failed to set TCP_NODELAY")
-                }
-            }
-
             // Spawn a new handshaker to upgrade connection
-            tokio::spawn(Self::handshake(
+            self.runtime.spawn(Self::handshake(
+                self.runtime.clone(),
                 self.connection.clone(),
+                self.replays.clone(),
                 stream,
                 tracker.clone(),
                 supervisor.clone(),
@@ -1,18 +1,28 @@
 use super::{ingress::Data, Config, Error, Mailbox, Message, Relay};
 use crate::{
     actors::tracker,
-    channels::Channels,
-    connection::{Sender, Stream},
-    metrics, wire,
+    channels::{self, Channels},
+    connection::{Receiver, Sender, Stream},
+    metrics,
+    runtime::{self, DirectRateLimiter, Handle, Runtime},
+    transport, wire,
 };
 use bytes::BytesMut;
 use commonware_cryptography::{PublicKey, Scheme};
-use governor::{DefaultDirectRateLimiter, Quota};
+use futures::{future, stream::FuturesUnordered, StreamExt};
+use governor::{Quota, RateLimiter};
 use prometheus_client::metrics::{counter::Counter, family::Family};
-use std::{cmp::min, collections::HashMap, sync::Arc, time::Duration};
-use tokio::{sync::mpsc, task::JoinHandle};
+use std::{
+    cmp::min,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::mpsc;
 
-pub struct Actor {
+pub struct Actor<R: Runtime> {
+    runtime: R,
+    mailbox_size: usize,
     gossip_bit_vec_frequency: Duration,
     allowed_bit_vec_rate: Quota,
     allowed_peers_rate: Quota,
@@ -29,14 +39,16 @@ pub struct Actor {
     _reservation: tracker::Reservation,
 }
 
-impl Actor {
-    pub fn new(cfg: Config, reservation: tracker::Reservation) -> (Self, Relay) {
+impl<R: Runtime> Actor<R> {
+    pub fn new(runtime: R, cfg: Config, reservation: tracker::Reservation) -> (Self, Relay) {
         let (control_sender, control_receiver) = mpsc::channel(cfg.mailbox_size);
         let (high_sender, high_receiver) = mpsc::channel(cfg.mailbox_size);
         let (low_sender, low_receiver) = mpsc::channel(cfg.mailbox_size);
 
         (
             Self {
+                runtime,
+                mailbox_size: cfg.mailbox_size,
                 mailbox: Mailbox::new(control_sender),
                 gossip_bit_vec_frequency: cfg.gossip_bit_vec_frequency,
                 allowed_bit_vec_rate: cfg.allowed_bit_vec_rate,
@@ -52,10 +64,10 @@ impl Actor {
         )
     }
 
-    async fn send_content(
+    async fn send_content<S: transport::Stream>(
         max_size: usize,
         max_content_size: usize,
-        sender: &mut Sender,
+        sender: &mut Sender<R, S>,
         peer: &PublicKey,
         data: Data,
         sent_messages: &Family<metrics::Message, Counter>,
@@ -68,7 +80,7 @@ impl Actor {
 
         // Compute required parts
         let mut total_parts = message_len / max_content_size;
-        if message_len % max_content_size != 0 {
+        if !message_len.is_multiple_of(max_content_size) {
             total_parts += 1;
         }
         if total_parts > u32::MAX as usize {
@@ -102,204 +114,348 @@ impl Actor {
         Ok(())
     }
 
-    pub async fn run<C: Scheme>(
+    /// Handles a chunk received from the peer (reading any remaining parts of the message from
+    /// the same receiver) and delivers the message to the channel.
+    async fn receive_chunk<S: transport::Stream>(
+        runtime: &R,
+        peer: &PublicKey,
+        max_content_size: usize,
+        rate_limits: &HashMap<u32, (DirectRateLimiter<R>, usize, mpsc::Sender<channels::Message>)>,
+        receiver: &mut Receiver<R, S>,
+        chunk: wire::Chunk,
+        received_messages: &Family<metrics::Message, Counter>,
+    ) -> Result<(), Error> {
+        received_messages
+            .get_or_create(&metrics::Message::new_chunk(peer, chunk.channel))
+            .inc();
+
+        // Ensure peer is not spamming us with content messages
+        let entry = rate_limits.get(&chunk.channel);
+        if entry.is_none() {
+            // We permit unknown messages to be received in case peers
+            // are on a newer version than us
+            return Ok(());
+        }
+        let (rate_limiter, max_size, sender) = entry.unwrap();
+        runtime::until_ready(runtime, rate_limiter).await;
+
+        // Ensure messasge is not too large
+        let chunk_len = chunk.content.len();
+        if chunk_len > *max_size {
+            return Err(Error::MessageTooLarge(chunk_len));
+        }
+
+        // Gather all chunks
+        let mut message = BytesMut::from(&chunk.content[..]);
+        let total_parts = chunk.total_parts;
+        if total_parts > 1 {
+            // Ensure first part is the max size
+            if chunk_len != max_content_size {
+                return Err(Error::InvalidChunk);
+            }
+
+            // Read chunk messages until we have all parts
+            let channel = chunk.channel;
+            let mut next_part = chunk.part + 1;
+            while next_part < total_parts {
+                let chunk = match receiver
+                    .receive()
+                    .await
+                    .map_err(Error::ReceiveFailed)?
+                    .payload
+                {
+                    Some(wire::message::Payload::Chunk(chunk)) => chunk,
+                    _ => return Err(Error::InvalidChunk),
+                };
+                if chunk.channel != channel {
+                    return Err(Error::InvalidChunk);
+                }
+                if chunk.total_parts != total_parts {
+                    return Err(Error::InvalidChunk);
+                }
+                if chunk.part != next_part {
+                    return Err(Error::InvalidChunk);
+                }
+                let chunk_len = chunk.content.len();
+                if chunk.part != total_parts - 1 && chunk_len != max_content_size {
+                    return Err(Error::InvalidChunk);
+                }
+                let new_len = message.len() + chunk_len;
+                if new_len > *max_size {
+                    return Err(Error::MessageTooLarge(new_len));
+                }
+                message.extend_from_slice(&chunk.content);
+                next_part += 1;
+            }
+        }
+
+        // Send message to client
+        sender.send((peer.clone(), message.freeze())).await.unwrap();
+        Ok(())
+    }
+
+    pub async fn run<C: Scheme, S: transport::Stream>(
         mut self,
         peer: PublicKey,
-        connection: Stream<C>,
+        connection: Stream<R, C, S>,
         tracker: tracker::Mailbox,
         channels: Channels,
     ) -> Error {
         // Instantiate rate limiters for each message type
         let mut rate_limits = HashMap::new();
         for (channel, (rate, max_size, sender)) in channels.collect() {
-            let rate_limiter = DefaultDirectRateLimiter::direct(rate);
+            let rate_limiter = RateLimiter::direct_with_clock(rate, &self.runtime);
             rate_limits.insert(channel, (rate_limiter, max_size, sender));
         }
         let rate_limits = Arc::new(rate_limits);
 
+        // Send (and receive) each channel over its own stream, if supported by the transport
+        let multiplexer = connection.multiplexer().map(Arc::new);
+
         // Send/Receive messages from the peer
         let (max_content_size, mut conn_sender, mut conn_receiver) = connection.split();
         let send_tracker = tracker.clone();
         let send_peer = peer.clone();
         let send_mailbox = self.mailbox.clone();
         let send_rate_limits = rate_limits.clone();
-        let mut send_handler: JoinHandle<Result<(), Error>> = tokio::spawn(async move {
-            let mut ticker = tokio::time::interval(self.gossip_bit_vec_frequency);
-            loop {
-                tokio::select! {
-                    // Ensure we send ip gossip before any user messages
-                    biased;
-
-                    _ = ticker.tick() => {
-                        // Get latest bitset from tracker (also used as ping)
-                        send_tracker.construct(send_peer.clone(), send_mailbox.clone()).await;
-                    }
-                    Some(msg) = self.control.recv() => {
-                        match msg {
-                            Message::BitVec { bit_vec } => {
-                                conn_sender.send(wire::Message{
-                                    payload: Some(wire::message::Payload::BitVec(bit_vec)),
-                                }).await.map_err(Error::SendFailed)?;
-                                self.sent_messages
-                                    .get_or_create(&metrics::Message::new_bit_vec(&send_peer))
-                                    .inc();
-                            }
-                            Message::Peers { peers: msg } => {
-                                conn_sender.send(wire::Message{
-                                    payload: Some(wire::message::Payload::Peers(msg)),
-                                }).await.map_err(Error::SendFailed)?;
-                                self.sent_messages
-                                    .get_or_create(&metrics::Message::new_peers(&send_peer))
-                                    .inc();
-                            }
-                            Message::Kill => {
-                                return Err(Error::PeerKilled(send_peer))
-                            }
+        let send_runtime = self.runtime.clone();
+        let send_multiplexer = multiplexer.clone();
+        let mut send_handler: Handle<Result<(), Error>> = self.runtime.spawn(async move {
+            // Queue content for each channel stream (sent concurrently by its own sender)
+            let mut channel_queues = HashMap::new();
+            let mut channel_senders = FuturesUnordered::new();
+            if let Some(multiplexer) = send_multiplexer {
+                for (&channel, &(_, max_size, _)) in send_rate_limits.iter() {
+                    let (queue, mut pending) = mpsc::channel::<Data>(self.mailbox_size);
+                    channel_queues.insert(channel, queue);
+                    let multiplexer = multiplexer.clone();
+                    let peer = send_peer.clone();
+                    let sent_messages = self.sent_messages.clone();
+                    channel_senders.push(async move {
+                        let mut sender =
+                            multiplexer.open(channel).await.map_err(Error::SendFailed)?;
+                        while let Some(data) = pending.recv().await {
+                            Self::send_content(
+                                max_size,
+                                max_content_size,
+                                &mut sender,
+                                &peer,
+                                data,
+                                &sent_messages,
+                            )
+                            .await?;
                         }
-                    }
-                    Some(msg) = self.high.recv() => {
-                        let entry = send_rate_limits.get(&msg.channel);
-                        if entry.is_none() {
-                            return Err(Error::InvalidChannel);
+                        Ok(())
+                    });
+                }
+            }
+            let channels = async {
+                while let Some(result) = channel_senders.next().await {
+                    result?;
+                }
+                future::pending().await
+            };
+
+            let control = async {
+                let mut next_gossip = send_runtime.current();
+                loop {
+                    let msg = tokio::select! {
+                        // Ensure we send ip gossip before any user messages
+                        biased;
+
+                        _ = send_runtime.sleep_until(next_gossip) => {
+                            next_gossip = send_runtime.current() + self.gossip_bit_vec_frequency;
+
+                            // Get latest bitset from tracker (also used as ping)
+                            send_tracker.construct(send_peer.clone(), send_mailbox.clone()).await;
+                            continue;
+                        }
+                        Some(msg) = self.control.recv() => {
+                            match msg {
+                                Message::BitVec { bit_vec } => {
+                                    conn_sender.send(wire::Message{
+                                        payload: Some(wire::message::Payload::BitVec(bit_vec)),
+                                    }).await.map_err(Error::SendFailed)?;
+                                    self.sent_messages
+                                        .get_or_create(&metrics::Message::new_bit_vec(&send_peer))
+                                        .inc();
+                                }
+                                Message::Peers { peers: msg } => {
+                                    conn_sender.send(wire::Message{
+                                        payload: Some(wire::message::Payload::Peers(msg)),
+                                    }).await.map_err(Error::SendFailed)?;
+                                    self.sent_messages
+                                        .get_or_create(&metrics::Message::new_peers(&send_peer))
+                                        .inc();
+                                }
+                                Message::Kill => {
+                                    return Err(Error::PeerKilled(send_peer))
+                                }
+                            }
+                            continue;
                         }
-                        let (_, max_size, _) = entry.unwrap();
-                        Self::send_content(*max_size, max_content_size, &mut conn_sender, &send_peer, msg, &self.sent_messages).await?;
+                        Some(msg) = self.high.recv() => msg,
+                        Some(msg) = self.low.recv() => msg,
+                        else => return Err(Error::PeerDisconnected),
+                    };
+
+                    // Send content over the channel's stream (or over the connection's stream, if
+                    // the transport does not support multiple streams)
+                    let entry = send_rate_limits.get(&msg.channel);
+                    if entry.is_none() {
+                        return Err(Error::InvalidChannel);
                     }
-                    Some(msg) = self.low.recv() => {
-                        let entry = send_rate_limits.get(&msg.channel);
-                        if entry.is_none() {
-                            return Err(Error::InvalidChannel);
+                    let (_, max_size, _) = entry.unwrap();
+                    match channel_queues.get(&msg.channel) {
+                        Some(queue) => queue.send(msg).await.map_err(|_| Error::MessageDropped)?,
+                        None => {
+                            Self::send_content(
+                                *max_size,
+                                max_content_size,
+                                &mut conn_sender,
+                                &send_peer,
+                                msg,
+                                &self.sent_messages,
+                            )
+                            .await?
                         }
-                        let (_, max_size, _) = entry.unwrap();
-                        Self::send_content(*max_size, max_content_size, &mut conn_sender, &send_peer, msg, &self.sent_messages).await?;
                     }
-                    else => return Err(Error::PeerDisconnected),
                 }
+            };
+
+            tokio::select! {
+                result = control => result,
+                result = channels => result,
             }
         });
-        let mut receive_handler: JoinHandle<Result<(), Error>> = tokio::spawn(async move {
-            let bit_vec_rate_limiter = DefaultDirectRateLimiter::direct(self.allowed_bit_vec_rate);
-            let peers_rate_limiter = DefaultDirectRateLimiter::direct(self.allowed_peers_rate);
-            loop {
-                match conn_receiver
-                    .receive()
-                    .await
-                    .map_err(Error::ReceiveFailed)?
-                    .payload
-                {
-                    Some(wire::message::Payload::BitVec(bit_vec)) => {
-                        self.received_messages
-                            .get_or_create(&metrics::Message::new_bit_vec(&peer))
-                            .inc();
+        let receive_runtime = self.runtime.clone();
+        let mut receive_handler: Handle<Result<(), Error>> = self.runtime.spawn(async move {
+            let bit_vec_rate_limiter: DirectRateLimiter<R> =
+                RateLimiter::direct_with_clock(self.allowed_bit_vec_rate, &receive_runtime);
+            let peers_rate_limiter: DirectRateLimiter<R> =
+                RateLimiter::direct_with_clock(self.allowed_peers_rate, &receive_runtime);
+            let control = async {
+                loop {
+                    match conn_receiver
+                        .receive()
+                        .await
+                        .map_err(Error::ReceiveFailed)?
+                        .payload
+                    {
+                        Some(wire::message::Payload::BitVec(bit_vec)) => {
+                            self.received_messages
+                                .get_or_create(&metrics::Message::new_bit_vec(&peer))
+                                .inc();
 
-                        // Ensure peer is not spamming us with bit vectors
-                        bit_vec_rate_limiter.until_ready().await;
+                            // Ensure peer is not spamming us with bit vectors
+                            runtime::until_ready(&receive_runtime, &bit_vec_rate_limiter).await;
 
-                        // Gather useful peers
-                        tracker.bit_vec(bit_vec, self.mailbox.clone()).await;
-                    }
-                    Some(wire::message::Payload::Peers(peers)) => {
-                        self.received_messages
-                            .get_or_create(&metrics::Message::new_peers(&peer))
-                            .inc();
+                            // Gather useful peers
+                            tracker.bit_vec(bit_vec, self.mailbox.clone()).await;
+                        }
+                        Some(wire::message::Payload::Peers(peers)) => {
+                            self.received_messages
+                                .get_or_create(&metrics::Message::new_peers(&peer))
+                                .inc();
 
-                        // Ensure peer is not spamming us with peer messages
-                        peers_rate_limiter.until_ready().await;
+                            // Ensure peer is not spamming us with peer messages
+                            runtime::until_ready(&receive_runtime, &peers_rate_limiter).await;
 
-                        // Send peers to tracker
-                        tracker.peers(peers, self.mailbox.clone()).await;
-                    }
-                    Some(wire::message::Payload::Chunk(chunk)) => {
-                        self.received_messages
-                            .get_or_create(&metrics::Message::new_chunk(&peer, chunk.channel))
-                            .inc();
-
-                        // Ensure peer is not spamming us with content messages
-                        let entry = rate_limits.get(&chunk.channel);
-                        if entry.is_none() {
-                            // We permit unknown messages to be received in case peers
-                            // are on a newer version than us
-                            continue;
+                            // Send peers to tracker
+                            tracker.peers(peers, self.mailbox.clone()).await;
+                        }
+                        Some(wire::message::Payload::Chunk(chunk)) => {
+                            Self::receive_chunk(
+                                &receive_runtime,
+                                &peer,
+                                max_content_size,
+                                &rate_limits,
+                                &mut conn_receiver,
+                                chunk,
+                                &self.received_messages,
+                            )
+                            .await?;
+                        }
+                        Some(wire::message::Payload::Handshake(_))
+                        | Some(wire::message::Payload::Confirmation(_)) => {
+                            self.received_messages
+                                .get_or_create(&metrics::Message::new_handshake(&peer))
+                                .inc();
+                            return Err(Error::UnexpectedHandshake);
                         }
-                        let (rate_limiter, max_size, sender) = entry.unwrap();
-                        rate_limiter.until_ready().await;
+                        _ => {
+                            self.received_messages
+                                .get_or_create(&metrics::Message::new_unknown(&peer))
+                                .inc();
 
-                        // Ensure messasge is not too large
-                        let chunk_len = chunk.content.len();
-                        if chunk_len > *max_size {
-                            return Err(Error::MessageTooLarge(chunk_len));
+                            // We permit unknown messages to be received in case
+                            // peers are on a newer version than us
+                            continue;
                         }
+                    }
+                }
+            };
 
-                        // Gather all chunks
-                        let mut message = BytesMut::from(&chunk.content[..]);
-                        let total_parts = chunk.total_parts;
-                        if total_parts > 1 {
-                            // Ensure first part is the max size
-                            if chunk_len != max_content_size {
-                                return Err(Error::InvalidChunk);
-                            }
+            // Receive content over each stream opened by the peer
+            let channels = async {
+                let Some(multiplexer) = multiplexer else {
+                    return future::pending().await;
+                };
+                let mut opened = HashSet::new();
+                let mut channel_receivers = FuturesUnordered::new();
+                loop {
+                    tokio::select! {
+                        result = multiplexer.accept() => {
+                            let (channel, mut receiver) = result.map_err(Error::ReceiveFailed)?;
 
-                            // Read chunk messages until we have all parts
-                            let channel = chunk.channel;
-                            let mut next_part = chunk.part + 1;
-                            while next_part < total_parts {
-                                let chunk = match conn_receiver
-                                    .receive()
-                                    .await
-                                    .map_err(Error::ReceiveFailed)?
-                                    .payload
-                                {
-                                    Some(wire::message::Payload::Chunk(chunk)) => chunk,
-                                    _ => return Err(Error::InvalidChunk),
-                                };
-                                if chunk.channel != channel {
-                                    return Err(Error::InvalidChunk);
-                                }
-                                if chunk.total_parts != total_parts {
-                                    return Err(Error::InvalidChunk);
-                                }
-                                if chunk.part != next_part {
-                                    return Err(Error::InvalidChunk);
-                                }
-                                let chunk_len = chunk.content.len();
-                                if chunk.part != total_parts - 1 && chunk_len != max_content_size {
-                                    return Err(Error::InvalidChunk);
-                                }
-                                let new_len = message.len() + chunk_len;
-                                if new_len > *max_size {
-                                    return Err(Error::MessageTooLarge(new_len));
-                                }
-                                message.extend_from_slice(&chunk.content);
-                                next_part += 1;
+                            // Ensure the peer only opens one stream per channel (so that it can't
+                            // replay a stream)
+                            if !opened.insert(channel) {
+                                return Err(Error::InvalidChannel);
                             }
+                            let (peer, receive_runtime, rate_limits, received_messages) =
+                                (&peer, &receive_runtime, &rate_limits, &self.received_messages);
+                            channel_receivers.push(async move {
+                                loop {
+                                    // Only content for the stream's channel may be sent over it
+                                    let chunk = match receiver
+                                        .receive()
+                                        .await
+                                        .map_err(Error::ReceiveFailed)?
+                                        .payload
+                                    {
+                                        Some(wire::message::Payload::Chunk(chunk))
+                                            if chunk.channel == channel => chunk,
+                                        _ => return Err(Error::InvalidChunk),
+                                    };
+                                    Self::receive_chunk(
+                                        receive_runtime,
+                                        peer,
+                                        max_content_size,
+                                        rate_limits,
+                                        &mut receiver,
+                                        chunk,
+                                        received_messages,
+                                    )
+                                    .await?;
+                                }
+                            });
                         }
-
-                        // Send message to client
-                        sender.send((peer.clone(), message.freeze())).await.unwrap();
-                    }
-                    Some(wire::message::Payload::Handshake(_)) => {
-                        self.received_messages
-                            .get_or_create(&metrics::Message::new_handshake(&peer))
-                            .inc();
-                        return Err(Error::UnexpectedHandshake);
-                    }
-                    _ => {
-                        self.received_messages
-                            .get_or_create(&metrics::Message::new_unknown(&peer))
-                            .inc();
-
-                        // We permit unknown messages to be received in case
-                        // peers are on a newer version than us
-                        continue;
+                        Some(result) = channel_receivers.next() => return result,
                     }
                 }
+            };
+
+            tokio::select! {
+                result = control => result,
+                result = channels => result,
             }
         });
 
         // Wait for one of the handlers to finish
         //
         // It is only possible for a handler to exit if there is an error.
-        let result = tokio::try_join!(&mut send_handler, &mut receive_handler);
+        let result = futures::try_join!(&mut send_handler, &mut receive_handler);
 
         // Ensure both handlers are aborted when one of them exits
         send_handler.abort();
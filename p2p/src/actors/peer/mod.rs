@@ -1,11 +1,10 @@
 //! Peer
 
-use crate::{connection, metrics};
+use crate::{connection, metrics, runtime};
 use commonware_cryptography::PublicKey;
 use governor::Quota;
 use prometheus_client::metrics::{counter::Counter, family::Family};
 use std::time::Duration;
-use tokio::task::JoinError;
 
 mod actor;
 pub use actor::Actor;
@@ -30,7 +29,7 @@ pub enum Error {
     PeerDisconnected,
     ReceiveFailed(connection::Error),
     UnexpectedHandshake,
-    UnexpectedFailure(JoinError),
+    UnexpectedFailure(runtime::Error),
     MessageDropped,
     MessageTooLarge(usize),
     InvalidChunk,
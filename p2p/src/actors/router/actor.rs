@@ -10,13 +10,13 @@ use crate::{
 use bytes::Bytes;
 use commonware_cryptography::PublicKey;
 use prometheus_client::metrics::{counter::Counter, family::Family};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use tokio::sync::mpsc;
 use tracing::debug;
 
 pub struct Actor {
     control: mpsc::Receiver<Message>,
-    connections: HashMap<PublicKey, peer::Relay>,
+    connections: BTreeMap<PublicKey, peer::Relay>,
 
     messages_dropped: Family<metrics::Message, Counter>,
 }
@@ -39,7 +39,7 @@ impl Actor {
         (
             Self {
                 control: control_receiver,
-                connections: HashMap::new(),
+                connections: BTreeMap::new(),
                 messages_dropped,
             },
             Mailbox::new(control_sender.clone()),
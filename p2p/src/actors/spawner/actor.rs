@@ -5,6 +5,8 @@ use super::{
 use crate::{
     actors::{peer, router, tracker},
     metrics,
+    runtime::Runtime,
+    transport,
 };
 use commonware_cryptography::Scheme;
 use governor::Quota;
@@ -13,20 +15,21 @@ use std::time::Duration;
 use tokio::sync::mpsc;
 use tracing::{debug, info};
 
-pub struct Actor<C: Scheme> {
+pub struct Actor<R: Runtime, C: Scheme, S: transport::Stream> {
+    runtime: R,
     mailbox_size: usize,
     gossip_bit_vec_frequency: Duration,
     allowed_bit_vec_rate: Quota,
     allowed_peers_rate: Quota,
 
-    receiver: mpsc::Receiver<Message<C>>,
+    receiver: mpsc::Receiver<Message<R, C, S>>,
 
     sent_messages: Family<metrics::Message, Counter>,
     received_messages: Family<metrics::Message, Counter>,
 }
 
-impl<C: Scheme> Actor<C> {
-    pub fn new(cfg: Config) -> (Self, Mailbox<C>) {
+impl<R: Runtime, C: Scheme, S: transport::Stream> Actor<R, C, S> {
+    pub fn new(runtime: R, cfg: Config) -> (Self, Mailbox<R, C, S>) {
         let sent_messages = Family::<metrics::Message, Counter>::default();
         let received_messages = Family::<metrics::Message, Counter>::default();
         {
@@ -42,6 +45,7 @@ impl<C: Scheme> Actor<C> {
 
         (
             Self {
+                runtime,
                 mailbox_size: cfg.mailbox_size,
                 gossip_bit_vec_frequency: cfg.gossip_bit_vec_frequency,
                 allowed_bit_vec_rate: cfg.allowed_bit_vec_rate,
@@ -67,6 +71,7 @@ impl<C: Scheme> Actor<C> {
                     let received_messages = self.received_messages.clone();
                     let tracker = tracker.clone();
                     let router = router.clone();
+                    let runtime = self.runtime.clone();
 
                     // Record handshake messages
                     //
@@ -79,10 +84,11 @@ impl<C: Scheme> Actor<C> {
                         .inc();
 
                     // Spawn peer
-                    tokio::spawn(async move {
+                    self.runtime.spawn(async move {
                         // Create peer
                         info!(peer = hex::encode(&peer), "peer started");
                         let (actor, messenger) = peer::Actor::new(
+                            runtime,
                             peer::Config {
                                 sent_messages,
                                 received_messages,
@@ -1,29 +1,28 @@
-use crate::{actors::tracker, connection::Stream};
+use crate::{actors::tracker, connection::Stream, runtime::Runtime, transport};
 use commonware_cryptography::{PublicKey, Scheme};
 use tokio::sync::mpsc;
 
-pub enum Message<C: Scheme> {
+pub enum Message<R: Runtime, C: Scheme, S: transport::Stream> {
     Spawn {
         peer: PublicKey,
-        connection: Stream<C>,
+        connection: Stream<R, C, S>,
         reservation: tracker::Reservation,
     },
 }
 
-#[derive(Clone)]
-pub struct Mailbox<C: Scheme> {
-    sender: mpsc::Sender<Message<C>>,
+pub struct Mailbox<R: Runtime, C: Scheme, S: transport::Stream> {
+    sender: mpsc::Sender<Message<R, C, S>>,
 }
 
-impl<C: Scheme> Mailbox<C> {
-    pub fn new(sender: mpsc::Sender<Message<C>>) -> Self {
+impl<R: Runtime, C: Scheme, S: transport::Stream> Mailbox<R, C, S> {
+    pub fn new(sender: mpsc::Sender<Message<R, C, S>>) -> Self {
         Self { sender }
     }
 
     pub async fn spawn(
         &self,
         peer: PublicKey,
-        connection: Stream<C>,
+        connection: Stream<R, C, S>,
         reservation: tracker::Reservation,
     ) {
         self.sender
@@ -36,3 +35,11 @@ impl<C: Scheme> Mailbox<C> {
             .unwrap();
     }
 }
+
+impl<R: Runtime, C: Scheme, S: transport::Stream> Clone for Mailbox<R, C, S> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
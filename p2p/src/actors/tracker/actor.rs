@@ -3,16 +3,20 @@ pub use super::{
     ingress::{Mailbox, Message, Oracle, Reservation},
     Config, Error,
 };
-use crate::{ip, metrics, wire};
+use crate::{
+    ip, metrics,
+    runtime::{KeyedRateLimiter, Runtime},
+    wire,
+};
 use bitvec::prelude::*;
-use commonware_cryptography::{PublicKey, Scheme};
-use governor::DefaultKeyedRateLimiter;
+use commonware_cryptography::{BatchItem, BatchScheme, PublicKey};
+use governor::RateLimiter;
 use prometheus_client::metrics::counter::Counter;
 use prometheus_client::metrics::family::Family;
 use prometheus_client::metrics::gauge::Gauge;
 use rand::prelude::IteratorRandom;
-use rand::{seq::SliceRandom, thread_rng};
-use std::time::{SystemTime, UNIX_EPOCH};
+use rand::seq::SliceRandom;
+use std::time::UNIX_EPOCH;
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
     net::SocketAddr,
@@ -124,17 +128,19 @@ impl AddressCount {
     }
 }
 
-pub struct Actor<C: Scheme> {
+pub struct Actor<R: Runtime, C: BatchScheme> {
+    runtime: R,
     crypto: C,
     allow_private_ips: bool,
     tracked_peer_sets: usize,
     peer_gossip_max_count: usize,
 
-    sender: mpsc::Sender<Message>,
     receiver: mpsc::Receiver<Message>,
-    peers: HashMap<PublicKey, AddressCount>,
+    releases: mpsc::UnboundedSender<PublicKey>,
+    released: mpsc::UnboundedReceiver<PublicKey>,
+    peers: BTreeMap<PublicKey, AddressCount>,
     sets: BTreeMap<u64, PeerSet>,
-    connections_rate_limiter: DefaultKeyedRateLimiter<PublicKey>,
+    connections_rate_limiter: KeyedRateLimiter<PublicKey, R>,
     connections: HashSet<PublicKey>,
 
     tracked_peers: Gauge,
@@ -144,10 +150,11 @@ pub struct Actor<C: Scheme> {
     ip_signature: wire::Peer,
 }
 
-impl<C: Scheme> Actor<C> {
-    pub fn new(mut cfg: Config<C>) -> (Self, Mailbox, Oracle) {
+impl<R: Runtime, C: BatchScheme> Actor<R, C> {
+    pub fn new(runtime: R, mut cfg: Config<C>) -> (Self, Mailbox, Oracle) {
         // Construct IP signature
-        let current_time = SystemTime::now()
+        let current_time = runtime
+            .system_time()
             .duration_since(UNIX_EPOCH)
             .expect("Failed to get current time")
             .as_secs();
@@ -163,7 +170,7 @@ impl<C: Scheme> Actor<C> {
         };
 
         // Register bootstrappers
-        let mut peers = HashMap::new();
+        let mut peers = BTreeMap::new();
         for (peer, address) in cfg.bootstrappers.into_iter() {
             if peer == cfg.crypto.me() {
                 continue;
@@ -180,10 +187,11 @@ impl<C: Scheme> Actor<C> {
 
         // Construct channels
         let (sender, receiver) = mpsc::channel(cfg.mailbox_size);
+        let (releases, released) = mpsc::unbounded_channel();
 
         // Create connections
         let connections_rate_limiter =
-            DefaultKeyedRateLimiter::keyed(cfg.allowed_connection_rate_per_peer);
+            RateLimiter::hashmap_with_clock(cfg.allowed_connection_rate_per_peer, &runtime);
 
         // Create metrics
         let tracked_peers = Gauge::default();
@@ -206,6 +214,7 @@ impl<C: Scheme> Actor<C> {
 
         (
             Self {
+                runtime,
                 crypto: cfg.crypto,
                 allow_private_ips: cfg.allow_private_ips,
                 tracked_peer_sets,
@@ -213,8 +222,9 @@ impl<C: Scheme> Actor<C> {
 
                 ip_signature,
 
-                sender: sender.clone(),
                 receiver,
+                releases,
+                released,
                 peers,
                 sets,
 
@@ -364,7 +374,7 @@ impl<C: Scheme> Actor<C> {
         // We allow peers to be sent in any order when responding to a bit vector (allows
         // for selecting a random subset of peers when there are too many) and allow
         // for duplicates (no need to create an additional set to check this)
-        let mut updated = false;
+        let mut parsed = Vec::with_capacity(peers_len);
         for peer in peers.peers {
             // Check if address is well formatted
             let address = socket_from_payload(&peer)?;
@@ -386,22 +396,42 @@ impl<C: Scheme> Actor<C> {
             if public_key == &self.crypto.me() {
                 return Err(Error::ReceivedSelf);
             }
-
-            // If any signature is invalid, disconnect from the peer
             let payload = wire_peer_payload(&peer);
-            if !C::verify(NAMESPACE, &payload, public_key, &signature.signature) {
-                return Err(Error::InvalidSignature);
-            }
+            parsed.push((address, payload, peer));
+        }
 
-            // Attempt to update peer record
+        // If any signature is invalid, disconnect from the peer
+        //
+        // We verify all signatures in a single batch (rather than one-by-one) because it is
+        // significantly cheaper and honest peers should never send us an invalid signature.
+        let batch = parsed
+            .iter()
+            .map(|(_, payload, peer)| {
+                let signature = peer.signature.as_ref().unwrap();
+                (
+                    NAMESPACE,
+                    payload.as_slice(),
+                    &signature.public_key,
+                    &signature.signature,
+                )
+            })
+            .collect::<Vec<BatchItem>>();
+        if !C::batch_verify(&mut self.runtime, &batch) {
+            return Err(Error::InvalidSignature);
+        }
+
+        // Attempt to update peer records
+        let mut updated = false;
+        for (address, _, peer) in parsed {
+            let public_key = peer.signature.as_ref().unwrap().public_key.clone();
             if self.handle_peer(
-                public_key,
+                &public_key,
                 Signature {
                     addr: address,
-                    peer: peer.clone(),
+                    peer,
                 },
             ) {
-                debug!(peer = hex::encode(public_key), "updated peer record");
+                debug!(peer = hex::encode(&public_key), "updated peer record");
                 updated = true;
             }
         }
@@ -415,7 +445,7 @@ impl<C: Scheme> Actor<C> {
         Ok(())
     }
 
-    fn handle_bit_vec(&self, bit_vec: wire::BitVec) -> Result<Option<wire::Peers>, Error> {
+    fn handle_bit_vec(&mut self, bit_vec: wire::BitVec) -> Result<Option<wire::Peers>, Error> {
         // Ensure we have the peerset requested
         let set = match self.sets.get(&bit_vec.index) {
             Some(set) => set,
@@ -480,7 +510,7 @@ impl<C: Scheme> Actor<C> {
         // select a subset to send (this increases the likelihood that
         // the recipient will hear about different peers from different sources)
         if peers.len() > self.peer_gossip_max_count {
-            peers.shuffle(&mut thread_rng());
+            peers.shuffle(&mut self.runtime);
             peers.truncate(self.peer_gossip_max_count);
         }
         Ok(Some(wire::Peers { peers }))
@@ -505,11 +535,26 @@ impl<C: Scheme> Actor<C> {
         // Reserve the connection
         self.connections.insert(peer.clone());
         self.reserved_connections.inc();
-        Some(Reservation::new(peer, Mailbox::new(self.sender.clone())))
+        Some(Reservation::new(peer, self.releases.clone()))
     }
 
     pub async fn run(mut self) {
-        while let Some(msg) = self.receiver.recv().await {
+        loop {
+            // Process released connections before any other message (so that they
+            // can be reserved again)
+            let msg = tokio::select! {
+                biased;
+
+                Some(peer) = self.released.recv() => {
+                    self.connections.remove(&peer);
+                    self.reserved_connections.dec();
+                    continue;
+                }
+                msg = self.receiver.recv() => match msg {
+                    Some(msg) => msg,
+                    None => break,
+                },
+            };
             match msg {
                 Message::Construct { public_key, peer } => {
                     // Kill if peer is not authorized
@@ -520,7 +565,7 @@ impl<C: Scheme> Actor<C> {
 
                     // Select a random peer set (we want to learn about all peers in
                     // our tracked sets)
-                    let set = match self.sets.values().choose(&mut thread_rng()) {
+                    let set = match self.sets.values().choose(&mut self.runtime) {
                         Some(set) => set,
                         None => {
                             debug!("no peer sets available");
@@ -576,10 +621,6 @@ impl<C: Scheme> Actor<C> {
                         let _ = reservation.send(None);
                     }
                 }
-                Message::Release { peer } => {
-                    self.connections.remove(&peer);
-                    self.reserved_connections.dec();
-                }
             }
         }
         debug!("tracker shutdown");
@@ -591,7 +632,8 @@ mod tests {
     use super::*;
     use crate::actors::peer;
     use crate::config::Bootstrapper;
-    use commonware_cryptography::ed25519;
+    use crate::runtime;
+    use commonware_cryptography::{ed25519, secp256k1, secp256r1, Scheme};
     use governor::Quota;
     use std::net::{IpAddr, Ipv4Addr};
     use std::num::NonZeroU32;
@@ -617,7 +659,7 @@ mod tests {
     async fn test_reserve_peer() {
         // Create actor
         let cfg = test_config(ed25519::insecure_signer(0), Vec::new());
-        let (actor, mailbox, oracle) = Actor::new(cfg);
+        let (actor, mailbox, oracle) = Actor::new(runtime::tokio::Context::default(), cfg);
 
         // Run actor in background
         tokio::spawn(async move {
@@ -655,12 +697,95 @@ mod tests {
         }
     }
 
+    async fn peers_invalid_signature<C: BatchScheme>(insecure_signer: fn(u16) -> C) {
+        // Create actor
+        let peer0 = insecure_signer(0);
+        let cfg = test_config(peer0.clone(), Vec::new());
+        let (actor, mailbox, oracle) = Actor::new(runtime::tokio::Context::default(), cfg);
+
+        // Run actor in background
+        tokio::spawn(async move {
+            actor.run().await;
+        });
+
+        // Register some peers
+        let mut peer1_signer = insecure_signer(1);
+        let peer1 = peer1_signer.me();
+        let mut peer2_signer = insecure_signer(2);
+        let peer2 = peer2_signer.me();
+        oracle
+            .register(0, vec![peer0.me(), peer1.clone(), peer2.clone()])
+            .await;
+
+        // Send a batch of peers where only one signature is invalid
+        let socket = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+        let (socket_bytes, payload_bytes) = socket_peer_payload(&socket, 0);
+        let valid = peer1_signer.sign(NAMESPACE, &payload_bytes);
+        let invalid = peer2_signer.sign(NAMESPACE, b"invalid");
+        let peers = wire::Peers {
+            peers: vec![
+                wire::Peer {
+                    socket: socket_bytes.clone(),
+                    timestamp: 0,
+                    signature: Some(wire::Signature {
+                        public_key: peer1.clone(),
+                        signature: valid,
+                    }),
+                },
+                wire::Peer {
+                    socket: socket_bytes,
+                    timestamp: 0,
+                    signature: Some(wire::Signature {
+                        public_key: peer2.clone(),
+                        signature: invalid,
+                    }),
+                },
+            ],
+        };
+        let (peer_mailbox, mut peer_receiver) = peer::Mailbox::test();
+        mailbox.peers(peers, peer_mailbox.clone()).await;
+
+        // Ensure the sender is killed
+        let msg = peer_receiver.recv().await.unwrap();
+        assert!(matches!(msg, peer::Message::Kill));
+
+        // Ensure no peer in the batch was recorded
+        mailbox.construct(peer1.clone(), peer_mailbox.clone()).await;
+        let msg = peer_receiver.recv().await.unwrap();
+        let bit_vec = match msg {
+            peer::Message::BitVec { bit_vec } => bit_vec,
+            _ => panic!("unexpected message"),
+        };
+        let mut sorted = [peer0.me(), peer1, peer2];
+        sorted.sort();
+        let me = sorted.iter().position(|p| *p == peer0.me()).unwrap();
+        let bits: BitVec<u8, Lsb0> = BitVec::from_vec(bit_vec.bits);
+        for (idx, bit) in bits.iter().enumerate() {
+            assert_eq!(*bit, idx == me);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_peers_invalid_signature() {
+        peers_invalid_signature(ed25519::insecure_signer).await;
+    }
+
+    #[tokio::test]
+    async fn test_peers_invalid_signature_secp256r1() {
+        peers_invalid_signature(secp256r1::insecure_signer).await;
+    }
+
+    #[tokio::test]
+    async fn test_peers_invalid_signature_secp256k1() {
+        peers_invalid_signature(secp256k1::insecure_signer).await;
+    }
+
     #[tokio::test]
     async fn test_bit_vec() {
         // Create actor
         let peer0 = ed25519::insecure_signer(0);
         let cfg = test_config(peer0.clone(), Vec::new());
-        let (actor, mailbox, oracle) = Actor::new(cfg);
+        let (actor, mailbox, oracle) = Actor::new(runtime::tokio::Context::default(), cfg);
 
         // Run actor in background
         tokio::spawn(async move {
@@ -34,11 +34,6 @@ pub enum Message {
         peer: PublicKey,
         reservation: oneshot::Sender<Option<Reservation>>,
     },
-
-    // Used by peer
-    Release {
-        peer: PublicKey,
-    },
 }
 
 #[derive(Clone)]
@@ -92,10 +87,6 @@ impl Mailbox {
             .unwrap();
         rx.await.unwrap()
     }
-
-    pub async fn release(&self, peer: PublicKey) {
-        self.sender.send(Message::Release { peer }).await.unwrap();
-    }
 }
 
 /// Mechanism to register authorized peers.
@@ -129,22 +120,21 @@ impl Oracle {
 }
 
 pub struct Reservation {
-    closer: Option<(PublicKey, Mailbox)>,
+    closer: Option<(PublicKey, mpsc::UnboundedSender<PublicKey>)>,
 }
 
 impl Reservation {
-    pub fn new(peer: PublicKey, mailbox: Mailbox) -> Self {
+    pub fn new(peer: PublicKey, releases: mpsc::UnboundedSender<PublicKey>) -> Self {
         Self {
-            closer: Some((peer, mailbox)),
+            closer: Some((peer, releases)),
         }
     }
 }
 
 impl Drop for Reservation {
     fn drop(&mut self) {
-        let (peer, mailbox) = self.closer.take().unwrap();
-        tokio::spawn(async move {
-            mailbox.release(peer).await;
-        });
+        // If the tracker has shutdown, there is nothing to release
+        let (peer, releases) = self.closer.take().unwrap();
+        let _ = releases.send(peer);
     }
 }
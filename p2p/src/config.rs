@@ -57,12 +57,29 @@ pub struct Config<C: Scheme> {
     /// Duration after which to close the connection if the handshake is not completed.
     pub handshake_timeout: Duration,
 
+    /// Maximum difference between the timestamp of a peer's handshake and our local time.
+    ///
+    /// Handshakes outside of this bound are rejected, as are handshakes replayed within it.
+    /// This should be larger than the expected clock skew between peers (plus the time it
+    /// takes to deliver a handshake).
+    pub synchrony_bound: Duration,
+
     /// Duration after which to close the connection if no message is read.
     pub read_timeout: Duration,
 
     /// Duration after which to close the connection if a message cannot be written.
     pub write_timeout: Duration,
 
+    /// Maximum duration we encrypt messages to a peer with the same key.
+    ///
+    /// When this elapses (checked before each message is sent), we ratchet our key
+    /// forward (and discard the previous key) so that compromise of the current key does
+    /// not expose previously sent messages.
+    pub rekey_frequency: Duration,
+
+    /// Maximum number of messages we encrypt to a peer with the same key.
+    pub rekey_messages: u64,
+
     /// Whether or not to disable Nagle's algorithm.
     ///
     /// The algorithm combines a series of small network packets into a single packet
@@ -134,8 +151,11 @@ impl<C: Scheme> Config<C> {
             mailbox_size: 1_000,
             max_frame_length: 1024 * 1024, // 1 MB
             handshake_timeout: Duration::from_secs(5),
+            synchrony_bound: Duration::from_secs(5),
             read_timeout: Duration::from_secs(60),
             write_timeout: Duration::from_secs(30),
+            rekey_frequency: Duration::from_secs(60 * 60),
+            rekey_messages: 1 << 30,
             tcp_nodelay: None,
             allowed_connection_rate_per_peer: Quota::per_minute(NonZeroU32::new(1).unwrap()),
             allowed_incoming_connection_rate: Quota::per_second(NonZeroU32::new(256).unwrap()),
@@ -170,8 +190,11 @@ impl<C: Scheme> Config<C> {
             mailbox_size: 1_000,
             max_frame_length: 1024 * 1024, // 1 MB
             handshake_timeout: Duration::from_secs(5),
+            synchrony_bound: Duration::from_secs(5),
             read_timeout: Duration::from_secs(10), // should be greater than gossip_bit_vec_frequency
             write_timeout: Duration::from_secs(10),
+            rekey_frequency: Duration::from_secs(60),
+            rekey_messages: 1 << 20,
             tcp_nodelay: None,
             allowed_connection_rate_per_peer: Quota::per_second(NonZeroU32::new(1).unwrap()),
             allowed_incoming_connection_rate: Quota::per_second(NonZeroU32::new(256).unwrap()),
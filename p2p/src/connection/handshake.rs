@@ -1,31 +1,87 @@
 use crate::{
-    connection::{utils::codec, x25519, Error},
-    wire,
+    connection::{
+        utils::{codec, hkdf, HmacSha256},
+        x25519, Error,
+    },
+    runtime::{self, Clock},
+    transport, wire,
 };
 use bytes::{Bytes, BytesMut};
 use commonware_cryptography::{PublicKey, Scheme};
 use futures::StreamExt;
+use hmac::Mac;
 use prost::Message;
-use tokio::net::TcpStream;
-use tokio::time;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, UNIX_EPOCH},
+};
 use tokio_util::codec::Framed;
 use tokio_util::codec::LengthDelimitedCodec;
+use zeroize::Zeroizing;
 
 const NAMESPACE: &[u8] = b"_COMMONWARE_P2P_HANDSHAKE_";
+const DIALER_KEY: &[u8] = b"dialer key";
+const LISTENER_KEY: &[u8] = b"listener key";
+const DIALER_CONFIRMATION: &[u8] = b"dialer confirmation";
+const LISTENER_CONFIRMATION: &[u8] = b"listener confirmation";
+
+/// Returns the current time (in milliseconds since the UNIX epoch).
+pub fn timestamp<R: Clock>(runtime: &R) -> u64 {
+    runtime
+        .system_time()
+        .duration_since(UNIX_EPOCH)
+        .expect("Failed to get current time")
+        .as_millis() as u64
+}
+
+/// Running hash of the handshakes exchanged over a connection.
+///
+/// Each handshake signs the transcript of all handshakes sent before it (and itself), so
+/// the listener's handshake is bound to the dialer's ephemeral public key.
+#[derive(Clone)]
+pub(super) struct Transcript {
+    hasher: Sha256,
+}
+
+impl Transcript {
+    pub(super) fn new() -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(NAMESPACE);
+        Self { hasher }
+    }
+
+    fn append(&mut self, recipient_public_key: &[u8], ephemeral_public_key: &[u8], timestamp: u64) {
+        for field in [recipient_public_key, ephemeral_public_key] {
+            self.hasher.update((field.len() as u32).to_be_bytes());
+            self.hasher.update(field);
+        }
+        self.hasher.update(timestamp.to_be_bytes());
+    }
 
-pub async fn create_handshake<C: Scheme>(
+    fn digest(&self) -> [u8; 32] {
+        self.hasher.clone().finalize().into()
+    }
+}
+
+pub(super) fn create_handshake<C: Scheme>(
     crypto: &mut C,
+    transcript: &mut Transcript,
     recipient_public_key: PublicKey,
     ephemeral_public_key: x25519_dalek::PublicKey,
-) -> Result<Bytes, Error> {
-    // Sign their public key
-    let mut payload = Vec::new();
-    payload.extend_from_slice(&recipient_public_key);
-    payload.extend_from_slice(ephemeral_public_key.as_bytes());
-    let signature = crypto.sign(NAMESPACE, &payload);
+    timestamp: u64,
+) -> Bytes {
+    // Sign the transcript (including this handshake)
+    transcript.append(
+        &recipient_public_key,
+        ephemeral_public_key.as_bytes(),
+        timestamp,
+    );
+    let signature = crypto.sign(NAMESPACE, &transcript.digest());
 
     // Send handshake
-    Ok(wire::Message {
+    wire::Message {
         payload: Some(wire::message::Payload::Handshake(wire::Handshake {
             recipient_public_key,
             ephemeral_public_key: x25519::encode_public_key(ephemeral_public_key),
@@ -33,19 +89,27 @@ pub async fn create_handshake<C: Scheme>(
                 public_key: crypto.me(),
                 signature,
             }),
+            timestamp,
         })),
     }
     .encode_to_vec()
-    .into())
+    .into()
 }
 
-pub struct Handshake {
+pub(super) struct Handshake {
     pub(super) ephemeral_public_key: x25519_dalek::PublicKey,
     pub(super) peer_public_key: PublicKey,
+    pub(super) timestamp: u64,
 }
 
 impl Handshake {
-    pub fn verify<C: Scheme>(crypto: &C, msg: BytesMut) -> Result<Self, Error> {
+    pub(super) fn verify<C: Scheme>(
+        crypto: &C,
+        transcript: &mut Transcript,
+        now: u64,
+        synchrony_bound: Duration,
+        msg: BytesMut,
+    ) -> Result<Self, Error> {
         // Parse handshake message
         let handshake = match wire::Message::decode(msg)
             .map_err(Error::UnableToDecode)?
@@ -79,51 +143,196 @@ impl Handshake {
             return Err(Error::InvalidPeerPublicKey);
         }
 
-        // Construct signing payload (ephemeral public key + my public key)
-        let mut payload = Vec::new();
-        payload.extend_from_slice(&our_public_key);
-        payload.extend_from_slice(&handshake.ephemeral_public_key);
+        // Verify that the handshake is recent
+        //
+        // This bounds how long we must remember a handshake to detect a replay.
+        if now.abs_diff(handshake.timestamp) > synchrony_bound.as_millis() as u64 {
+            return Err(Error::InvalidTimestamp(handshake.timestamp));
+        }
 
-        // Verify signature
-        if !C::verify(NAMESPACE, &payload, &public_key, &signature.signature) {
+        // Verify signature over the transcript (including this handshake)
+        transcript.append(
+            &our_public_key,
+            &handshake.ephemeral_public_key,
+            handshake.timestamp,
+        );
+        if !C::verify(
+            NAMESPACE,
+            &transcript.digest(),
+            &public_key,
+            &signature.signature,
+        ) {
             return Err(Error::InvalidSignature);
         }
 
         Ok(Self {
             ephemeral_public_key,
             peer_public_key: public_key,
+            timestamp: handshake.timestamp,
+        })
+    }
+}
+
+/// Session keys derived from the transcript of a completed handshake.
+pub(super) struct Keys {
+    pub(super) send: Zeroizing<[u8; 32]>,
+    pub(super) receive: Zeroizing<[u8; 32]>,
+
+    transcript: [u8; 32],
+    confirmation: [u8; 32],
+    peer_confirmation: [u8; 32],
+}
+
+impl Keys {
+    /// Derives separate keys for each direction of the connection (and for each party's
+    /// confirmation) from the shared secret, salted with the transcript.
+    pub(super) fn derive(
+        secret: x25519_dalek::EphemeralSecret,
+        peer_ephemeral_public_key: &x25519_dalek::PublicKey,
+        transcript: &Transcript,
+        dialer: bool,
+    ) -> Result<Self, Error> {
+        // Reject low-order ephemeral public keys (which would result in a known shared secret)
+        let shared_secret = secret.diffie_hellman(peer_ephemeral_public_key);
+        if !shared_secret.was_contributory() {
+            return Err(Error::InvalidEphemeralPublicKey);
+        }
+
+        // Derive keys
+        let transcript = transcript.digest();
+        let derive = |info| hkdf(&transcript, shared_secret.as_bytes(), info);
+        let dialer_key = Zeroizing::new(derive(DIALER_KEY));
+        let listener_key = Zeroizing::new(derive(LISTENER_KEY));
+        let dialer_confirmation = derive(DIALER_CONFIRMATION);
+        let listener_confirmation = derive(LISTENER_CONFIRMATION);
+        Ok(if dialer {
+            Self {
+                send: dialer_key,
+                receive: listener_key,
+                transcript,
+                confirmation: dialer_confirmation,
+                peer_confirmation: listener_confirmation,
+            }
+        } else {
+            Self {
+                send: listener_key,
+                receive: dialer_key,
+                transcript,
+                confirmation: listener_confirmation,
+                peer_confirmation: dialer_confirmation,
+            }
         })
     }
+
+    fn tag(key: &[u8; 32], transcript: &[u8; 32]) -> HmacSha256 {
+        let mut mac =
+            <HmacSha256 as Mac>::new_from_slice(key).expect("HMAC accepts any key length");
+        mac.update(transcript);
+        mac
+    }
+
+    /// Creates a message proving that we derived the same keys as the peer.
+    pub(super) fn create_confirmation(&self) -> Bytes {
+        let tag = Self::tag(&self.confirmation, &self.transcript)
+            .finalize()
+            .into_bytes();
+        wire::Message {
+            payload: Some(wire::message::Payload::Confirmation(wire::Confirmation {
+                tag: tag.to_vec(),
+            })),
+        }
+        .encode_to_vec()
+        .into()
+    }
+
+    /// Verifies that the peer derived the same keys as us.
+    pub(super) fn verify_confirmation(&self, msg: BytesMut) -> Result<(), Error> {
+        let confirmation = match wire::Message::decode(msg)
+            .map_err(Error::UnableToDecode)?
+            .payload
+        {
+            Some(wire::message::Payload::Confirmation(confirmation)) => confirmation,
+            _ => return Err(Error::UnexpectedMessage),
+        };
+        Self::tag(&self.peer_confirmation, &self.transcript)
+            .verify_slice(&confirmation.tag)
+            .map_err(|_| Error::InvalidConfirmation)
+    }
+}
+
+/// Recently accepted incoming handshakes (used to reject replays).
+///
+/// Handshakes with a timestamp outside of the synchrony bound are rejected before they
+/// are checked against this cache, so entries are discarded once they leave the bound.
+#[derive(Default)]
+pub struct Replays {
+    seen: HashMap<[u8; 32], u64>,
 }
 
-pub struct IncomingHandshake {
+impl Replays {
+    /// Records the ephemeral public key of a handshake (returning `false` if it was
+    /// already recorded).
+    fn insert(
+        &mut self,
+        now: u64,
+        synchrony_bound: Duration,
+        ephemeral_public_key: &x25519_dalek::PublicKey,
+        timestamp: u64,
+    ) -> bool {
+        let bound = synchrony_bound.as_millis() as u64;
+        self.seen
+            .retain(|_, seen| seen.saturating_add(bound) >= now);
+        self.seen
+            .insert(ephemeral_public_key.to_bytes(), timestamp)
+            .is_none()
+    }
+}
+
+pub struct IncomingHandshake<S: transport::Stream> {
     pub peer_public_key: PublicKey,
-    pub(super) framed: Framed<TcpStream, LengthDelimitedCodec>,
+    pub(super) framed: Framed<S, LengthDelimitedCodec>,
     pub(super) ephemeral_public_key: x25519_dalek::PublicKey,
+    pub(super) transcript: Transcript,
 }
 
-impl IncomingHandshake {
-    pub async fn verify<C: Scheme>(
+impl<S: transport::Stream> IncomingHandshake<S> {
+    pub async fn verify<R: Clock, C: Scheme>(
+        runtime: &R,
         crypto: &C,
         max_frame_len: usize,
-        handshake_timeout: time::Duration,
-        stream: TcpStream,
+        handshake_timeout: Duration,
+        synchrony_bound: Duration,
+        replays: &Mutex<Replays>,
+        stream: S,
     ) -> Result<Self, Error> {
         // Setup connection
         let mut framed = Framed::new(stream, codec(max_frame_len));
 
         // Verify handshake message from peer
-        let msg = time::timeout(handshake_timeout, framed.next())
+        let msg = runtime::timeout(runtime, handshake_timeout, framed.next())
             .await
             .map_err(|_| Error::HandshakeTimeout)?
             .ok_or(Error::StreamClosed)?
             .map_err(|_| Error::ReadFailed)?;
-        let handshake = Handshake::verify(crypto, msg)?;
+        let now = timestamp(runtime);
+        let mut transcript = Transcript::new();
+        let handshake = Handshake::verify(crypto, &mut transcript, now, synchrony_bound, msg)?;
+
+        // Ensure the handshake has not been seen before
+        if !replays.lock().unwrap().insert(
+            now,
+            synchrony_bound,
+            &handshake.ephemeral_public_key,
+            handshake.timestamp,
+        ) {
+            return Err(Error::ReplayedHandshake);
+        }
 
         Ok(Self {
             framed,
             peer_public_key: handshake.peer_public_key,
             ephemeral_public_key: handshake.ephemeral_public_key,
+            transcript,
         })
     }
 }
@@ -5,21 +5,24 @@ use prost::DecodeError;
 use std::time::Duration;
 
 mod handshake;
+mod ratchet;
 mod stream;
 mod utils;
 mod x25519;
 
-pub use handshake::IncomingHandshake;
-pub use stream::{Sender, Stream};
+pub use handshake::{IncomingHandshake, Replays};
+pub use stream::{Receiver, Sender, Stream};
 
 #[derive(Clone)]
 pub struct Config<C: Scheme> {
     pub crypto: C,
     pub max_frame_length: usize,
     pub handshake_timeout: Duration,
+    pub synchrony_bound: Duration,
     pub read_timeout: Duration,
     pub write_timeout: Duration,
-    pub tcp_nodelay: Option<bool>,
+    pub rekey_frequency: Duration,
+    pub rekey_messages: u64,
 }
 
 #[derive(Debug)]
@@ -32,6 +35,9 @@ pub enum Error {
     HandshakeNotForUs,
     MissingSignature,
     InvalidSignature,
+    InvalidTimestamp(u64),
+    ReplayedHandshake,
+    InvalidConfirmation,
     HandshakeTimeout,
     ReadTimeout,
     WriteTimeout,
@@ -39,7 +45,6 @@ pub enum Error {
     ReadFailed,
     SendFailed,
     StreamClosed,
-    CipherCreationFailed,
     PeerNonceOverflow,
     OurNonceOverflow,
     EncryptionFailed,
@@ -64,6 +69,9 @@ impl std::fmt::Display for Error {
             }
             Error::MissingSignature => write!(f, "missing signature"),
             Error::InvalidSignature => write!(f, "invalid signature"),
+            Error::InvalidTimestamp(timestamp) => write!(f, "invalid timestamp: {}", timestamp),
+            Error::ReplayedHandshake => write!(f, "replayed handshake"),
+            Error::InvalidConfirmation => write!(f, "invalid confirmation"),
             Error::HandshakeTimeout => write!(f, "handshake timeout"),
             Error::ReadTimeout => write!(f, "read timeout"),
             Error::WriteTimeout => write!(f, "write timeout"),
@@ -71,7 +79,6 @@ impl std::fmt::Display for Error {
             Error::ReadFailed => write!(f, "read failed"),
             Error::SendFailed => write!(f, "send failed"),
             Error::StreamClosed => write!(f, "connection closed"),
-            Error::CipherCreationFailed => write!(f, "cipher creation failed"),
             Error::PeerNonceOverflow => write!(f, "peer nonce overflow"),
             Error::OurNonceOverflow => write!(f, "our nonce overflow"),
             Error::EncryptionFailed => write!(f, "encryption failed"),
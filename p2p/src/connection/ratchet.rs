@@ -0,0 +1,103 @@
+use crate::connection::utils::{hkdf, nonce_bytes};
+use chacha20poly1305::{aead::KeyInit, ChaCha20Poly1305, Key, Nonce};
+use zeroize::Zeroizing;
+
+const NAMESPACE: &[u8] = b"_COMMONWARE_P2P_RATCHET_";
+const NEXT_KEY: &[u8] = b"next key";
+
+/// Key (and nonce) used to encrypt one direction of a connection.
+///
+/// Advancing the ratchet replaces the key with a one-way function of itself (and discards the
+/// previous key), so compromise of the current key does not expose messages encrypted with any
+/// previous key.
+pub struct Ratchet {
+    key: Zeroizing<[u8; 32]>,
+    cipher: ChaCha20Poly1305,
+    nonce: u64,
+}
+
+impl Ratchet {
+    pub fn new(key: Zeroizing<[u8; 32]>) -> Self {
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key.as_ref()));
+        Self {
+            key,
+            cipher,
+            nonce: 0,
+        }
+    }
+
+    /// Returns the cipher for the current key.
+    pub fn cipher(&self) -> &ChaCha20Poly1305 {
+        &self.cipher
+    }
+
+    /// Returns the number of messages encrypted with the current key.
+    pub fn messages(&self) -> u64 {
+        self.nonce
+    }
+
+    /// Returns the nonce for the next message (or `None` if all nonces for the current key
+    /// have been used).
+    pub fn nonce(&mut self) -> Option<Nonce> {
+        if self.nonce == u64::MAX {
+            return None;
+        }
+        let nonce = nonce_bytes(self.nonce);
+        self.nonce += 1;
+        Some(nonce)
+    }
+
+    /// Replaces the current key with the next key (and resets the nonce).
+    pub fn advance(&mut self) {
+        *self = Self::new(Zeroizing::new(hkdf(NAMESPACE, self.key.as_ref(), NEXT_KEY)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chacha20poly1305::aead::Aead;
+
+    #[test]
+    fn test_advance() {
+        let mut sender = Ratchet::new(Zeroizing::new([1; 32]));
+        let mut receiver = Ratchet::new(Zeroizing::new([1; 32]));
+        let mut ciphertexts = Vec::new();
+        for _ in 0..3 {
+            // Both sides derive the same key
+            let nonce = sender.nonce().unwrap();
+            let ciphertext = sender.cipher().encrypt(&nonce, b"hello".as_ref()).unwrap();
+            let nonce = receiver.nonce().unwrap();
+            let plaintext = receiver
+                .cipher()
+                .decrypt(&nonce, ciphertext.as_ref())
+                .unwrap();
+            assert_eq!(plaintext, b"hello");
+            assert_eq!(sender.messages(), 1);
+            ciphertexts.push(ciphertext);
+
+            // Each key is different (and the nonce is reset)
+            sender.advance();
+            receiver.advance();
+            assert_eq!(sender.messages(), 0);
+        }
+        assert_ne!(ciphertexts[0], ciphertexts[1]);
+        assert_ne!(ciphertexts[1], ciphertexts[2]);
+
+        // Messages encrypted with a previous key can't be decrypted with the current key
+        let nonce = receiver.nonce().unwrap();
+        assert!(receiver
+            .cipher()
+            .decrypt(&nonce, ciphertexts[2].as_ref())
+            .is_err());
+    }
+
+    #[test]
+    fn test_nonce_overflow() {
+        let mut ratchet = Ratchet::new(Zeroizing::new([1; 32]));
+        ratchet.nonce = u64::MAX;
+        assert!(ratchet.nonce().is_none());
+        ratchet.advance();
+        assert!(ratchet.nonce().is_some());
+    }
+}
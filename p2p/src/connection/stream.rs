@@ -1,210 +1,627 @@
 use crate::{
     connection::{
-        handshake::{create_handshake, Handshake, IncomingHandshake},
-        utils::{codec, nonce_bytes},
+        handshake::{create_handshake, timestamp, Handshake, IncomingHandshake, Keys, Transcript},
+        ratchet::Ratchet,
+        utils::{codec, hkdf},
         Config, Error,
     },
+    runtime::{self, Clock, Runtime},
+    transport::{self, Mux},
     wire,
 };
-use bytes::Bytes;
-use chacha20poly1305::{
-    aead::{Aead, KeyInit},
-    ChaCha20Poly1305, Nonce,
-};
+use bytes::{Bytes, BytesMut};
+use chacha20poly1305::aead::Aead;
 use commonware_cryptography::{PublicKey, Scheme};
 use futures::{
     stream::{SplitSink, SplitStream},
     SinkExt, StreamExt,
 };
 use prost::Message;
-use std::time::Duration;
-use tokio::net::TcpStream;
-use tokio::{select, time};
+use std::time::{Duration, Instant};
 use tokio_util::codec::Framed;
 use tokio_util::codec::LengthDelimitedCodec;
+use zeroize::Zeroizing;
 
 const CHUNK_PADDING: usize = 32 /* protobuf padding*/ + 12 /* chunk info */ + 16 /* encryption tag */;
 
-pub struct Stream<C: Scheme> {
+const CHANNEL_NAMESPACE: &[u8] = b"_COMMONWARE_P2P_CHANNEL_";
+
+pub struct Stream<R: Runtime, C: Scheme, S: transport::Stream> {
+    runtime: R,
     config: Config<C>,
-    dialer: bool,
-    framed: Framed<TcpStream, LengthDelimitedCodec>,
-    cipher: ChaCha20Poly1305,
+    framed: Framed<S, LengthDelimitedCodec>,
+    send_key: Zeroizing<[u8; 32]>,
+    receive_key: Zeroizing<[u8; 32]>,
 }
 
-impl<C: Scheme> Stream<C> {
+impl<R: Runtime, C: Scheme, S: transport::Stream> Stream<R, C, S> {
     pub async fn upgrade_dialer(
+        mut runtime: R,
         mut config: Config<C>,
-        stream: TcpStream,
+        stream: S,
         peer: PublicKey,
     ) -> Result<Self, Error> {
         // Setup connection
         let mut framed = Framed::new(stream, codec(config.max_frame_length));
 
         // Generate shared secret
-        let secret = x25519_dalek::EphemeralSecret::random();
+        let secret = x25519_dalek::EphemeralSecret::random_from_rng(&mut runtime);
         let ephemeral = x25519_dalek::PublicKey::from(&secret);
 
         // Send handshake
-        let msg = create_handshake(&mut config.crypto, peer.clone(), ephemeral).await?;
-        time::timeout(config.handshake_timeout, framed.send(msg))
-            .await
-            .map_err(|_| Error::HandshakeTimeout)?
-            .map_err(|_| Error::SendFailed)?;
+        let mut transcript = Transcript::new();
+        let msg = create_handshake(
+            &mut config.crypto,
+            &mut transcript,
+            peer.clone(),
+            ephemeral,
+            timestamp(&runtime),
+        );
+        Self::send_handshake(&runtime, &config, &mut framed, msg).await?;
 
         // Verify handshake message from peer
-        let msg = time::timeout(config.handshake_timeout, framed.next())
-            .await
-            .map_err(|_| Error::HandshakeTimeout)?
-            .ok_or(Error::StreamClosed)?
-            .map_err(|_| Error::ReadFailed)?;
-        let handshake = Handshake::verify(&config.crypto, msg)?;
+        let msg = Self::receive_handshake(&runtime, &config, &mut framed).await?;
+        let handshake = Handshake::verify(
+            &config.crypto,
+            &mut transcript,
+            timestamp(&runtime),
+            config.synchrony_bound,
+            msg,
+        )?;
 
         // Ensure we connected to the right peer
         if peer != handshake.peer_public_key {
             return Err(Error::WrongPeer);
         }
 
-        // Create cipher
-        let shared_secret = secret.diffie_hellman(&handshake.ephemeral_public_key);
-        let cipher = ChaCha20Poly1305::new_from_slice(shared_secret.as_bytes())
-            .map_err(|_| Error::CipherCreationFailed)?;
+        // Derive keys
+        let keys = Keys::derive(secret, &handshake.ephemeral_public_key, &transcript, true)?;
+
+        // Verify that the peer derived the same keys (and then prove that we did)
+        let msg = Self::receive_handshake(&runtime, &config, &mut framed).await?;
+        keys.verify_confirmation(msg)?;
+        Self::send_handshake(&runtime, &config, &mut framed, keys.create_confirmation()).await?;
 
-        // We keep track of dialer to determine who adds a bit to their nonce (to prevent reuse)
         Ok(Self {
+            runtime,
             config,
-            dialer: true,
             framed,
-            cipher,
+            send_key: keys.send,
+            receive_key: keys.receive,
         })
     }
 
     pub async fn upgrade_listener(
+        mut runtime: R,
         mut config: Config<C>,
-        mut handshake: IncomingHandshake,
+        mut handshake: IncomingHandshake<S>,
     ) -> Result<Self, Error> {
         // Generate shared secret
-        let secret = x25519_dalek::EphemeralSecret::random();
+        let secret = x25519_dalek::EphemeralSecret::random_from_rng(&mut runtime);
         let ephemeral = x25519_dalek::PublicKey::from(&secret);
 
-        // Send handshake
+        // Send handshake (which binds the peer's handshake)
         let msg = create_handshake(
             &mut config.crypto,
+            &mut handshake.transcript,
             handshake.peer_public_key.clone(),
             ephemeral,
+            timestamp(&runtime),
+        );
+        Self::send_handshake(&runtime, &config, &mut handshake.framed, msg).await?;
+
+        // Derive keys
+        let keys = Keys::derive(
+            secret,
+            &handshake.ephemeral_public_key,
+            &handshake.transcript,
+            false,
+        )?;
+
+        // Prove that we derived the same keys as the peer (and then verify that they did)
+        Self::send_handshake(
+            &runtime,
+            &config,
+            &mut handshake.framed,
+            keys.create_confirmation(),
         )
         .await?;
-        time::timeout(config.handshake_timeout, handshake.framed.send(msg))
-            .await
-            .map_err(|_| Error::HandshakeTimeout)?
-            .map_err(|_| Error::SendFailed)?;
-
-        // Create cipher
-        let shared_secret = secret.diffie_hellman(&handshake.ephemeral_public_key);
-        let cipher = ChaCha20Poly1305::new_from_slice(shared_secret.as_bytes())
-            .map_err(|_| Error::CipherCreationFailed)?;
+        let msg = Self::receive_handshake(&runtime, &config, &mut handshake.framed).await?;
+        keys.verify_confirmation(msg)?;
 
         Ok(Stream {
+            runtime,
             config,
-            dialer: false,
             framed: handshake.framed,
-            cipher,
+            send_key: keys.send,
+            receive_key: keys.receive,
+        })
+    }
+
+    async fn send_handshake(
+        runtime: &R,
+        config: &Config<C>,
+        framed: &mut Framed<S, LengthDelimitedCodec>,
+        msg: Bytes,
+    ) -> Result<(), Error> {
+        runtime::timeout(runtime, config.handshake_timeout, framed.send(msg))
+            .await
+            .map_err(|_| Error::HandshakeTimeout)?
+            .map_err(|_| Error::SendFailed)
+    }
+
+    async fn receive_handshake(
+        runtime: &R,
+        config: &Config<C>,
+        framed: &mut Framed<S, LengthDelimitedCodec>,
+    ) -> Result<BytesMut, Error> {
+        runtime::timeout(runtime, config.handshake_timeout, framed.next())
+            .await
+            .map_err(|_| Error::HandshakeTimeout)?
+            .ok_or(Error::StreamClosed)?
+            .map_err(|_| Error::ReadFailed)
+    }
+
+    /// Returns a handle to send each channel over its own stream (or `None` if the transport
+    /// only supports a single stream per connection).
+    pub fn multiplexer(&self) -> Option<Multiplexer<R, S>> {
+        let mux = self.framed.get_ref().mux()?;
+        Some(Multiplexer {
+            runtime: self.runtime.clone(),
+            mux,
+            max_frame_length: self.config.max_frame_length,
+            handshake_timeout: self.config.handshake_timeout,
+            write_timeout: self.config.write_timeout,
+            rekey_frequency: self.config.rekey_frequency,
+            rekey_messages: self.config.rekey_messages,
+            send_key: self.send_key.clone(),
+            receive_key: self.receive_key.clone(),
         })
     }
 
-    pub fn split(self) -> (usize, Sender, Receiver) {
+    pub fn split(self) -> (usize, Sender<R, S>, Receiver<R, S>) {
         let (sink, stream) = self.framed.split();
+        let next_rekey = self.runtime.current() + self.config.rekey_frequency;
         (
             self.config.max_frame_length - CHUNK_PADDING,
             Sender {
+                runtime: self.runtime.clone(),
                 write_timeout: self.config.write_timeout,
-                dialer: self.dialer,
-                cipher: self.cipher.clone(),
+                rekey_frequency: self.config.rekey_frequency,
+                rekey_messages: self.config.rekey_messages,
+                next_rekey,
+                ratchet: Ratchet::new(self.send_key),
                 sink,
-                my_nonce: 0,
             },
             Receiver {
-                read_timeout: self.config.read_timeout,
-                dialer: self.dialer,
-                cipher: self.cipher,
+                runtime: self.runtime,
+                read_timeout: Some(self.config.read_timeout),
+                ratchet: Ratchet::new(self.receive_key),
                 stream,
-                peer_nonce: 0,
             },
         )
     }
 }
 
-pub struct Sender {
+pub struct Sender<R: Clock, S: transport::Stream> {
+    runtime: R,
     write_timeout: Duration,
-    dialer: bool,
-    cipher: ChaCha20Poly1305,
-    sink: SplitSink<Framed<TcpStream, LengthDelimitedCodec>, Bytes>,
-    my_nonce: u64,
+    rekey_frequency: Duration,
+    rekey_messages: u64,
+    next_rekey: Instant,
+    ratchet: Ratchet,
+    sink: SplitSink<Framed<S, LengthDelimitedCodec>, Bytes>,
 }
 
-impl Sender {
-    fn my_nonce(&mut self) -> Result<Nonce, Error> {
-        if self.my_nonce == u64::MAX {
-            return Err(Error::OurNonceOverflow);
-        }
-        let nonce_bytes = nonce_bytes(self.my_nonce, self.dialer);
-        self.my_nonce += 1;
-        Ok(nonce_bytes)
-    }
-
-    pub async fn send(&mut self, msg: wire::Message) -> Result<(), Error> {
+impl<R: Clock, S: transport::Stream> Sender<R, S> {
+    async fn send_encrypted(&mut self, msg: wire::Message) -> Result<(), Error> {
         // Encrypt data
         let msg = msg.encode_to_vec();
-        let nonce = self.my_nonce()?;
+        let nonce = self.ratchet.nonce().ok_or(Error::OurNonceOverflow)?;
         let msg = self
-            .cipher
+            .ratchet
+            .cipher()
             .encrypt(&nonce, msg.as_ref())
             .map_err(|_| Error::EncryptionFailed)?;
 
         // Send data
-        let result = time::timeout(self.write_timeout, self.sink.send(Bytes::from(msg))).await;
+        let result = runtime::timeout(
+            &self.runtime,
+            self.write_timeout,
+            self.sink.send(Bytes::from(msg)),
+        )
+        .await;
         result
             .map_err(|_| Error::WriteTimeout)?
             .map_err(|_| Error::SendFailed)
     }
+
+    pub async fn send(&mut self, msg: wire::Message) -> Result<(), Error> {
+        // Ratchet our key forward if we've used it for too many messages (or for too long)
+        //
+        // The peer advances its copy of the key when it receives the rekey message (which is
+        // encrypted with the current key).
+        let now = self.runtime.current();
+        if self.ratchet.messages() >= self.rekey_messages || now >= self.next_rekey {
+            self.send_encrypted(wire::Message {
+                payload: Some(wire::message::Payload::Rekey(wire::Rekey {})),
+            })
+            .await?;
+            self.ratchet.advance();
+            self.next_rekey = now + self.rekey_frequency;
+        }
+        self.send_encrypted(msg).await
+    }
 }
 
-pub struct Receiver {
-    read_timeout: Duration,
-    dialer: bool,
-    cipher: ChaCha20Poly1305,
-    stream: SplitStream<Framed<TcpStream, LengthDelimitedCodec>>,
-    peer_nonce: u64,
+/// Opens (and accepts) a dedicated stream for each channel over a multiplexed connection.
+///
+/// Each stream only carries messages in one direction (from the peer that opened it) and is
+/// encrypted with a key derived from the connection's key for that direction and the channel (so
+/// messages can't be moved between streams).
+pub struct Multiplexer<R: Runtime, S: transport::Stream> {
+    runtime: R,
+    mux: S::Mux,
+    max_frame_length: usize,
+    handshake_timeout: Duration,
+    write_timeout: Duration,
+    rekey_frequency: Duration,
+    rekey_messages: u64,
+    send_key: Zeroizing<[u8; 32]>,
+    receive_key: Zeroizing<[u8; 32]>,
 }
 
-impl Receiver {
-    fn peer_nonce(&mut self) -> Result<Nonce, Error> {
-        if self.peer_nonce == u64::MAX {
-            return Err(Error::PeerNonceOverflow);
-        }
-        let nonce_bytes = nonce_bytes(self.peer_nonce, !self.dialer);
-        self.peer_nonce += 1;
-        Ok(nonce_bytes)
+impl<R: Runtime, S: transport::Stream> Multiplexer<R, S> {
+    fn channel_key(key: &[u8; 32], channel: u32) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(hkdf(CHANNEL_NAMESPACE, key, &channel.to_be_bytes()))
     }
 
-    pub async fn receive(&mut self) -> Result<wire::Message, Error> {
-        select! {
-            Some(msg) = self.stream.next() => {
-                // Invalid frame
-                let msg = msg.map_err(|_| Error::ReadInvalidFrame)?;
-
-                // Decrypt data
-                let nonce = self.peer_nonce()?;
-                let msg = self
-                    .cipher
-                    .decrypt(&nonce, msg.as_ref())
-                    .map_err(|_| Error::DecryptionFailed)?;
-
-                // Deserialize data
-                Ok(wire::Message::decode(msg.as_ref()).map_err(Error::UnableToDecode)?)
+    /// Opens a stream to send messages over the provided channel.
+    pub async fn open(&self, channel: u32) -> Result<Sender<R, S>, Error> {
+        let stream = self.mux.open().await.map_err(|_| Error::SendFailed)?;
+        let mut framed = Framed::new(stream, codec(self.max_frame_length));
+
+        // Identify the channel (any other channel would fail to decrypt the stream)
+        let header = Bytes::copy_from_slice(&channel.to_be_bytes());
+        runtime::timeout(&self.runtime, self.write_timeout, framed.send(header))
+            .await
+            .map_err(|_| Error::WriteTimeout)?
+            .map_err(|_| Error::SendFailed)?;
+
+        let (sink, _) = framed.split();
+        Ok(Sender {
+            runtime: self.runtime.clone(),
+            write_timeout: self.write_timeout,
+            rekey_frequency: self.rekey_frequency,
+            rekey_messages: self.rekey_messages,
+            next_rekey: self.runtime.current() + self.rekey_frequency,
+            ratchet: Ratchet::new(Self::channel_key(&self.send_key, channel)),
+            sink,
+        })
+    }
+
+    /// Accepts a stream opened by the peer (and returns the channel it sends messages over).
+    ///
+    /// Channels may be idle for long periods, so the returned receiver does not time out (the
+    /// liveness of the peer is checked on the connection's own stream).
+    pub async fn accept(&self) -> Result<(u32, Receiver<R, S>), Error> {
+        let stream = self.mux.accept().await.map_err(|_| Error::StreamClosed)?;
+        let mut framed = Framed::new(stream, codec(self.max_frame_length));
+
+        // Read the channel identifier
+        let header = runtime::timeout(&self.runtime, self.handshake_timeout, framed.next())
+            .await
+            .map_err(|_| Error::ReadTimeout)?
+            .ok_or(Error::StreamClosed)?
+            .map_err(|_| Error::ReadInvalidFrame)?;
+        let channel = u32::from_be_bytes(
+            header
+                .as_ref()
+                .try_into()
+                .map_err(|_| Error::ReadInvalidFrame)?,
+        );
+
+        let (_, stream) = framed.split();
+        Ok((
+            channel,
+            Receiver {
+                runtime: self.runtime.clone(),
+                read_timeout: None,
+                ratchet: Ratchet::new(Self::channel_key(&self.receive_key, channel)),
+                stream,
             },
-            _ = time::sleep(self.read_timeout) => Err(Error::ReadTimeout),
-            else => Err(Error::StreamClosed),
+        ))
+    }
+}
+
+pub struct Receiver<R: Clock, S: transport::Stream> {
+    runtime: R,
+    read_timeout: Option<Duration>,
+    ratchet: Ratchet,
+    stream: SplitStream<Framed<S, LengthDelimitedCodec>>,
+}
+
+impl<R: Clock, S: transport::Stream> Receiver<R, S> {
+    pub async fn receive(&mut self) -> Result<wire::Message, Error> {
+        loop {
+            // Read data
+            let msg = match self.read_timeout {
+                Some(read_timeout) => {
+                    runtime::timeout(&self.runtime, read_timeout, self.stream.next())
+                        .await
+                        .map_err(|_| Error::ReadTimeout)?
+                }
+                None => self.stream.next().await,
+            };
+            let msg = msg
+                .ok_or(Error::StreamClosed)?
+                .map_err(|_| Error::ReadInvalidFrame)?;
+
+            // Decrypt data
+            let nonce = self.ratchet.nonce().ok_or(Error::PeerNonceOverflow)?;
+            let msg = self
+                .ratchet
+                .cipher()
+                .decrypt(&nonce, msg.as_ref())
+                .map_err(|_| Error::DecryptionFailed)?;
+
+            // Deserialize data
+            let msg = wire::Message::decode(msg.as_ref()).map_err(Error::UnableToDecode)?;
+
+            // Ratchet the peer's key forward (if requested)
+            if let Some(wire::message::Payload::Rekey(_)) = msg.payload {
+                self.ratchet.advance();
+                continue;
+            }
+            return Ok(msg);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        connection::Replays,
+        runtime::{deterministic, Spawner},
+    };
+    use commonware_cryptography::ed25519;
+    use std::sync::Mutex;
+    use tokio::io::{duplex, DuplexStream};
+
+    const MAX_FRAME_LENGTH: usize = 1024;
+
+    fn config(seed: u16) -> Config<ed25519::Ed25519> {
+        Config {
+            crypto: ed25519::insecure_signer(seed),
+            max_frame_length: MAX_FRAME_LENGTH,
+            handshake_timeout: Duration::from_secs(5),
+            synchrony_bound: Duration::from_secs(5),
+            read_timeout: Duration::from_secs(5),
+            write_timeout: Duration::from_secs(5),
+            rekey_frequency: Duration::from_secs(60),
+            rekey_messages: 2,
+        }
+    }
+
+    fn chunk(content: &'static [u8]) -> wire::Message {
+        wire::Message {
+            payload: Some(wire::message::Payload::Chunk(wire::Chunk {
+                channel: 0,
+                part: 0,
+                total_parts: 1,
+                content: Bytes::from_static(content),
+            })),
+        }
+    }
+
+    fn content(msg: wire::Message) -> Bytes {
+        match msg.payload {
+            Some(wire::message::Payload::Chunk(chunk)) => chunk.content,
+            _ => panic!("unexpected message"),
         }
     }
+
+    /// Performs a handshake between two peers over an in-memory stream.
+    async fn connect(
+        context: deterministic::Context,
+    ) -> (
+        Stream<deterministic::Context, ed25519::Ed25519, DuplexStream>,
+        Stream<deterministic::Context, ed25519::Ed25519, DuplexStream>,
+    ) {
+        let (dialer_stream, listener_stream) = duplex(MAX_FRAME_LENGTH);
+        let dialer_config = config(0);
+        let listener_config = config(1);
+        let listener_public_key = listener_config.crypto.me();
+        let listener = context.spawn({
+            let context = context.clone();
+            async move {
+                let replays = Mutex::new(Replays::default());
+                let handshake = IncomingHandshake::verify(
+                    &context,
+                    &listener_config.crypto,
+                    MAX_FRAME_LENGTH,
+                    listener_config.handshake_timeout,
+                    listener_config.synchrony_bound,
+                    &replays,
+                    listener_stream,
+                )
+                .await
+                .unwrap();
+                Stream::upgrade_listener(context, listener_config, handshake)
+                    .await
+                    .unwrap()
+            }
+        });
+        let dialer =
+            Stream::upgrade_dialer(context, dialer_config, dialer_stream, listener_public_key)
+                .await
+                .unwrap();
+        (dialer, listener.await.unwrap())
+    }
+
+    #[test]
+    fn test_handshake() {
+        let (runner, context) = deterministic::Runner::init(deterministic::Config {
+            seed: 0,
+            timeout: Some(Duration::from_secs(60)),
+        });
+        runner.start(async move {
+            let (dialer, listener) = connect(context).await;
+
+            // Send messages in both directions (each of which uses a different key)
+            let (_, mut dialer_sender, mut dialer_receiver) = dialer.split();
+            let (_, mut listener_sender, mut listener_receiver) = listener.split();
+            dialer_sender.send(chunk(b"hello")).await.unwrap();
+            assert_eq!(content(listener_receiver.receive().await.unwrap()), "hello");
+            listener_sender.send(chunk(b"world")).await.unwrap();
+            assert_eq!(content(dialer_receiver.receive().await.unwrap()), "world");
+        });
+    }
+
+    #[test]
+    fn test_rekey() {
+        let (runner, context) = deterministic::Runner::init(deterministic::Config {
+            seed: 0,
+            timeout: Some(Duration::from_secs(600)),
+        });
+        runner.start(async move {
+            let (dialer, listener) = connect(context.clone()).await;
+            let (_, mut sender, _) = dialer.split();
+            let (_, _, mut receiver) = listener.split();
+
+            // Rekey after every 2 messages (the rekey message is not delivered)
+            for i in 0..5 {
+                sender.send(chunk(b"hello")).await.unwrap();
+                assert_eq!(content(receiver.receive().await.unwrap()), "hello");
+                assert_eq!(sender.ratchet.messages(), i % 2 + 1);
+                assert_eq!(receiver.ratchet.messages(), i % 2 + 1);
+            }
+
+            // Rekey after the rekey frequency elapses
+            context.sleep(Duration::from_secs(60)).await;
+            sender.send(chunk(b"world")).await.unwrap();
+            assert_eq!(content(receiver.receive().await.unwrap()), "world");
+            assert_eq!(sender.ratchet.messages(), 1);
+            assert_eq!(receiver.ratchet.messages(), 1);
+        });
+    }
+
+    #[test]
+    fn test_unbound_handshake() {
+        let (runner, context) = deterministic::Runner::init(deterministic::Config {
+            seed: 0,
+            timeout: Some(Duration::from_secs(60)),
+        });
+        runner.start(async move {
+            let (dialer_stream, listener_stream) = duplex(MAX_FRAME_LENGTH);
+            let listener_config = config(1);
+
+            // Listener responds with a (validly signed) handshake that does not bind the
+            // dialer's handshake (like one replayed from another connection)
+            context.spawn({
+                let context = context.clone();
+                async move {
+                    let mut framed = Framed::new(listener_stream, codec(MAX_FRAME_LENGTH));
+                    let _ = framed.next().await;
+                    let mut crypto = listener_config.crypto;
+                    let secret = x25519_dalek::EphemeralSecret::random_from_rng(context.clone());
+                    let msg = create_handshake(
+                        &mut crypto,
+                        &mut Transcript::new(),
+                        config(0).crypto.me(),
+                        x25519_dalek::PublicKey::from(&secret),
+                        timestamp(&context),
+                    );
+                    framed.send(msg).await.unwrap();
+                }
+            });
+            let result = Stream::upgrade_dialer(
+                context.clone(),
+                config(0),
+                dialer_stream,
+                config(1).crypto.me(),
+            )
+            .await;
+            assert!(matches!(result, Err(Error::InvalidSignature)));
+        });
+    }
+
+    /// Sends a handshake from `dialer` to `listener` with the provided timestamp (returning
+    /// the listener's side of the connection).
+    async fn send_handshake(
+        dialer: &mut ed25519::Ed25519,
+        listener: PublicKey,
+        ephemeral: x25519_dalek::PublicKey,
+        timestamp: u64,
+    ) -> DuplexStream {
+        let (dialer_stream, listener_stream) = duplex(MAX_FRAME_LENGTH);
+        let mut framed = Framed::new(dialer_stream, codec(MAX_FRAME_LENGTH));
+        let msg = create_handshake(
+            dialer,
+            &mut Transcript::new(),
+            listener,
+            ephemeral,
+            timestamp,
+        );
+        framed.send(msg).await.unwrap();
+        listener_stream
+    }
+
+    #[test]
+    fn test_stale_and_replayed_handshakes() {
+        let (runner, context) = deterministic::Runner::init(deterministic::Config {
+            seed: 0,
+            timeout: Some(Duration::from_secs(60)),
+        });
+        runner.start(async move {
+            let mut dialer = ed25519::insecure_signer(0);
+            let listener = config(1);
+            let replays = Mutex::new(Replays::default());
+            let secret = x25519_dalek::EphemeralSecret::random_from_rng(context.clone());
+            let ephemeral = x25519_dalek::PublicKey::from(&secret);
+
+            // Move past the start of time (so that we can send stale handshakes)
+            context.sleep(Duration::from_secs(10)).await;
+            let now = timestamp(&context);
+
+            // Handshakes outside of the synchrony bound are rejected
+            for timestamp in [now - 5_001, now + 5_001] {
+                let stream =
+                    send_handshake(&mut dialer, listener.crypto.me(), ephemeral, timestamp).await;
+                let result = IncomingHandshake::verify(
+                    &context,
+                    &listener.crypto,
+                    MAX_FRAME_LENGTH,
+                    listener.handshake_timeout,
+                    listener.synchrony_bound,
+                    &replays,
+                    stream,
+                )
+                .await;
+                assert!(matches!(result, Err(Error::InvalidTimestamp(t)) if t == timestamp));
+            }
+
+            // Handshakes within the synchrony bound are accepted once
+            for replayed in [false, true] {
+                let stream =
+                    send_handshake(&mut dialer, listener.crypto.me(), ephemeral, now).await;
+                let result = IncomingHandshake::verify(
+                    &context,
+                    &listener.crypto,
+                    MAX_FRAME_LENGTH,
+                    listener.handshake_timeout,
+                    listener.synchrony_bound,
+                    &replays,
+                    stream,
+                )
+                .await;
+                if replayed {
+                    assert!(matches!(result, Err(Error::ReplayedHandshake)));
+                } else {
+                    assert!(result.is_ok());
+                }
+            }
+        });
+    }
 }
@@ -1,6 +1,11 @@
 use chacha20poly1305::Nonce;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
 use tokio_util::codec::LengthDelimitedCodec;
 
+/// HMAC-SHA256.
+pub type HmacSha256 = Hmac<Sha256>;
+
 pub fn codec(max_frame_len: usize) -> LengthDelimitedCodec {
     LengthDelimitedCodec::builder()
         .length_field_type::<u32>()
@@ -8,15 +13,28 @@ pub fn codec(max_frame_len: usize) -> LengthDelimitedCodec {
         .new_codec()
 }
 
-pub fn nonce_bytes(nonce: u64, dialer: bool) -> Nonce {
+pub fn nonce_bytes(nonce: u64) -> Nonce {
     let mut nonce_bytes = Nonce::default();
     nonce_bytes[..8].copy_from_slice(&nonce.to_be_bytes());
-    if dialer {
-        nonce_bytes[0] = 1;
-    }
     nonce_bytes
 }
 
+/// Derives a 32-byte key from `ikm` (salted with `salt`) for the provided `info`.
+///
+/// This is HKDF-SHA256 (RFC 5869) with an output length of a single block.
+pub fn hkdf(salt: &[u8], ikm: &[u8], info: &[u8]) -> [u8; 32] {
+    // Extract
+    let mut extract = HmacSha256::new_from_slice(salt).expect("HMAC accepts any key length");
+    extract.update(ikm);
+    let prk = extract.finalize().into_bytes();
+
+    // Expand
+    let mut expand = HmacSha256::new_from_slice(&prk).expect("HMAC accepts any key length");
+    expand.update(info);
+    expand.update(&[1]);
+    expand.finalize().into_bytes().into()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -25,6 +43,19 @@ mod tests {
     use std::io::Cursor;
     use tokio_util::codec::Framed;
 
+    #[test]
+    fn test_hkdf() {
+        // RFC 5869 (Test Case 1), truncated to a single block
+        let ikm = [0x0b; 22];
+        let salt = hex::decode("000102030405060708090a0b0c").unwrap();
+        let info = hex::decode("f0f1f2f3f4f5f6f7f8f9").unwrap();
+        let okm = hkdf(&salt, &ikm, &info);
+        assert_eq!(
+            hex::encode(okm),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf"
+        );
+    }
+
     #[tokio::test]
     async fn test_codec_invalid_frame_len() {
         let max_frame_len = 10;
@@ -16,12 +16,15 @@
 //! # Features
 //!
 //! * No TLS, No X.509 Certificates, No Protocol Negotiation
-//! * ChaCha20-Poly1305 Stream Encryption
+//! * ChaCha20-Poly1305 Stream Encryption (With Automatic Rekeying)
 //! * Arbitrary Cryptographic Peer Identities
 //! * Automatic Peer Discovery Using Bit Vectors (Used as Ping/Pongs)
 //! * Multiplexing With Configurable Rate Limiting Per Channel and Send Prioritization
 //! * Emebdded Message Chunking
 //! * Metrics via Prometheus
+//! * Pluggable Transports (With a Simulated Network for Testing)
+//! * Optional QUIC Transport (With a Stream per Channel)
+//! * Deterministic Runtime (With Virtual Time and Replayable Executions)
 //!
 //! # Example
 //!
@@ -92,6 +95,8 @@ mod connection;
 mod ip;
 mod metrics;
 mod network;
+pub mod runtime;
+pub mod transport;
 mod wire {
     include!(concat!(env!("OUT_DIR"), "/wire.rs"));
 }
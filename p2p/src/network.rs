@@ -5,23 +5,28 @@ use crate::{
     channels::{self, Channels},
     config::Config,
     connection,
+    runtime::{self, Runtime},
+    transport::{Tcp, Transport},
 };
-use commonware_cryptography::Scheme;
+use commonware_cryptography::BatchScheme;
 use tracing::info;
 
 /// Instance of a commonware-p2p network.
-pub struct Network<C: Scheme> {
+pub struct Network<C: BatchScheme, T: Transport = Tcp, R: Runtime = runtime::tokio::Context> {
+    runtime: R,
     cfg: Config<C>,
+    transport: T,
 
     channels: Channels,
-    tracker: tracker::Actor<C>,
+    tracker: tracker::Actor<R, C>,
     tracker_mailbox: tracker::Mailbox,
     router: router::Actor,
     router_mailbox: router::Mailbox,
 }
 
-impl<C: Scheme> Network<C> {
-    /// Create a new instance of a commonware-p2p network.
+impl<C: BatchScheme> Network<C> {
+    /// Create a new instance of a commonware-p2p network (that communicates over TCP
+    /// and runs on `tokio`).
     ///
     /// # Parameters
     ///
@@ -32,17 +37,40 @@ impl<C: Scheme> Network<C> {
     /// * A tuple containing the network instance and the oracle that
     ///   can be used by a developer to configure which peers are authorized.
     pub fn new(cfg: Config<C>) -> (Self, tracker::Oracle) {
-        let (tracker, tracker_mailbox, oracle) = tracker::Actor::new(tracker::Config {
-            crypto: cfg.crypto.clone(),
-            registry: cfg.registry.clone(),
-            address: cfg.address,
-            bootstrappers: cfg.bootstrappers.clone(),
-            allow_private_ips: cfg.allow_private_ips,
-            mailbox_size: cfg.mailbox_size,
-            tracked_peer_sets: cfg.tracked_peer_sets,
-            allowed_connection_rate_per_peer: cfg.allowed_connection_rate_per_peer,
-            peer_gossip_max_count: cfg.peer_gossip_max_count,
-        });
+        let transport = Tcp::new(cfg.tcp_nodelay);
+        Self::with_runtime(runtime::tokio::Context::default(), cfg, transport)
+    }
+}
+
+impl<C: BatchScheme, T: Transport, R: Runtime> Network<C, T, R> {
+    /// Create a new instance of a commonware-p2p network that runs on the provided
+    /// runtime and communicates over the provided transport.
+    ///
+    /// # Parameters
+    ///
+    /// * `runtime` - Runtime used to spawn tasks, keep time, and generate randomness.
+    /// * `cfg` - Configuration for the network (`tcp_nodelay` is ignored).
+    /// * `transport` - Transport used to listen for and dial connections.
+    ///
+    /// # Returns
+    ///
+    /// * A tuple containing the network instance and the oracle that
+    ///   can be used by a developer to configure which peers are authorized.
+    pub fn with_runtime(runtime: R, cfg: Config<C>, transport: T) -> (Self, tracker::Oracle) {
+        let (tracker, tracker_mailbox, oracle) = tracker::Actor::new(
+            runtime.clone(),
+            tracker::Config {
+                crypto: cfg.crypto.clone(),
+                registry: cfg.registry.clone(),
+                address: cfg.address,
+                bootstrappers: cfg.bootstrappers.clone(),
+                allow_private_ips: cfg.allow_private_ips,
+                mailbox_size: cfg.mailbox_size,
+                tracked_peer_sets: cfg.tracked_peer_sets,
+                allowed_connection_rate_per_peer: cfg.allowed_connection_rate_per_peer,
+                peer_gossip_max_count: cfg.peer_gossip_max_count,
+            },
+        );
         let (router, router_mailbox, messenger) = router::Actor::new(router::Config {
             registry: cfg.registry.clone(),
             mailbox_size: cfg.mailbox_size,
@@ -50,7 +78,9 @@ impl<C: Scheme> Network<C> {
 
         (
             Self {
+                runtime,
                 cfg,
+                transport,
 
                 channels: Channels::new(messenger),
                 tracker,
@@ -90,51 +120,68 @@ impl<C: Scheme> Network<C> {
     /// After the network is started, it is not possible to add more channels.
     pub async fn run(self) {
         // Start tracker
-        let mut tracker_task = tokio::spawn(self.tracker.run());
+        let mut tracker_task = self.runtime.spawn(self.tracker.run());
 
         // Start router
-        let mut router_task = tokio::spawn(self.router.run(self.channels));
+        let mut router_task = self.runtime.spawn(self.router.run(self.channels));
 
         // Start spawner
-        let (spawner, spawner_mailbox) = spawner::Actor::new(spawner::Config {
-            registry: self.cfg.registry.clone(),
-            mailbox_size: self.cfg.mailbox_size,
-            gossip_bit_vec_frequency: self.cfg.gossip_bit_vec_frequency,
-            allowed_bit_vec_rate: self.cfg.allowed_bit_vec_rate,
-            allowed_peers_rate: self.cfg.allowed_peers_rate,
-        });
-        let mut spawner_task =
-            tokio::spawn(spawner.run(self.tracker_mailbox.clone(), self.router_mailbox));
+        let (spawner, spawner_mailbox) = spawner::Actor::<R, C, T::Stream>::new(
+            self.runtime.clone(),
+            spawner::Config {
+                registry: self.cfg.registry.clone(),
+                mailbox_size: self.cfg.mailbox_size,
+                gossip_bit_vec_frequency: self.cfg.gossip_bit_vec_frequency,
+                allowed_bit_vec_rate: self.cfg.allowed_bit_vec_rate,
+                allowed_peers_rate: self.cfg.allowed_peers_rate,
+            },
+        );
+        let mut spawner_task = self
+            .runtime
+            .spawn(spawner.run(self.tracker_mailbox.clone(), self.router_mailbox));
 
         // Start listener
         let connection = connection::Config {
             crypto: self.cfg.crypto,
             max_frame_length: self.cfg.max_frame_length,
             handshake_timeout: self.cfg.handshake_timeout,
+            synchrony_bound: self.cfg.synchrony_bound,
             read_timeout: self.cfg.read_timeout,
             write_timeout: self.cfg.write_timeout,
-            tcp_nodelay: self.cfg.tcp_nodelay,
+            rekey_frequency: self.cfg.rekey_frequency,
+            rekey_messages: self.cfg.rekey_messages,
         };
-        let listener = listener::Actor::new(listener::Config {
-            port: self.cfg.address.port(),
-            connection: connection.clone(),
-            allowed_incoming_connectioned_rate: self.cfg.allowed_incoming_connection_rate,
-        });
-        let mut listener_task =
-            tokio::spawn(listener.run(self.tracker_mailbox.clone(), spawner_mailbox.clone()));
+        let listener = listener::Actor::new(
+            self.runtime.clone(),
+            listener::Config {
+                port: self.cfg.address.port(),
+                transport: self.transport.clone(),
+                connection: connection.clone(),
+                allowed_incoming_connectioned_rate: self.cfg.allowed_incoming_connection_rate,
+            },
+        );
+        let mut listener_task = self
+            .runtime
+            .spawn(listener.run(self.tracker_mailbox.clone(), spawner_mailbox.clone()));
 
         // Start dialer
-        let dialer = dialer::Actor::new(dialer::Config {
-            registry: self.cfg.registry,
-            connection,
-            dial_frequency: self.cfg.dial_frequency,
-            dial_rate: self.cfg.dial_rate,
-        });
-        let mut dialer_task = tokio::spawn(dialer.run(self.tracker_mailbox, spawner_mailbox));
+        let dialer = dialer::Actor::new(
+            self.runtime.clone(),
+            dialer::Config {
+                registry: self.cfg.registry,
+                transport: self.transport,
+                connection,
+                dial_frequency: self.cfg.dial_frequency,
+                dial_rate: self.cfg.dial_rate,
+            },
+        );
+        let mut dialer_task = self
+            .runtime
+            .spawn(dialer.run(self.tracker_mailbox, spawner_mailbox));
 
         // Wait for actors
         info!("network started");
-        let err = tokio::try_join!(
+        let err = futures::try_join!(
             &mut tracker_task,
             &mut router_task,
             &mut spawner_task,
@@ -0,0 +1,450 @@
+//! Single-threaded runtime with virtual time and a seeded RNG.
+//!
+//! All tasks are executed on the thread that calls `Runner::start`. Whenever no task can make
+//! progress, time is advanced (instantly) to the earliest pending sleep. The order in which ready
+//! tasks are polled, and any randomness requested by tasks, is drawn from a RNG seeded with
+//! `Config::seed`. Given the same seed (and the same inputs), an execution can be replayed exactly.
+//!
+//! Virtual (monotonic) time starts at the `Instant` the runtime is initialized and does not
+//! advance unless all tasks are blocked. The virtual wall clock starts at `UNIX_EPOCH`.
+//!
+//! # Example
+//!
+//! ```rust
+//! use commonware_p2p::runtime::{deterministic, Clock, Spawner};
+//! use std::time::{Duration, UNIX_EPOCH};
+//!
+//! let (runner, context) = deterministic::Runner::init(deterministic::Config {
+//!     seed: 0,
+//!     timeout: None,
+//! });
+//! let elapsed = runner.start(async move {
+//!     let handle = context.spawn({
+//!         let context = context.clone();
+//!         async move {
+//!             context.sleep(Duration::from_secs(60)).await;
+//!             context.system_time()
+//!         }
+//!     });
+//!     handle.await.unwrap()
+//! });
+//! assert_eq!(elapsed, UNIX_EPOCH + Duration::from_secs(60));
+//! ```
+
+use super::{Clock, Handle, Spawner};
+use futures::task::{waker, ArcWake};
+use governor::clock::Clock as GovernorClock;
+use rand::{rngs::StdRng, seq::SliceRandom, CryptoRng, RngCore, SeedableRng};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BinaryHeap},
+    future::Future,
+    mem,
+    pin::{pin, Pin},
+    sync::{
+        atomic::{self, AtomicBool},
+        Arc, Mutex,
+    },
+    task::{self, Poll, Waker},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Configuration for the deterministic runtime.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Seed for the RNG used to order tasks (and provided to tasks).
+    pub seed: u64,
+
+    /// Maximum amount of (virtual) time the runtime can run before panicking.
+    pub timeout: Option<Duration>,
+}
+
+type Work = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+struct Task {
+    id: u64,
+    work: Mutex<Option<Work>>,
+    queued: AtomicBool,
+    ready: Arc<Mutex<Vec<Arc<Task>>>>,
+}
+
+impl ArcWake for Task {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        if !arc_self.queued.swap(true, atomic::Ordering::SeqCst) {
+            arc_self.ready.lock().unwrap().push(arc_self.clone());
+        }
+    }
+}
+
+struct Root {
+    woken: AtomicBool,
+}
+
+impl ArcWake for Root {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.woken.store(true, atomic::Ordering::SeqCst);
+    }
+}
+
+struct Alarm {
+    deadline: Instant,
+    id: u64,
+    waker: Waker,
+}
+
+impl PartialEq for Alarm {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Alarm {}
+
+impl PartialOrd for Alarm {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Alarm {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so that the earliest alarm is at the top of the heap
+        (other.deadline, other.id).cmp(&(self.deadline, self.id))
+    }
+}
+
+struct Executor {
+    timeout: Option<Instant>,
+    rng: Mutex<StdRng>,
+    start: Instant,
+    time: Mutex<Instant>,
+    counter: Mutex<u64>,
+
+    tasks: Mutex<BTreeMap<u64, Arc<Task>>>,
+    ready: Arc<Mutex<Vec<Arc<Task>>>>,
+    alarms: Mutex<BinaryHeap<Alarm>>,
+}
+
+impl Executor {
+    fn next_id(&self) -> u64 {
+        let mut counter = self.counter.lock().unwrap();
+        let id = *counter;
+        *counter += 1;
+        id
+    }
+
+    /// Polls all ready tasks (in a random order).
+    fn poll_ready(&self) {
+        let mut ready = mem::take(&mut *self.ready.lock().unwrap());
+        ready.shuffle(&mut *self.rng.lock().unwrap());
+        for task in ready {
+            task.queued.store(false, atomic::Ordering::SeqCst);
+            let mut work = task.work.lock().unwrap();
+            let Some(future) = work.as_mut() else {
+                continue;
+            };
+            let waker = waker(task.clone());
+            if future
+                .as_mut()
+                .poll(&mut task::Context::from_waker(&waker))
+                .is_ready()
+            {
+                *work = None;
+                self.tasks.lock().unwrap().remove(&task.id);
+            }
+        }
+    }
+
+    /// Advances time to the earliest alarm and wakes all tasks waiting on it.
+    fn advance(&self) {
+        let mut alarms = self.alarms.lock().unwrap();
+        let alarm = alarms.pop().expect("runtime stalled");
+        let mut time = self.time.lock().unwrap();
+        *time = (*time).max(alarm.deadline);
+        if let Some(timeout) = self.timeout {
+            assert!(*time <= timeout, "runtime timeout");
+        }
+        alarm.waker.wake();
+        while alarms.peek().is_some_and(|alarm| alarm.deadline <= *time) {
+            alarms.pop().unwrap().waker.wake();
+        }
+    }
+}
+
+/// Executor of tasks (on the current thread).
+pub struct Runner {
+    executor: Arc<Executor>,
+}
+
+impl Runner {
+    /// Creates a new deterministic runtime (and a context to interact with it).
+    pub fn init(cfg: Config) -> (Self, Context) {
+        let start = Instant::now();
+        let executor = Arc::new(Executor {
+            timeout: cfg.timeout.map(|timeout| start + timeout),
+            rng: Mutex::new(StdRng::seed_from_u64(cfg.seed)),
+            start,
+            time: Mutex::new(start),
+            counter: Mutex::new(0),
+            tasks: Mutex::new(BTreeMap::new()),
+            ready: Arc::new(Mutex::new(Vec::new())),
+            alarms: Mutex::new(BinaryHeap::new()),
+        });
+        (
+            Self {
+                executor: executor.clone(),
+            },
+            Context { executor },
+        )
+    }
+
+    /// Runs the provided future (and any task it spawns) until it completes.
+    ///
+    /// Any task still running when the future completes is dropped. If all tasks are
+    /// blocked (and none are sleeping) or the configured timeout is reached, this will
+    /// panic.
+    pub fn start<F: Future>(self, future: F) -> F::Output {
+        let root = Arc::new(Root {
+            woken: AtomicBool::new(true),
+        });
+        let root_waker = waker(root.clone());
+        let mut future = pin!(future);
+        let output = loop {
+            // Poll the root future (if it may make progress)
+            if root.woken.swap(false, atomic::Ordering::SeqCst) {
+                if let Poll::Ready(output) = future
+                    .as_mut()
+                    .poll(&mut task::Context::from_waker(&root_waker))
+                {
+                    break output;
+                }
+            }
+
+            // Poll spawned tasks
+            self.executor.poll_ready();
+
+            // If no task can make progress, skip ahead to the next alarm
+            if !root.woken.load(atomic::Ordering::SeqCst)
+                && self.executor.ready.lock().unwrap().is_empty()
+            {
+                self.executor.advance();
+            }
+        };
+
+        // Drop all remaining tasks (which may reference the executor)
+        let tasks = mem::take(&mut *self.executor.tasks.lock().unwrap());
+        for task in tasks.into_values() {
+            let work = task.work.lock().unwrap().take();
+            drop(work);
+        }
+        self.executor.ready.lock().unwrap().clear();
+        self.executor.alarms.lock().unwrap().clear();
+        output
+    }
+}
+
+/// Handle to the deterministic runtime.
+#[derive(Clone)]
+pub struct Context {
+    executor: Arc<Executor>,
+}
+
+impl Spawner for Context {
+    fn spawn<F, T>(&self, future: F) -> Handle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (future, handle) = Handle::init(future);
+        let task = Arc::new(Task {
+            id: self.executor.next_id(),
+            work: Mutex::new(Some(Box::pin(future))),
+            queued: AtomicBool::new(true),
+            ready: self.executor.ready.clone(),
+        });
+        self.executor
+            .tasks
+            .lock()
+            .unwrap()
+            .insert(task.id, task.clone());
+        self.executor.ready.lock().unwrap().push(task);
+        handle
+    }
+}
+
+struct Sleeper {
+    executor: Arc<Executor>,
+    deadline: Instant,
+    registered: bool,
+}
+
+impl Future for Sleeper {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<()> {
+        if *self.executor.time.lock().unwrap() >= self.deadline {
+            return Poll::Ready(());
+        }
+        if !self.registered {
+            self.registered = true;
+            let id = self.executor.next_id();
+            self.executor.alarms.lock().unwrap().push(Alarm {
+                deadline: self.deadline,
+                id,
+                waker: cx.waker().clone(),
+            });
+        }
+        Poll::Pending
+    }
+}
+
+impl GovernorClock for Context {
+    type Instant = Instant;
+
+    fn now(&self) -> Self::Instant {
+        self.current()
+    }
+}
+
+impl Clock for Context {
+    fn current(&self) -> Instant {
+        *self.executor.time.lock().unwrap()
+    }
+
+    fn system_time(&self) -> SystemTime {
+        UNIX_EPOCH + self.current().duration_since(self.executor.start)
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send + 'static {
+        self.sleep_until(self.current() + duration)
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> impl Future<Output = ()> + Send + 'static {
+        Sleeper {
+            executor: self.executor.clone(),
+            deadline,
+            registered: false,
+        }
+    }
+}
+
+impl RngCore for Context {
+    fn next_u32(&mut self) -> u32 {
+        self.executor.rng.lock().unwrap().next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.executor.rng.lock().unwrap().next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.executor.rng.lock().unwrap().fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.executor.rng.lock().unwrap().try_fill_bytes(dest)
+    }
+}
+
+impl CryptoRng for Context {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::{self, Error};
+    use rand::Rng;
+
+    fn runner(seed: u64) -> (Runner, Context) {
+        Runner::init(Config {
+            seed,
+            timeout: Some(Duration::from_secs(60)),
+        })
+    }
+
+    /// Spawns tasks that record the order in which they run (and some randomness).
+    fn trace(seed: u64) -> Vec<(u64, u64)> {
+        let (runner, context) = runner(seed);
+        runner.start(async move {
+            let trace = Arc::new(Mutex::new(Vec::new()));
+            let mut handles = Vec::new();
+            for i in 0..10u64 {
+                let mut context = context.clone();
+                let trace = trace.clone();
+                handles.push(context.clone().spawn(async move {
+                    for _ in 0..3 {
+                        let delay = context.gen_range(0..10);
+                        context.sleep(Duration::from_millis(delay)).await;
+                        trace.lock().unwrap().push((i, delay));
+                    }
+                }));
+            }
+            for handle in handles {
+                handle.await.unwrap();
+            }
+            let trace = trace.lock().unwrap().clone();
+            trace
+        })
+    }
+
+    #[test]
+    fn test_replay() {
+        assert_eq!(trace(0), trace(0));
+        assert_ne!(trace(0), trace(1));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let (runner, context) = runner(0);
+        runner.start(async move {
+            // Futures that complete in time return their output
+            let output = runtime::timeout(&context, Duration::from_secs(2), async {
+                context.sleep(Duration::from_secs(1)).await;
+                1
+            })
+            .await;
+            assert_eq!(output, Ok(1));
+
+            // Futures that don't are cancelled
+            let output = runtime::timeout(&context, Duration::from_secs(1), async {
+                context.sleep(Duration::from_secs(2)).await;
+                1
+            })
+            .await;
+            assert_eq!(output, Err(Error::Timeout));
+            assert_eq!(context.system_time(), UNIX_EPOCH + Duration::from_secs(2));
+        });
+    }
+
+    #[test]
+    fn test_abort() {
+        let (runner, context) = runner(0);
+        runner.start(async move {
+            let handle = context.spawn({
+                let context = context.clone();
+                async move {
+                    context.sleep(Duration::from_secs(10)).await;
+                }
+            });
+            handle.abort();
+            assert_eq!(handle.await, Err(Error::Closed));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "runtime stalled")]
+    fn test_stalled() {
+        let (runner, _) = runner(0);
+        runner.start(futures::future::pending::<()>());
+    }
+
+    #[test]
+    #[should_panic(expected = "runtime timeout")]
+    fn test_runtime_timeout() {
+        let (runner, context) = runner(0);
+        runner.start(async move {
+            loop {
+                context.sleep(Duration::from_secs(1)).await;
+            }
+        });
+    }
+}
@@ -0,0 +1,158 @@
+//! Execute asynchronous tasks against a configurable runtime.
+//!
+//! All actors in commonware-p2p spawn tasks, sleep, read the current time, and generate randomness
+//! through a `Runtime` (rather than calling `tokio` or `std` directly). This allows the same code to
+//! run on a multi-threaded production runtime (`tokio::Context`) or on a single-threaded,
+//! deterministic runtime with virtual time and a seeded RNG (`deterministic::Context`).
+//!
+//! When running on the deterministic runtime (over a `transport::simulated` network), any execution
+//! (including a failing one) can be replayed exactly by providing the same seed.
+
+use futures::{
+    channel::oneshot,
+    future::{AbortHandle, Abortable},
+};
+use governor::{
+    clock::Clock as GovernorClock,
+    middleware::NoOpMiddleware,
+    state::{keyed::HashMapStateStore, InMemoryState, NotKeyed},
+    RateLimiter,
+};
+use rand::{CryptoRng, RngCore};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant, SystemTime},
+};
+
+pub mod deterministic;
+pub mod tokio;
+
+/// Errors that can occur when interacting with a task.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    Closed,
+    Timeout,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::Closed => write!(f, "task closed"),
+            Error::Timeout => write!(f, "timeout"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Interface to spawn tasks.
+pub trait Spawner: Clone + Send + Sync + 'static {
+    /// Spawns a task that runs concurrently with the caller.
+    fn spawn<F, T>(&self, future: F) -> Handle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static;
+}
+
+/// Interface to read the current time and to sleep.
+///
+/// Sleeps and rate limiting are driven by a monotonic clock (`current`). The wall clock
+/// (`system_time`) should only be used for timestamps that are shared with peers.
+///
+/// Any implementation can also be used as the clock of a rate limiter.
+pub trait Clock: GovernorClock<Instant = Instant> + Clone + Send + Sync + 'static {
+    /// Returns the current (monotonic) time.
+    fn current(&self) -> Instant;
+
+    /// Returns the current wall-clock time.
+    fn system_time(&self) -> SystemTime;
+
+    /// Sleeps for the provided duration.
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send + 'static;
+
+    /// Sleeps until the provided time.
+    fn sleep_until(&self, deadline: Instant) -> impl Future<Output = ()> + Send + 'static;
+}
+
+/// Interface to spawn tasks, keep time, and generate randomness.
+pub trait Runtime: Spawner + Clock + RngCore + CryptoRng {}
+
+impl<R: Spawner + Clock + RngCore + CryptoRng> Runtime for R {}
+
+/// Handle to a spawned task.
+///
+/// Awaiting the handle returns the output of the task (or `Error::Closed` if the task was
+/// aborted or panicked). Dropping the handle does not abort the task.
+pub struct Handle<T> {
+    receiver: oneshot::Receiver<T>,
+    abort: AbortHandle,
+}
+
+impl<T: Send + 'static> Handle<T> {
+    /// Wraps a future so that its output is returned to (and it can be aborted by) the
+    /// returned handle.
+    pub(crate) fn init<F>(future: F) -> (impl Future<Output = ()> + Send + 'static, Self)
+    where
+        F: Future<Output = T> + Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let (abort, registration) = AbortHandle::new_pair();
+        let future = Abortable::new(
+            async move {
+                let _ = sender.send(future.await);
+            },
+            registration,
+        );
+        (
+            async move {
+                let _ = future.await;
+            },
+            Self { receiver, abort },
+        )
+    }
+
+    /// Aborts the task (the next time it yields).
+    pub fn abort(&self) {
+        self.abort.abort();
+    }
+}
+
+impl<T> Future for Handle<T> {
+    type Output = Result<T, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.receiver)
+            .poll(cx)
+            .map(|result| result.map_err(|_| Error::Closed))
+    }
+}
+
+/// Rate limiter that reads the current time from a `Clock`.
+pub type DirectRateLimiter<C> = RateLimiter<NotKeyed, InMemoryState, C, NoOpMiddleware<Instant>>;
+
+/// Rate limiter (keyed by `K`) that reads the current time from a `Clock`.
+pub type KeyedRateLimiter<K, C> = RateLimiter<K, HashMapStateStore<K>, C, NoOpMiddleware<Instant>>;
+
+/// Waits until the rate limiter allows a single cell through.
+pub async fn until_ready<C: Clock>(clock: &C, limiter: &DirectRateLimiter<C>) {
+    while let Err(not_until) = limiter.check() {
+        clock.sleep_until(not_until.earliest_possible()).await;
+    }
+}
+
+/// Waits for a future to complete (or returns `Error::Timeout` if it does not complete
+/// within the provided duration).
+pub async fn timeout<C: Clock, F: Future>(
+    clock: &C,
+    duration: Duration,
+    future: F,
+) -> Result<F::Output, Error> {
+    ::tokio::select! {
+        biased;
+
+        output = future => Ok(output),
+        _ = clock.sleep(duration) => Err(Error::Timeout),
+    }
+}
@@ -0,0 +1,76 @@
+//! Production runtime backed by `tokio`.
+//!
+//! Tasks are spawned on the `tokio` runtime of the caller (so all methods that spawn
+//! or sleep must be called from within a `tokio` runtime), sleeps are driven by the
+//! monotonic clock (timestamps are read from the system clock), and randomness is drawn
+//! from the operating system.
+
+use super::{Clock, Handle, Spawner};
+use governor::clock::Clock as GovernorClock;
+use rand::{rngs::OsRng, CryptoRng, RngCore};
+use std::{
+    future::Future,
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Handle to the `tokio` runtime.
+#[derive(Clone, Default)]
+pub struct Context {}
+
+impl Spawner for Context {
+    fn spawn<F, T>(&self, future: F) -> Handle<T>
+    where
+        F: Future<Output = T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (future, handle) = Handle::init(future);
+        tokio::spawn(future);
+        handle
+    }
+}
+
+impl GovernorClock for Context {
+    type Instant = Instant;
+
+    fn now(&self) -> Self::Instant {
+        self.current()
+    }
+}
+
+impl Clock for Context {
+    fn current(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn system_time(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send + 'static {
+        tokio::time::sleep(duration)
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> impl Future<Output = ()> + Send + 'static {
+        tokio::time::sleep_until(deadline.into())
+    }
+}
+
+impl RngCore for Context {
+    fn next_u32(&mut self) -> u32 {
+        OsRng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        OsRng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        OsRng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        OsRng.try_fill_bytes(dest)
+    }
+}
+
+impl CryptoRng for Context {}
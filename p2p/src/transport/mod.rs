@@ -0,0 +1,86 @@
+//! Transports over which connections are established.
+//!
+//! Connections are established by dialing (or accepting from) a `Transport` and are then
+//! upgraded into encrypted streams (using the cryptographic identity of each peer). Any
+//! reliable, ordered byte stream can be used as the underlying connection.
+//!
+//! By default, connections are established over TCP (`Tcp`). With the `quic` feature enabled,
+//! connections can instead be established over QUIC (`Quic`), which sends each channel over its
+//! own stream (so a large message on one channel does not delay messages on another). For
+//! testing, `simulated` provides an in-process network with configurable latency, jitter, packet
+//! loss, and partitions.
+
+use std::{convert::Infallible, future::Future, io, net::SocketAddr};
+use tokio::io::{AsyncRead, AsyncWrite, DuplexStream};
+
+#[cfg(feature = "quic")]
+mod quic;
+pub mod simulated;
+mod tcp;
+
+#[cfg(feature = "quic")]
+pub use quic::{Quic, QuicListener, QuicMux, QuicStream};
+pub use tcp::{Tcp, TcpListener};
+
+/// Reliable, ordered, bidirectional byte stream between two peers.
+pub trait Stream: AsyncRead + AsyncWrite + Unpin + Send + Sized + 'static {
+    /// Handle used to open (and accept) additional streams over the same connection.
+    ///
+    /// Transports that only support a single stream per connection use `Infallible`.
+    type Mux: Mux<Self>;
+
+    /// Returns a handle to open (and accept) additional streams over the same connection (or
+    /// `None` if the transport only supports a single stream per connection).
+    fn mux(&self) -> Option<Self::Mux> {
+        None
+    }
+}
+
+/// Opens (and accepts) additional streams over an established connection.
+pub trait Mux<S>: Clone + Send + Sync + 'static {
+    /// Opens a new stream to the peer.
+    ///
+    /// The peer is not notified of the stream until data is written to it.
+    fn open(&self) -> impl Future<Output = io::Result<S>> + Send;
+
+    /// Accepts the next stream opened by the peer.
+    fn accept(&self) -> impl Future<Output = io::Result<S>> + Send;
+}
+
+impl<S: Send + 'static> Mux<S> for Infallible {
+    async fn open(&self) -> io::Result<S> {
+        match *self {}
+    }
+
+    async fn accept(&self) -> io::Result<S> {
+        match *self {}
+    }
+}
+
+impl Stream for DuplexStream {
+    type Mux = Infallible;
+}
+
+/// Source of incoming connections.
+pub trait Listener: Send + 'static {
+    /// Stream produced for each accepted connection.
+    type Stream: Stream;
+
+    /// Accepts the next incoming connection (and returns the address of the dialer).
+    fn accept(&mut self) -> impl Future<Output = io::Result<(Self::Stream, SocketAddr)>> + Send;
+}
+
+/// Mechanism to listen for and dial connections.
+pub trait Transport: Clone + Send + Sync + 'static {
+    /// Stream produced by dialing (or accepting) a connection.
+    type Stream: Stream;
+
+    /// Listener produced by binding to an address.
+    type Listener: Listener<Stream = Self::Stream>;
+
+    /// Listens for incoming connections on the provided address.
+    fn bind(&self, address: SocketAddr) -> impl Future<Output = io::Result<Self::Listener>> + Send;
+
+    /// Dials the provided address.
+    fn dial(&self, address: SocketAddr) -> impl Future<Output = io::Result<Self::Stream>> + Send;
+}
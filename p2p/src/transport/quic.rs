@@ -0,0 +1,425 @@
+//! Transport that establishes connections over QUIC.
+//!
+//! Each connection starts with a single stream (over which the handshake is performed and peer
+//! discovery messages are exchanged) and then opens a dedicated stream for each channel, so a
+//! large (chunked) message on one channel does not delay messages on another (like it would when
+//! all channels share a single TCP stream).
+//!
+//! QUIC requires TLS, so each `Quic` transport generates a throwaway self-signed certificate and
+//! accepts any certificate presented by a peer. TLS is only used to encrypt the connection: peers
+//! are authenticated by the same handshake (using their cryptographic identity) that is performed
+//! over any other transport.
+
+use super::{Listener, Mux, Stream, Transport};
+use quinn::{
+    crypto::rustls::{QuicClientConfig, QuicServerConfig},
+    ClientConfig, Connection, Endpoint, RecvStream, SendStream, ServerConfig,
+};
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{self, CryptoProvider},
+    pki_types::{CertificateDer, PrivatePkcs8KeyDer, ServerName, UnixTime},
+    DigitallySignedStruct, SignatureScheme,
+};
+use std::{
+    io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    sync::mpsc,
+    task::JoinHandle,
+};
+use tracing::debug;
+
+/// Name of the server presented in (and expected by) every TLS handshake.
+const SERVER_NAME: &str = "commonware-p2p";
+
+/// Maximum number of accepted connections waiting to be returned by `QuicListener::accept`.
+const BACKLOG: usize = 128;
+
+/// Transport that establishes connections over QUIC.
+#[derive(Clone)]
+pub struct Quic {
+    client: ClientConfig,
+    server: ServerConfig,
+}
+
+impl Default for Quic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Quic {
+    /// Creates a new QUIC transport (with a newly generated self-signed certificate).
+    pub fn new() -> Self {
+        let provider = Arc::new(crypto::ring::default_provider());
+
+        // Generate a certificate for incoming connections
+        let certificate = rcgen::generate_simple_self_signed(vec![SERVER_NAME.to_string()])
+            .expect("failed to generate certificate");
+        let key = PrivatePkcs8KeyDer::from(certificate.key_pair.serialize_der());
+        let server = rustls::ServerConfig::builder_with_provider(provider.clone())
+            .with_protocol_versions(&[&rustls::version::TLS13])
+            .expect("TLS 1.3 is supported")
+            .with_no_client_auth()
+            .with_single_cert(vec![certificate.cert.der().clone()], key.into())
+            .expect("certificate is valid");
+        let server = ServerConfig::with_crypto(Arc::new(
+            QuicServerConfig::try_from(server).expect("TLS 1.3 is supported"),
+        ));
+
+        // Accept any certificate for outgoing connections
+        let client = rustls::ClientConfig::builder_with_provider(provider.clone())
+            .with_protocol_versions(&[&rustls::version::TLS13])
+            .expect("TLS 1.3 is supported")
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(AnyCertificate(provider)))
+            .with_no_client_auth();
+        let client = ClientConfig::new(Arc::new(
+            QuicClientConfig::try_from(client).expect("TLS 1.3 is supported"),
+        ));
+
+        Self { client, server }
+    }
+}
+
+impl Transport for Quic {
+    type Stream = QuicStream;
+    type Listener = QuicListener;
+
+    async fn bind(&self, address: SocketAddr) -> io::Result<Self::Listener> {
+        let endpoint = Endpoint::server(self.server.clone(), address)?;
+        let address = endpoint.local_addr()?;
+
+        // Wait for the first stream of each connection in the background (so that a slow
+        // dialer can't block other connections from being accepted)
+        let (sender, receiver) = mpsc::channel(BACKLOG);
+        let task = tokio::spawn(async move {
+            while let Some(incoming) = endpoint.accept().await {
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    let connection = match incoming.await {
+                        Ok(connection) => connection,
+                        Err(e) => {
+                            debug!(error = ?e, "failed to accept QUIC connection");
+                            return;
+                        }
+                    };
+                    let (send, receive) = match connection.accept_bi().await {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            debug!(error = ?e, "failed to accept QUIC stream");
+                            return;
+                        }
+                    };
+                    let address = connection.remote_address();
+                    let stream = QuicStream {
+                        connection,
+                        send,
+                        receive,
+                    };
+                    let _ = sender.send((stream, address)).await;
+                });
+            }
+        });
+        Ok(QuicListener {
+            address,
+            receiver,
+            task,
+        })
+    }
+
+    async fn dial(&self, address: SocketAddr) -> io::Result<Self::Stream> {
+        // Each outgoing connection is sent from its own (ephemeral) port
+        let local = match address {
+            SocketAddr::V4(_) => SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0),
+            SocketAddr::V6(_) => SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0),
+        };
+        let endpoint = Endpoint::client(local)?;
+        let connection = endpoint
+            .connect_with(self.client.clone(), address, SERVER_NAME)
+            .map_err(io::Error::other)?
+            .await?;
+        let (send, receive) = connection.open_bi().await?;
+        Ok(QuicStream {
+            connection,
+            send,
+            receive,
+        })
+    }
+}
+
+/// Listener for incoming QUIC connections.
+pub struct QuicListener {
+    address: SocketAddr,
+    receiver: mpsc::Receiver<(QuicStream, SocketAddr)>,
+    task: JoinHandle<()>,
+}
+
+impl QuicListener {
+    /// Returns the address the listener is bound to.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+}
+
+impl Listener for QuicListener {
+    type Stream = QuicStream;
+
+    async fn accept(&mut self) -> io::Result<(Self::Stream, SocketAddr)> {
+        self.receiver
+            .recv()
+            .await
+            .ok_or_else(|| io::Error::from(io::ErrorKind::BrokenPipe))
+    }
+}
+
+impl Drop for QuicListener {
+    fn drop(&mut self) {
+        // Stop accepting connections (without closing those already accepted)
+        self.task.abort();
+    }
+}
+
+/// Bidirectional stream over a QUIC connection.
+pub struct QuicStream {
+    connection: Connection,
+    send: SendStream,
+    receive: RecvStream,
+}
+
+impl Stream for QuicStream {
+    type Mux = QuicMux;
+
+    fn mux(&self) -> Option<Self::Mux> {
+        Some(QuicMux {
+            connection: self.connection.clone(),
+        })
+    }
+}
+
+impl AsyncRead for QuicStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        AsyncRead::poll_read(Pin::new(&mut self.receive), cx, buf)
+    }
+}
+
+impl AsyncWrite for QuicStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        AsyncWrite::poll_write(Pin::new(&mut self.send), cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        AsyncWrite::poll_flush(Pin::new(&mut self.send), cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        AsyncWrite::poll_shutdown(Pin::new(&mut self.send), cx)
+    }
+}
+
+/// Opens (and accepts) additional streams over a QUIC connection.
+#[derive(Clone)]
+pub struct QuicMux {
+    connection: Connection,
+}
+
+impl Mux<QuicStream> for QuicMux {
+    async fn open(&self) -> io::Result<QuicStream> {
+        let (send, receive) = self.connection.open_bi().await?;
+        Ok(QuicStream {
+            connection: self.connection.clone(),
+            send,
+            receive,
+        })
+    }
+
+    async fn accept(&self) -> io::Result<QuicStream> {
+        let (send, receive) = self.connection.accept_bi().await?;
+        Ok(QuicStream {
+            connection: self.connection.clone(),
+            send,
+            receive,
+        })
+    }
+}
+
+/// Verifier that accepts any certificate (as long as the peer proves it holds the
+/// certificate's key).
+#[derive(Debug)]
+struct AnyCertificate(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for AnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        runtime::{tokio::Context, Spawner},
+        Config as NetworkConfig, Network as P2P,
+    };
+    use bytes::Bytes;
+    use commonware_cryptography::{ed25519, Scheme};
+    use governor::Quota;
+    use prometheus_client::registry::Registry;
+    use std::{net::IpAddr, num::NonZeroU32, sync::Mutex, time::Duration};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    async fn exchange(sender: &mut QuicStream, receiver: &mut QuicStream, msg: &[u8]) {
+        sender.write_all(msg).await.unwrap();
+        let mut buf = vec![0u8; msg.len()];
+        receiver.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, msg);
+    }
+
+    #[tokio::test]
+    async fn test_streams() {
+        let transport = Quic::new();
+        let mut listener = transport
+            .bind(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0))
+            .await
+            .unwrap();
+
+        // The connection is accepted once the dialer writes to its first stream
+        let mut dialer = transport.dial(listener.address()).await.unwrap();
+        dialer.write_all(b"hello").await.unwrap();
+        let (mut accepted, address) = listener.accept().await.unwrap();
+        assert_eq!(address.ip(), IpAddr::from(Ipv4Addr::LOCALHOST));
+        let mut buf = [0u8; 5];
+        accepted.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"hello");
+        exchange(&mut accepted, &mut dialer, b"world").await;
+
+        // Either side can open additional streams over the same connection
+        let dialer_mux = dialer.mux().unwrap();
+        let accepted_mux = accepted.mux().unwrap();
+        let mut opened = dialer_mux.open().await.unwrap();
+        opened.write_all(b"first").await.unwrap();
+        let mut incoming = accepted_mux.accept().await.unwrap();
+        let mut buf = [0u8; 5];
+        incoming.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"first");
+        exchange(&mut incoming, &mut opened, b"reply").await;
+
+        let mut opened = accepted_mux.open().await.unwrap();
+        opened.write_all(b"second").await.unwrap();
+        let mut incoming = dialer_mux.accept().await.unwrap();
+        let mut buf = [0u8; 6];
+        incoming.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"second");
+    }
+
+    #[tokio::test]
+    async fn test_network() {
+        // Create peers
+        let signers = (0..2).map(ed25519::insecure_signer).collect::<Vec<_>>();
+        let peers = signers.iter().map(|s| s.me()).collect::<Vec<_>>();
+        let addresses =
+            [43_101, 43_102].map(|port| SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port));
+        let bootstrappers = vec![(peers[0].clone(), addresses[0])];
+
+        // Start peers (with a small channel and a channel for messages that must be chunked)
+        let mut channels = Vec::new();
+        for (i, signer) in signers.into_iter().enumerate() {
+            let config = NetworkConfig::aggressive(
+                signer,
+                Arc::new(Mutex::new(Registry::default())),
+                addresses[i],
+                bootstrappers.clone(),
+            );
+            let max_frame_length = config.max_frame_length;
+            let (mut p2p, oracle) = P2P::with_runtime(Context::default(), config, Quic::new());
+            oracle.register(0, peers.clone()).await;
+            let quota = Quota::per_second(NonZeroU32::new(100).unwrap());
+            let small = p2p.register(0, quota, 1024, 128);
+            let large = p2p.register(1, quota, 4 * max_frame_length, 128);
+            Context::default().spawn(p2p.run());
+            channels.push((small, large));
+        }
+        let ((_, mut small_receiver), (_, mut large_receiver)) = channels.remove(0);
+        let ((small_sender, _), (large_sender, _)) = channels.remove(0);
+
+        // Send messages over both channels until they are received
+        let large = Bytes::from(vec![7u8; 3 * 1024 * 1024]);
+        let recipients = Some(vec![peers[0].clone()]);
+        let result = tokio::time::timeout(Duration::from_secs(30), async {
+            let (mut small_received, mut large_received) = (false, false);
+            while !small_received || !large_received {
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_millis(100)) => {
+                        large_sender.send(recipients.clone(), large.clone(), false).await;
+                        small_sender.send(recipients.clone(), Bytes::from_static(b"small"), true).await;
+                    }
+                    Some((peer, message)) = small_receiver.recv() => {
+                        assert_eq!(peer, peers[1]);
+                        assert_eq!(message, b"small"[..]);
+                        small_received = true;
+                    }
+                    Some((peer, message)) = large_receiver.recv() => {
+                        assert_eq!(peer, peers[1]);
+                        assert_eq!(message, large);
+                        large_received = true;
+                    }
+                }
+            }
+        })
+        .await;
+        assert!(result.is_ok(), "messages were not received");
+    }
+}
@@ -0,0 +1,888 @@
+//! In-process network with configurable latency, jitter, packet loss, and partitions.
+//!
+//! Each participant is assigned an `Endpoint` (identified by an IP address) that implements
+//! `Transport`. Endpoints can listen on any port of their IP address and dial any other endpoint
+//! registered on the same `Network` (without touching the operating system's network stack).
+//!
+//! Each write to a `Connection` is treated as a single packet. A packet sent over a link is delivered
+//! after the link's latency (plus up to the link's jitter). A packet that is lost is retransmitted
+//! (after `Config::retransmit`) until it is delivered, so connections remain reliable and ordered
+//! (like TCP). Dials (which require a round trip) are subject to the same conditions.
+//!
+//! Endpoints that are partitioned from each other cannot dial each other. Writing to an established
+//! connection between them resets the connection (rather than dropping the packet and leaving a gap
+//! in the stream once the partition is healed): the write fails with `BrokenPipe`, the peer reads
+//! EOF (after any packets already in flight), and all subsequent writes on either side fail.
+//!
+//! All randomness (jitter and loss) is drawn from a RNG seeded with `Config::seed` and all delays are
+//! measured with the provided `Clock`, so network conditions are reproducible when the sequence of
+//! operations performed on the network is deterministic (i.e. when running on the `deterministic`
+//! runtime).
+//!
+//! # Example
+//!
+//! ```rust
+//! use commonware_p2p::{
+//!     runtime::deterministic,
+//!     transport::{simulated, Listener, Transport},
+//! };
+//! use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+//! use std::time::Duration;
+//! use tokio::io::{AsyncReadExt, AsyncWriteExt};
+//!
+//! let (runner, context) = deterministic::Runner::init(deterministic::Config {
+//!     seed: 0,
+//!     timeout: None,
+//! });
+//! runner.start(async move {
+//!     // Create network
+//!     let network = simulated::Network::new(context, simulated::Config {
+//!         link: simulated::Link {
+//!             latency: Duration::from_millis(10),
+//!             jitter: Duration::from_millis(5),
+//!             loss: 0.01,
+//!         },
+//!         retransmit: Duration::from_millis(200),
+//!         seed: 0,
+//!     });
+//!
+//!     // Create endpoints
+//!     let a = network.endpoint(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+//!     let b = network.endpoint(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)));
+//!
+//!     // Connect endpoints
+//!     let mut listener = a.bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 3000)).await.unwrap();
+//!     let mut dialer = b.dial(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 3000)).await.unwrap();
+//!     let (mut accepted, _) = listener.accept().await.unwrap();
+//!
+//!     // Send data
+//!     dialer.write_all(b"hello").await.unwrap();
+//!     let mut buf = [0u8; 5];
+//!     accepted.read_exact(&mut buf).await.unwrap();
+//!     assert_eq!(&buf, b"hello");
+//! });
+//! ```
+
+use super::{Listener, Stream, Transport};
+use crate::runtime::Clock;
+use bytes::{Buf, Bytes};
+use futures::ready;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    future::Future,
+    io,
+    net::{IpAddr, SocketAddr},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    sync::mpsc,
+};
+
+/// First port assigned to outgoing connections.
+const EPHEMERAL_PORT_START: u16 = 49152;
+
+/// Conditions of a (directional) link between two endpoints.
+#[derive(Clone, Debug)]
+pub struct Link {
+    /// Minimum time it takes for a packet to be delivered.
+    pub latency: Duration,
+
+    /// Maximum additional (uniformly random) time it takes for a packet to be delivered.
+    pub jitter: Duration,
+
+    /// Probability that a packet is lost (and must be retransmitted).
+    ///
+    /// Must be in `[0, 1)`.
+    pub loss: f64,
+}
+
+/// Configuration for a simulated network.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Conditions of any link that is not explicitly configured.
+    pub link: Link,
+
+    /// Time after which a lost packet is retransmitted.
+    pub retransmit: Duration,
+
+    /// Seed for the RNG used to sample jitter and loss.
+    pub seed: u64,
+}
+
+struct State<R: Clock> {
+    link: Link,
+    retransmit: Duration,
+    rng: StdRng,
+
+    links: HashMap<(IpAddr, IpAddr), Link>,
+    partitions: Option<HashMap<IpAddr, usize>>,
+    listeners: HashMap<SocketAddr, mpsc::UnboundedSender<(Connection<R>, SocketAddr)>>,
+    ports: HashMap<IpAddr, u16>,
+}
+
+impl<R: Clock> State<R> {
+    /// Returns whether packets can be sent between two endpoints.
+    fn reachable(&self, from: IpAddr, to: IpAddr) -> bool {
+        match &self.partitions {
+            Some(partitions) => partitions.get(&from) == partitions.get(&to),
+            None => true,
+        }
+    }
+
+    /// Samples the time it takes to deliver a packet (or `None` if it can't be delivered).
+    fn delay(&mut self, from: IpAddr, to: IpAddr) -> Option<Duration> {
+        if !self.reachable(from, to) {
+            return None;
+        }
+        let link = self.links.get(&(from, to)).unwrap_or(&self.link);
+        let (latency, jitter, loss) = (link.latency, link.jitter, link.loss);
+        let mut delay = latency;
+        if !jitter.is_zero() {
+            delay += jitter.mul_f64(self.rng.gen::<f64>());
+        }
+        while loss > 0.0 && self.rng.gen_bool(loss) {
+            delay += self.retransmit;
+        }
+        Some(delay)
+    }
+
+    /// Assigns an unused (ephemeral) port to an endpoint.
+    fn port(&mut self, ip: IpAddr) -> u16 {
+        let next = self.ports.entry(ip).or_insert(EPHEMERAL_PORT_START);
+        loop {
+            let port = *next;
+            *next = next.checked_add(1).unwrap_or(EPHEMERAL_PORT_START);
+            if !self.listeners.contains_key(&SocketAddr::new(ip, port)) {
+                return port;
+            }
+        }
+    }
+}
+
+fn validate(link: &Link) {
+    assert!(
+        (0.0..1.0).contains(&link.loss),
+        "loss must be in [0, 1): {}",
+        link.loss
+    );
+}
+
+/// Simulated network shared by a set of endpoints.
+#[derive(Clone)]
+pub struct Network<R: Clock> {
+    runtime: R,
+    state: Arc<Mutex<State<R>>>,
+}
+
+impl<R: Clock> Network<R> {
+    /// Creates a new simulated network (that measures delays with the provided clock).
+    ///
+    /// If `cfg.link.loss` is not in `[0, 1)`, this will panic.
+    pub fn new(runtime: R, cfg: Config) -> Self {
+        validate(&cfg.link);
+        Self {
+            runtime,
+            state: Arc::new(Mutex::new(State {
+                link: cfg.link,
+                retransmit: cfg.retransmit,
+                rng: StdRng::seed_from_u64(cfg.seed),
+                links: HashMap::new(),
+                partitions: None,
+                listeners: HashMap::new(),
+                ports: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Returns the endpoint with the provided IP address.
+    pub fn endpoint(&self, ip: IpAddr) -> Endpoint<R> {
+        Endpoint {
+            ip,
+            runtime: self.runtime.clone(),
+            state: self.state.clone(),
+        }
+    }
+
+    /// Overrides the conditions of the link from one endpoint to another.
+    ///
+    /// If `link.loss` is not in `[0, 1)`, this will panic.
+    pub fn link(&self, from: IpAddr, to: IpAddr, link: Link) {
+        validate(&link);
+        self.state.lock().unwrap().links.insert((from, to), link);
+    }
+
+    /// Partitions the network into the provided groups of endpoints.
+    ///
+    /// Endpoints can only communicate with endpoints in the same group (any endpoint
+    /// not in any group is placed in an implicit group with all other such endpoints). Any
+    /// previous partition is replaced.
+    pub fn partition(&self, groups: Vec<Vec<IpAddr>>) {
+        let mut partitions = HashMap::new();
+        for (group, ips) in groups.into_iter().enumerate() {
+            for ip in ips {
+                partitions.insert(ip, group);
+            }
+        }
+        self.state.lock().unwrap().partitions = Some(partitions);
+    }
+
+    /// Removes any partition.
+    pub fn heal(&self) {
+        self.state.lock().unwrap().partitions = None;
+    }
+}
+
+/// Participant in a simulated network.
+#[derive(Clone)]
+pub struct Endpoint<R: Clock> {
+    ip: IpAddr,
+    runtime: R,
+    state: Arc<Mutex<State<R>>>,
+}
+
+impl<R: Clock> Endpoint<R> {
+    /// Returns the IP address of the endpoint.
+    pub fn ip(&self) -> IpAddr {
+        self.ip
+    }
+}
+
+impl<R: Clock> Transport for Endpoint<R> {
+    type Stream = Connection<R>;
+    type Listener = Binding<R>;
+
+    async fn bind(&self, address: SocketAddr) -> io::Result<Self::Listener> {
+        // Endpoints can only listen on their own IP
+        if !address.ip().is_unspecified() && address.ip() != self.ip {
+            return Err(io::ErrorKind::AddrNotAvailable.into());
+        }
+
+        // Register listener
+        let mut state = self.state.lock().unwrap();
+        let port = match address.port() {
+            0 => state.port(self.ip),
+            port => port,
+        };
+        let address = SocketAddr::new(self.ip, port);
+        if state.listeners.contains_key(&address) {
+            return Err(io::ErrorKind::AddrInUse.into());
+        }
+        let (sender, receiver) = mpsc::unbounded_channel();
+        state.listeners.insert(address, sender.clone());
+        Ok(Binding {
+            address,
+            state: self.state.clone(),
+            sender,
+            receiver,
+        })
+    }
+
+    async fn dial(&self, address: SocketAddr) -> io::Result<Self::Stream> {
+        // Establishing a connection requires a round trip
+        let (delay, listener, local) = {
+            let mut state = self.state.lock().unwrap();
+            let there = state.delay(self.ip, address.ip());
+            let back = state.delay(address.ip(), self.ip);
+            let delay = match (there, back) {
+                (Some(there), Some(back)) => there + back,
+                _ => return Err(io::ErrorKind::TimedOut.into()),
+            };
+            let listener = state
+                .listeners
+                .get(&address)
+                .cloned()
+                .ok_or(io::ErrorKind::ConnectionRefused)?;
+            let local = SocketAddr::new(self.ip, state.port(self.ip));
+            (delay, listener, local)
+        };
+        self.runtime.sleep(delay).await;
+
+        // Deliver connection to listener
+        let (dialer, listener_stream) =
+            Connection::pair(self.runtime.clone(), self.state.clone(), local, address);
+        listener
+            .send((listener_stream, local))
+            .map_err(|_| io::ErrorKind::ConnectionRefused)?;
+        Ok(dialer)
+    }
+}
+
+/// Listener bound to an address of an endpoint.
+pub struct Binding<R: Clock> {
+    address: SocketAddr,
+    state: Arc<Mutex<State<R>>>,
+    sender: mpsc::UnboundedSender<(Connection<R>, SocketAddr)>,
+    receiver: mpsc::UnboundedReceiver<(Connection<R>, SocketAddr)>,
+}
+
+impl<R: Clock> Binding<R> {
+    /// Returns the address the listener is bound to.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+}
+
+impl<R: Clock> Listener for Binding<R> {
+    type Stream = Connection<R>;
+
+    async fn accept(&mut self) -> io::Result<(Self::Stream, SocketAddr)> {
+        // We hold a sender, so the channel is never closed
+        Ok(self.receiver.recv().await.unwrap())
+    }
+}
+
+impl<R: Clock> Drop for Binding<R> {
+    fn drop(&mut self) {
+        // Only remove the listener if it has not been replaced
+        let mut state = self.state.lock().unwrap();
+        if let Some(sender) = state.listeners.get(&self.address) {
+            if sender.same_channel(&self.sender) {
+                state.listeners.remove(&self.address);
+            }
+        }
+    }
+}
+
+/// Pending delivery of a packet.
+type Delivery = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Connection between two endpoints.
+pub struct Connection<R: Clock> {
+    local: IpAddr,
+    remote: IpAddr,
+    runtime: R,
+    state: Arc<Mutex<State<R>>>,
+
+    sender: Option<mpsc::UnboundedSender<(Instant, Bytes)>>,
+    last: Instant,
+
+    receiver: mpsc::UnboundedReceiver<(Instant, Bytes)>,
+    pending: Option<(Delivery, Bytes)>,
+    buffer: Bytes,
+}
+
+impl<R: Clock> Connection<R> {
+    fn pair(runtime: R, state: Arc<Mutex<State<R>>>, a: SocketAddr, b: SocketAddr) -> (Self, Self) {
+        let (a_sender, b_receiver) = mpsc::unbounded_channel();
+        let (b_sender, a_receiver) = mpsc::unbounded_channel();
+        let now = runtime.current();
+        (
+            Self {
+                local: a.ip(),
+                remote: b.ip(),
+                runtime: runtime.clone(),
+                state: state.clone(),
+                sender: Some(a_sender),
+                last: now,
+                receiver: a_receiver,
+                pending: None,
+                buffer: Bytes::new(),
+            },
+            Self {
+                local: b.ip(),
+                remote: a.ip(),
+                runtime,
+                state,
+                sender: Some(b_sender),
+                last: now,
+                receiver: b_receiver,
+                pending: None,
+                buffer: Bytes::new(),
+            },
+        )
+    }
+}
+
+impl<R: Clock> Connection<R> {
+    /// Closes both directions of the connection.
+    fn reset(&mut self) {
+        self.sender = None;
+        self.receiver.close();
+    }
+}
+
+// Fields of a connection are never pinned
+impl<R: Clock> Unpin for Connection<R> {}
+
+impl<R: Clock> Stream for Connection<R> {
+    type Mux = Infallible;
+}
+
+impl<R: Clock> AsyncRead for Connection<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            // Return any delivered data
+            if !this.buffer.is_empty() {
+                let len = this.buffer.len().min(buf.remaining());
+                buf.put_slice(&this.buffer[..len]);
+                this.buffer.advance(len);
+                return Poll::Ready(Ok(()));
+            }
+
+            // Wait for the next packet to be delivered
+            if let Some((sleep, _)) = &mut this.pending {
+                ready!(sleep.as_mut().poll(cx));
+                this.buffer = this.pending.take().unwrap().1;
+                continue;
+            }
+
+            // Wait for the next packet to be sent (if the channel is closed, we've reached EOF)
+            match ready!(this.receiver.poll_recv(cx)) {
+                Some((deadline, data)) => {
+                    this.pending = Some((Box::pin(this.runtime.sleep_until(deadline)), data));
+                }
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+}
+
+impl<R: Clock> AsyncWrite for Connection<R> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.sender.is_none() {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+
+        // Packets can't be sent between partitioned endpoints (so the connection is reset)
+        let delay = this.state.lock().unwrap().delay(this.local, this.remote);
+        let Some(delay) = delay else {
+            this.reset();
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        };
+
+        // Packets are delivered in order
+        let deadline = (this.runtime.current() + delay).max(this.last);
+        this.last = deadline;
+        let sender = this.sender.as_ref().unwrap();
+        if sender
+            .send((deadline, Bytes::copy_from_slice(buf)))
+            .is_err()
+        {
+            this.reset();
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().sender = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        runtime::{deterministic, Spawner},
+        Config as NetworkConfig, Network as P2P,
+    };
+    use commonware_cryptography::{ed25519, secp256r1, BatchScheme};
+    use governor::Quota;
+    use prometheus_client::registry::Registry;
+    use std::{collections::BTreeSet, net::Ipv4Addr, num::NonZeroU32, time::SystemTime};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn ip(i: u16) -> IpAddr {
+        IpAddr::V4(Ipv4Addr::new(10, 0, (i >> 8) as u8, i as u8))
+    }
+
+    fn link(latency: u64, jitter: u64, loss: f64) -> Link {
+        Link {
+            latency: Duration::from_millis(latency),
+            jitter: Duration::from_millis(jitter),
+            loss,
+        }
+    }
+
+    fn runner(seed: u64) -> (deterministic::Runner, deterministic::Context) {
+        deterministic::Runner::init(deterministic::Config {
+            seed,
+            timeout: Some(Duration::from_secs(3_600)),
+        })
+    }
+
+    fn network(context: &deterministic::Context, link: Link) -> Network<deterministic::Context> {
+        Network::new(
+            context.clone(),
+            Config {
+                link,
+                retransmit: Duration::from_millis(200),
+                seed: 0,
+            },
+        )
+    }
+
+    async fn connect<R: Clock>(network: &Network<R>, port: u16) -> (Connection<R>, Connection<R>) {
+        let mut listener = network
+            .endpoint(ip(1))
+            .bind(SocketAddr::new(ip(1), port))
+            .await
+            .unwrap();
+        let dialer = network
+            .endpoint(ip(2))
+            .dial(SocketAddr::new(ip(1), port))
+            .await
+            .unwrap();
+        let (accepted, address) = listener.accept().await.unwrap();
+        assert_eq!(address.ip(), ip(2));
+        (dialer, accepted)
+    }
+
+    fn elapsed(context: &deterministic::Context, start: Instant) -> Duration {
+        context.current().duration_since(start)
+    }
+
+    #[test]
+    fn test_latency() {
+        let (runner, context) = runner(0);
+        runner.start(async move {
+            let network = network(&context, link(50, 0, 0.0));
+
+            // Dialing requires a round trip
+            let start = context.current();
+            let (mut dialer, mut accepted) = connect(&network, 3000).await;
+            assert_eq!(elapsed(&context, start), Duration::from_millis(100));
+
+            // Each packet is delayed by the latency of the link
+            let start = context.current();
+            dialer.write_all(b"hello").await.unwrap();
+            dialer.write_all(b"world").await.unwrap();
+            let mut buf = [0u8; 10];
+            accepted.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"helloworld");
+            assert_eq!(elapsed(&context, start), Duration::from_millis(50));
+
+            // Links can be configured per direction
+            network.link(ip(1), ip(2), link(10, 0, 0.0));
+            let start = context.current();
+            accepted.write_all(b"back").await.unwrap();
+            let mut buf = [0u8; 4];
+            dialer.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"back");
+            assert_eq!(elapsed(&context, start), Duration::from_millis(10));
+
+            // Closing a connection is observed by the peer
+            dialer.shutdown().await.unwrap();
+            assert_eq!(accepted.read(&mut buf).await.unwrap(), 0);
+            drop(accepted);
+            assert!(dialer.write_all(b"closed").await.is_err());
+        });
+    }
+
+    #[test]
+    fn test_jitter_and_loss_preserve_order() {
+        let (runner, context) = runner(0);
+        runner.start(async move {
+            let network = network(&context, link(10, 50, 0.3));
+            let (mut dialer, mut accepted) = connect(&network, 3000).await;
+
+            // Send many packets (some of which are lost and retransmitted)
+            let start = context.current();
+            for i in 0..=255u8 {
+                dialer.write_all(&[i]).await.unwrap();
+            }
+
+            // All packets are delivered in order
+            let mut buf = [0u8; 256];
+            accepted.read_exact(&mut buf).await.unwrap();
+            for (i, byte) in buf.iter().enumerate() {
+                assert_eq!(*byte as usize, i);
+            }
+
+            // Lost packets are delayed by retransmissions
+            assert!(elapsed(&context, start) >= Duration::from_millis(200));
+        });
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let mut deliveries = Vec::new();
+        for _ in 0..2 {
+            let (runner, context) = runner(0);
+            deliveries.push(runner.start(async move {
+                let network = network(&context, link(10, 50, 0.3));
+                let (mut dialer, mut accepted) = connect(&network, 3000).await;
+                let start = context.current();
+                let mut deliveries = Vec::new();
+                for i in 0..32u8 {
+                    dialer.write_all(&[i]).await.unwrap();
+                    let mut buf = [0u8; 1];
+                    accepted.read_exact(&mut buf).await.unwrap();
+                    deliveries.push(elapsed(&context, start));
+                }
+                deliveries
+            }));
+        }
+        assert_eq!(deliveries[0], deliveries[1]);
+    }
+
+    #[test]
+    fn test_bind_and_dial_errors() {
+        let (runner, context) = runner(0);
+        runner.start(async move {
+            let network = network(&context, link(10, 0, 0.0));
+            let endpoint = network.endpoint(ip(1));
+
+            // Dialing an address without a listener fails
+            let err = endpoint
+                .dial(SocketAddr::new(ip(2), 3000))
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+
+            // Endpoints can only bind to their own address
+            let err = endpoint
+                .bind(SocketAddr::new(ip(2), 3000))
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::AddrNotAvailable);
+
+            // Addresses can only be bound once
+            let listener = endpoint
+                .bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 3000))
+                .await
+                .unwrap();
+            assert_eq!(listener.address(), SocketAddr::new(ip(1), 3000));
+            let err = endpoint
+                .bind(SocketAddr::new(ip(1), 3000))
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+
+            // Dropping a listener releases its address
+            drop(listener);
+            let err = network
+                .endpoint(ip(2))
+                .dial(SocketAddr::new(ip(1), 3000))
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+            endpoint.bind(SocketAddr::new(ip(1), 3000)).await.unwrap();
+        });
+    }
+
+    #[test]
+    fn test_partition() {
+        let (runner, context) = runner(0);
+        runner.start(async move {
+            let network = network(&context, link(10, 0, 0.0));
+            let (mut dialer, mut accepted) = connect(&network, 3000).await;
+
+            // Partitioned endpoints can't dial each other
+            network.partition(vec![vec![ip(1)], vec![ip(2)]]);
+            let err = network
+                .endpoint(ip(2))
+                .dial(SocketAddr::new(ip(1), 3000))
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+            // Endpoints in the same group (or in no group) can still communicate
+            let mut listener = network
+                .endpoint(ip(3))
+                .bind(SocketAddr::new(ip(3), 3000))
+                .await
+                .unwrap();
+            network
+                .endpoint(ip(4))
+                .dial(SocketAddr::new(ip(3), 3000))
+                .await
+                .unwrap();
+            listener.accept().await.unwrap();
+
+            // Writing across the partition resets the connection
+            let err = dialer.write_all(b"lost").await.err().unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+            let mut buf = [0u8; 5];
+            assert_eq!(accepted.read(&mut buf).await.unwrap(), 0);
+
+            // Once healed, endpoints can dial each other again
+            network.heal();
+            let (mut dialer, mut accepted) = connect(&network, 3001).await;
+            dialer.write_all(b"found").await.unwrap();
+            accepted.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"found");
+        });
+    }
+
+    #[test]
+    fn test_partition_heal_mid_stream() {
+        let (runner, context) = runner(0);
+        runner.start(async move {
+            let network = network(&context, link(10, 0, 0.0));
+            let (mut dialer, mut accepted) = connect(&network, 3000).await;
+
+            // Packets in flight when the partition starts are still delivered
+            dialer.write_all(b"hello").await.unwrap();
+            network.partition(vec![vec![ip(1)], vec![ip(2)]]);
+            let err = dialer.write_all(b"lost").await.err().unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+            network.heal();
+
+            // The stream ends (without a gap) after the last packet sent before the partition
+            let mut buf = Vec::new();
+            accepted.read_to_end(&mut buf).await.unwrap();
+            assert_eq!(buf, b"hello");
+
+            // Neither side can write after healing
+            let err = dialer.write_all(b"world").await.err().unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+            let err = accepted.write_all(b"world").await.err().unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+            assert_eq!(dialer.read(&mut [0u8; 1]).await.unwrap(), 0);
+        });
+    }
+
+    /// Runs `n` peers (signing with `insecure_signer`) on a simulated network (with the provided
+    /// seed) until each peer has heard from all other peers, returning the (virtual) time at which
+    /// each peer finished.
+    fn run_network<C: BatchScheme>(
+        insecure_signer: fn(u16) -> C,
+        n: u16,
+        seed: u64,
+    ) -> Vec<(usize, SystemTime)> {
+        let (runner, context) = runner(seed);
+        runner.start(async move {
+            // Create simulated network
+            let network = Network::new(
+                context.clone(),
+                Config {
+                    link: link(5, 5, 0.01),
+                    retransmit: Duration::from_millis(50),
+                    seed,
+                },
+            );
+
+            // Create peers
+            let signers = (0..n).map(insecure_signer).collect::<Vec<_>>();
+            let peers = signers.iter().map(|s| s.me()).collect::<Vec<_>>();
+            let addresses = (0..n)
+                .map(|i| SocketAddr::new(ip(i), 3000))
+                .collect::<Vec<_>>();
+            let bootstrappers = vec![(peers[0].clone(), addresses[0])];
+
+            // Start peers
+            let (done_sender, mut done_receiver) = mpsc::unbounded_channel();
+            for (i, signer) in signers.into_iter().enumerate() {
+                // Configure network
+                let mut config = NetworkConfig::aggressive(
+                    signer.clone(),
+                    Arc::new(Mutex::new(Registry::default())),
+                    addresses[i],
+                    bootstrappers.clone(),
+                );
+                config.dial_frequency = Duration::from_secs(1);
+                config.dial_rate = Quota::per_second(NonZeroU32::new(1_000).unwrap());
+                let (mut p2p, oracle) =
+                    P2P::with_runtime(context.clone(), config, network.endpoint(ip(i as u16)));
+                oracle.register(0, peers.clone()).await;
+                let (sender, mut receiver) = p2p.register(
+                    0,
+                    Quota::per_second(NonZeroU32::new(100).unwrap()),
+                    1024,
+                    128,
+                );
+                context.spawn(p2p.run());
+
+                // Ping all peers we haven't heard from (and respond to any ping) until we've
+                // heard from all of them
+                let me = signer.me();
+                let mut pending = peers
+                    .iter()
+                    .filter(|p| **p != me)
+                    .cloned()
+                    .collect::<BTreeSet<_>>();
+                let done_sender = done_sender.clone();
+                let context = context.clone();
+                context.clone().spawn(async move {
+                    let mut next_ping = context.current();
+                    loop {
+                        tokio::select! {
+                            biased;
+
+                            _ = context.sleep_until(next_ping) => {
+                                next_ping = context.current() + Duration::from_secs(1);
+                                if pending.is_empty() {
+                                    continue;
+                                }
+                                let recipients = pending.iter().cloned().collect();
+                                sender.send(Some(recipients), Bytes::from_static(b"ping"), false).await;
+                            }
+                            Some((peer, message)) = receiver.recv() => {
+                                if message == b"ping"[..] {
+                                    sender.send(Some(vec![peer.clone()]), Bytes::from_static(b"pong"), false).await;
+                                }
+                                if pending.remove(&peer) && pending.is_empty() {
+                                    done_sender.send((i, context.system_time())).unwrap();
+                                }
+                            }
+                        }
+                    }
+                });
+            }
+
+            // Wait for all peers to hear from all other peers
+            let mut finished = Vec::new();
+            for _ in 0..n {
+                finished.push(done_receiver.recv().await.unwrap());
+            }
+            finished
+        })
+    }
+
+    #[test]
+    fn test_network() {
+        let finished = run_network(ed25519::insecure_signer, 25, 0);
+        assert_eq!(finished.len(), 25);
+    }
+
+    #[test]
+    fn test_network_secp256r1() {
+        let finished = run_network(secp256r1::insecure_signer, 25, 0);
+        assert_eq!(finished.len(), 25);
+    }
+
+    #[test]
+    fn test_network_large() {
+        let finished = run_network(ed25519::insecure_signer, 100, 0);
+        assert_eq!(finished.len(), 100);
+    }
+
+    #[test]
+    fn test_network_replay() {
+        // Executions with the same seed are identical
+        let first = run_network(ed25519::insecure_signer, 10, 42);
+        let second = run_network(ed25519::insecure_signer, 10, 42);
+        assert_eq!(first, second);
+
+        // Executions with different seeds are not
+        let third = run_network(ed25519::insecure_signer, 10, 43);
+        assert_ne!(first, third);
+    }
+}
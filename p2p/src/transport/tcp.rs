@@ -0,0 +1,65 @@
+use super::{Listener, Stream, Transport};
+use std::{convert::Infallible, io, net::SocketAddr};
+use tokio::net::{self, TcpStream};
+use tracing::debug;
+
+/// Transport that establishes connections over TCP.
+#[derive(Clone, Default)]
+pub struct Tcp {
+    nodelay: Option<bool>,
+}
+
+impl Tcp {
+    /// Creates a new TCP transport.
+    ///
+    /// If `nodelay` is provided, `TCP_NODELAY` is set to its value on each connection.
+    pub fn new(nodelay: Option<bool>) -> Self {
+        Self { nodelay }
+    }
+
+    fn configure(&self, stream: &TcpStream, address: SocketAddr) {
+        if let Some(nodelay) = self.nodelay {
+            if let Err(e) = stream.set_nodelay(nodelay) {
+                debug!(ip = ?address.ip(), port = ?address.port(), error = ?e, "failed to set TCP_NODELAY")
+            }
+        }
+    }
+}
+
+impl Transport for Tcp {
+    type Stream = TcpStream;
+    type Listener = TcpListener;
+
+    async fn bind(&self, address: SocketAddr) -> io::Result<Self::Listener> {
+        Ok(TcpListener {
+            transport: self.clone(),
+            listener: net::TcpListener::bind(address).await?,
+        })
+    }
+
+    async fn dial(&self, address: SocketAddr) -> io::Result<Self::Stream> {
+        let stream = TcpStream::connect(address).await?;
+        self.configure(&stream, address);
+        Ok(stream)
+    }
+}
+
+impl Stream for TcpStream {
+    type Mux = Infallible;
+}
+
+/// Listener for incoming TCP connections.
+pub struct TcpListener {
+    transport: Tcp,
+    listener: net::TcpListener,
+}
+
+impl Listener for TcpListener {
+    type Stream = TcpStream;
+
+    async fn accept(&mut self) -> io::Result<(Self::Stream, SocketAddr)> {
+        let (stream, address) = self.listener.accept().await?;
+        self.transport.configure(&stream, address);
+        Ok((stream, address))
+    }
+}